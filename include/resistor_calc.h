@@ -0,0 +1,59 @@
+/*
+ * C interface to resistor-calc, built with the `capi` feature.
+ *
+ * A problem is created with rc_problem_new, given series and bounds, and solved with
+ * rc_problem_solve to produce a results handle. Both handles must be released with their free
+ * function. Functions that can fail return a negative value or NULL, and rc_last_error describes
+ * the most recent failure on the calling thread.
+ */
+
+#ifndef RESISTOR_CALC_H
+#define RESISTOR_CALC_H
+
+#include <stddef.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+typedef struct RcProblem RcProblem;
+typedef struct RcResults RcResults;
+
+/* The most recent error on the calling thread, or NULL. Valid until the next failing call. */
+const char *rc_last_error(void);
+
+/* Creates an empty problem, to be released with rc_problem_free. */
+RcProblem *rc_problem_new(void);
+
+/* Adds a position drawn from a named series such as "E24". Returns 0, or -1 on error. */
+int rc_problem_add_series(RcProblem *problem, const char *name);
+
+/* Adds a bound such as "R2 / (R1 + R2) ~ 0.25". Returns 0, or -1 on error. */
+int rc_problem_add_bound(RcProblem *problem, const char *expr);
+
+/* Declares a named constant for the bounds. Returns 0, or -1 on error. */
+int rc_problem_add_constant(RcProblem *problem, const char *name, double value);
+
+/* Solves the problem. Returns results to be released with rc_results_free, or NULL if the
+ * bounds cannot be evaluated or nothing satisfies them, as described by rc_last_error. */
+RcResults *rc_problem_solve(const RcProblem *problem);
+
+void rc_problem_free(RcProblem *problem);
+
+/* The number of results, ordered from lowest to highest error. */
+size_t rc_results_len(const RcResults *results);
+
+/* The error of the i'th result, or a negative value if there is no such result. */
+double rc_results_error(const RcResults *results, size_t i);
+
+/* Copies up to len values of R1, R2, ..., Rn of the i'th result into out, returning the number
+ * of values in the result, or 0 if there is no such result. */
+size_t rc_results_values(const RcResults *results, size_t i, double *out, size_t len);
+
+void rc_results_free(RcResults *results);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* RESISTOR_CALC_H */
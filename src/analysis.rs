@@ -0,0 +1,680 @@
+use itertools::Itertools;
+use rand::{self, Rng};
+
+use std::cmp::Ordering;
+use std::f64::consts::PI;
+
+use {Error, ROp, RRes, RSet};
+
+/// The range of values taken by the expression of a single bound as the values of a result are
+/// varied, produced by the analyses on `RRes`.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub struct BoundRange {
+    /// The source expression of the bound.
+    pub bound: String,
+    /// The value of the expression at the nominal resistor values.
+    pub nominal: f64,
+    /// The lowest value of the expression seen.
+    pub min: f64,
+    /// The highest value of the expression seen.
+    pub max: f64,
+    /// Whether the bound held for every variation. Always true for `~` bounds.
+    pub holds: bool,
+    /// The worst deviation seen. For `~` bounds this is the largest error from the target, for
+    /// other bounds it is the furthest the expression strayed past the target, or zero if it held.
+    pub deviation: f64,
+}
+
+/// The outcome of a single bound for a candidate set of values, produced by `ROp::explain`.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub struct BoundOutcome {
+    /// The source expression of the bound.
+    pub bound: String,
+    /// The value of the expression for the candidate.
+    pub value: f64,
+    /// Whether this is a `~` bound.
+    pub soft: bool,
+    /// Whether the bound held. Always true for `~` bounds.
+    pub holds: bool,
+    /// For `~` bounds the error from the target, for other bounds how far the expression is past
+    /// the target, or zero if it held.
+    pub deviation: f64,
+}
+
+impl ROp {
+    /// Reports the outcome of every bound for a candidate set of values, showing which
+    /// comparison bounds reject it and by how much, along with the error of each `~` bound.
+    /// # Example
+    /// ```
+    ///     # use resistor_calc::*;
+    ///     let op = ROpBuilder::new()
+    ///         .bound("R1 + R2 >= 1e4")
+    ///         .bound("0.8 * (1 + R1/R2) ~ 6.0")
+    ///         .build();
+    ///     let outcomes = op.explain(&RSet::new(&[6.8e3, 1e3]));
+    ///     assert!(!outcomes[0].holds);
+    ///     assert_eq!(outcomes[0].deviation, 2.2e3);
+    /// ```
+    pub fn explain(&self, rs: &RSet) -> Vec<BoundOutcome> {
+        self.try_explain(rs).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// As with `explain`, returning an error rather than panicking if a bound cannot be
+    /// evaluated, such as one naming a resistor that `rs` does not have.
+    /// # Example
+    /// ```
+    ///     # use resistor_calc::*;
+    ///     let op = ROpBuilder::new().bound("R3 ~ 1").build();
+    ///     assert!(matches!(op.try_explain(&RSet::new(&[1e3, 2e3])), Err(Error::Eval(_))));
+    /// ```
+    pub fn try_explain(&self, rs: &RSet) -> Result<Vec<BoundOutcome>, Error> {
+        Ok(self
+            .try_quantities(&rs.0)?
+            .into_iter()
+            .enumerate()
+            .map(|(idx, value)| {
+                let (holds, deviation) = self.outcome(idx, value);
+                BoundOutcome {
+                    bound: self.bounds()[idx].clone(),
+                    value,
+                    soft: self.is_soft(idx),
+                    holds,
+                    deviation,
+                }
+            })
+            .collect())
+    }
+
+    /// Evaluates every bound at the nominal values and across each of the `variants`, collecting
+    /// the range of values seen for each bound.
+    pub(crate) fn ranges<I>(&self, nominal: &[f64], variants: I) -> Vec<BoundRange>
+    where
+        I: IntoIterator<Item = Vec<f64>>,
+    {
+        let mut ranges: Vec<BoundRange> = self
+            .quantities(nominal)
+            .into_iter()
+            .enumerate()
+            .map(|(idx, val)| {
+                let (holds, deviation) = self.outcome(idx, val);
+                BoundRange {
+                    bound: self.bounds()[idx].clone(),
+                    nominal: val,
+                    min: val,
+                    max: val,
+                    holds,
+                    deviation,
+                }
+            })
+            .collect();
+        for values in variants {
+            for (idx, (range, val)) in ranges.iter_mut().zip(self.quantities(&values)).enumerate() {
+                let (holds, deviation) = self.outcome(idx, val);
+                range.min = range.min.min(val);
+                range.max = range.max.max(val);
+                range.holds &= holds;
+                range.deviation = range.deviation.max(deviation);
+            }
+        }
+        ranges
+    }
+}
+
+impl ROp {
+    /// Numerically estimates the partial derivative of every bound's expression with respect to
+    /// every value, indexed as `[bound][value]`.
+    pub(crate) fn gradients(&self, values: &[f64]) -> Vec<Vec<f64>> {
+        let mut grads = vec![Vec::with_capacity(values.len()); self.bounds().len()];
+        let mut probe = values.to_vec();
+        for (i, v) in values.iter().enumerate() {
+            let h = v * 1e-6;
+            probe[i] = v + h;
+            let hi = self.quantities(&probe);
+            probe[i] = v - h;
+            let lo = self.quantities(&probe);
+            probe[i] = *v;
+            for (grad, (hi, lo)) in grads.iter_mut().zip(hi.into_iter().zip(lo)) {
+                grad.push((hi - lo) / (2.0 * h));
+            }
+        }
+        grads
+    }
+}
+
+impl RRes {
+    /// Checks that the bounds of `op` can be evaluated for these results, so that none of the
+    /// analyses of the results with `op` can panic. The analyses only vary the values of the
+    /// resistors, so bounds that can be evaluated for one result can be evaluated for every
+    /// variation of every result. Results found with `op` itself, or with `RCalc::try_calc`,
+    /// need no check.
+    /// # Example
+    /// ```
+    ///     # use resistor_calc::*;
+    ///     let res = RCalc::e24(2)
+    ///         .calc(ROpBuilder::new().bound("R2 / (R1 + R2) ~ 0.25").finish())
+    ///         .unwrap();
+    ///     let op = ROpBuilder::new().bound("R2 / (R1 + R2 + R3) ~ 0.25").build();
+    ///     assert!(matches!(res.validate(&op), Err(Error::Eval(_))));
+    /// ```
+    pub fn validate(&self, op: &ROp) -> Result<(), Error> {
+        match self.res.first() {
+            Some((_, rs)) => op.try_quantities(&rs.0).map(|_| ()),
+            None => Ok(()),
+        }
+    }
+
+    /// Re-evaluates every result against `op` without searching again, typically after changing
+    /// a constant with `ROp::set_constant`. Results that no longer satisfy the bounds are dropped
+    /// and the remainder are re-sorted by their new error, returning `None` if none remain.
+    pub fn reevaluate(&self, op: &ROp) -> Option<RRes> {
+        let mut res: Vec<(u64, RSet)> = self
+            .res
+            .iter()
+            .filter_map(|(_, rs)| {
+                op.eval(rs)
+                    .map(|err| ((err * 1e9).round() as u64, rs.clone()))
+            })
+            .collect();
+        res.sort_by_key(|(err, _rs)| *err);
+        if !res.is_empty() {
+            Some(RRes {
+                res,
+                pos: self.pos.clone(),
+                partial: self.partial,
+            })
+        } else {
+            None
+        }
+    }
+}
+
+/// Produces every combination of each value scaled to the bottom and top of its spread, 2^n of
+/// them for n values.
+pub(crate) fn corners(values: &[f64], spread: &[f64]) -> Vec<Vec<f64>> {
+    if values.is_empty() {
+        return vec![Vec::new()];
+    }
+    values
+        .iter()
+        .zip(spread)
+        .map(|(v, s)| vec![v * (1.0 - s), v * (1.0 + s)])
+        .multi_cartesian_product()
+        .collect()
+}
+
+impl RRes {
+    /// Worst-case tolerance analysis of the `i`th result. Every bound of `op` is evaluated with
+    /// each resistor at both ends of the tolerance of its series, giving the guaranteed rather
+    /// than the nominal accuracy of the result.
+    /// # Example
+    /// ```
+    ///     # use resistor_calc::*;
+    ///     let op = ROpBuilder::new().bound("R1 / (R1 + R2) ~ 0.5").build();
+    ///     let res = RCalc::e24(2).calc(|rs| op.eval(rs)).unwrap();
+    ///     for range in res.worst_case(0, &op) {
+    ///         println!("{}: {} to {}", range.bound, range.min, range.max);
+    ///     }
+    /// ```
+    pub fn worst_case(&self, i: usize, op: &ROp) -> Vec<BoundRange> {
+        let values = &(self.res[i].1).0;
+        let tols: Vec<f64> = self.pos.iter().map(|p| p.tolerance).collect();
+        op.ranges(values, corners(values, &tols))
+    }
+}
+
+impl RRes {
+    /// Temperature drift analysis of the `i`th result. Every bound of `op` is evaluated at both
+    /// `t_min` and `t_max` in °C, with each resistor drifting in either direction from its value
+    /// at 25 °C by the temperature coefficient of its series.
+    /// # Example
+    /// ```
+    ///     # use resistor_calc::*;
+    ///     let series = E24.clone().with_tempco(100.0);
+    ///     let op = ROpBuilder::new().bound("0.8 * (1 + R1/R2) ~ 5.0").build();
+    ///     let res = RCalc::new(vec![&series, &series]).calc(|rs| op.eval(rs)).unwrap();
+    ///     for range in res.temperature_drift(0, &op, -40.0, 85.0) {
+    ///         println!("{}: {} to {}", range.bound, range.min, range.max);
+    ///     }
+    /// ```
+    pub fn temperature_drift(&self, i: usize, op: &ROp, t_min: f64, t_max: f64) -> Vec<BoundRange> {
+        let values = &(self.res[i].1).0;
+        let variants = [t_min, t_max]
+            .iter()
+            .flat_map(|t| {
+                let spread: Vec<f64> = self
+                    .pos
+                    .iter()
+                    .map(|p| p.tempco * 1e-6 * (t - 25.0).abs())
+                    .collect();
+                corners(values, &spread)
+            })
+            .collect::<Vec<_>>();
+        op.ranges(values, variants)
+    }
+}
+
+impl RRes {
+    /// Ratio-tracking worst-case analysis of the `i`th result. Resistors drawn from the same
+    /// series are assumed to track each other, such as parts from a matched array or the same
+    /// reel, so they all shift together across the tolerance of the series, with each part only
+    /// varying independently by the fractional `mismatch`. Compare with `worst_case` which treats
+    /// every part as independent.
+    /// # Example
+    /// ```
+    ///     # use resistor_calc::*;
+    ///     let op = ROpBuilder::new().bound("R2 / (R1 + R2) ~ 0.25").build();
+    ///     let res = RCalc::e24(2).calc(|rs| op.eval(rs)).unwrap();
+    ///     let tracking = res.tracking_worst_case(0, &op, 0.001);
+    ///     let independent = res.worst_case(0, &op);
+    ///     assert!(tracking[0].deviation < independent[0].deviation);
+    ///     // Custom series of different tolerances do not track each other.
+    ///     let precise = RSeries::new(&[1.0]).with_tolerance(0.001);
+    ///     let loose = RSeries::new(&[3.0]).with_tolerance(0.05);
+    ///     let res = RCalc::new(vec![&loose, &precise]).calc(|rs| op.eval(rs)).unwrap();
+    ///     let tracking = res.tracking_worst_case(0, &op, 0.0);
+    ///     assert_eq!(tracking[0].deviation, res.worst_case(0, &op)[0].deviation);
+    /// ```
+    pub fn tracking_worst_case(&self, i: usize, op: &ROp, mismatch: f64) -> Vec<BoundRange> {
+        let values = &(self.res[i].1).0;
+        // Parts of the same series track each other. Series are told apart by their tolerance
+        // as well as their name, as every series from `RSeries::new` is named "Custom".
+        let mut groups: Vec<(&str, f64)> = Vec::new();
+        let group_of: Vec<usize> = self
+            .pos
+            .iter()
+            .map(|p| {
+                let key = (p.series.as_str(), p.tolerance);
+                groups.iter().position(|&g| g == key).unwrap_or_else(|| {
+                    groups.push(key);
+                    groups.len() - 1
+                })
+            })
+            .collect();
+        let group_tols: Vec<f64> = groups.iter().map(|&(_, tol)| tol).collect();
+        let ones = vec![1.0; groups.len()];
+        let mismatches = vec![mismatch; values.len()];
+        let variants = corners(&ones, &group_tols)
+            .into_iter()
+            .flat_map(|shift| {
+                let shifted: Vec<f64> = values
+                    .iter()
+                    .zip(&group_of)
+                    .map(|(v, &g)| v * shift[g])
+                    .collect();
+                corners(&shifted, &mismatches)
+            })
+            .collect::<Vec<_>>();
+        op.ranges(values, variants)
+    }
+}
+
+impl RRes {
+    /// End-of-life worst-case analysis of the `i`th result. As with `worst_case`, but with the
+    /// long-term drift of each series added on top of its tolerance.
+    /// # Example
+    /// ```
+    ///     # use resistor_calc::*;
+    ///     let aged = E24.clone().with_drift(0.01);
+    ///     let op = ROpBuilder::new().bound("R2 / (R1 + R2) ~ 0.25").build();
+    ///     let res = RCalc::new(vec![&aged, &aged]).calc(|rs| op.eval(rs)).unwrap();
+    ///     for range in res.end_of_life(0, &op) {
+    ///         println!("{}: {} to {}", range.bound, range.min, range.max);
+    ///     }
+    /// ```
+    pub fn end_of_life(&self, i: usize, op: &ROp) -> Vec<BoundRange> {
+        let values = &(self.res[i].1).0;
+        let spread: Vec<f64> = self.pos.iter().map(|p| p.tolerance + p.drift).collect();
+        op.ranges(values, corners(values, &spread))
+    }
+}
+
+impl RRes {
+    /// Sweeps named constants of `op` over ranges for the `i`th result and reports the range of
+    /// each bound's expression. Each entry of `params` gives a constant declared with
+    /// `ROpBuilder::constant` and its minimum and maximum, which is sampled at `steps` evenly
+    /// spaced points. When several constants are given every combination of their points is
+    /// evaluated. The nominal values of the results are those at the declared constants. Returns
+    /// an error if a name is not that of a declared constant.
+    /// # Example
+    /// ```
+    ///     # use resistor_calc::*;
+    ///     let op = ROpBuilder::new()
+    ///         .constant("VIN", 12.0)
+    ///         .bound("VIN * R2 / (R1 + R2) ~ 3.0")
+    ///         .build();
+    ///     let res = RCalc::e24(2).calc(|rs| op.eval(rs)).unwrap();
+    ///     let sweep = res.sweep(0, &op, &[("VIN", 10.8, 13.2)], 5).unwrap();
+    ///     assert!(sweep[0].max > sweep[0].min);
+    ///     assert!(res.sweep(0, &op, &[("VCC", 10.8, 13.2)], 5).is_err());
+    /// ```
+    pub fn sweep(
+        &self,
+        i: usize,
+        op: &ROp,
+        params: &[(&str, f64, f64)],
+        steps: usize,
+    ) -> Result<Vec<BoundRange>, Error> {
+        for &(name, _, _) in params {
+            op.constant_index(name)?;
+        }
+        let values = &(self.res[i].1).0;
+        let mut ranges = op.ranges(values, None);
+        let points = params.iter().map(|&(_, min, max)| {
+            (0..steps).map(move |s| {
+                if steps > 1 {
+                    min + (max - min) * (s as f64) / ((steps - 1) as f64)
+                } else {
+                    min
+                }
+            })
+        });
+        for point in points.multi_cartesian_product() {
+            let mut swept = op.clone();
+            for (&(name, _, _), v) in params.iter().zip(point) {
+                swept.set_constant(name, v)?;
+            }
+            for (range, swept) in ranges.iter_mut().zip(swept.ranges(values, None)) {
+                range.min = range.min.min(swept.nominal);
+                range.max = range.max.max(swept.nominal);
+                range.holds &= swept.holds;
+                range.deviation = range.deviation.max(swept.deviation);
+            }
+        }
+        Ok(ranges)
+    }
+}
+
+/// The statistical spread of a single bound's expression, produced by `RRes::rss`.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub struct BoundSigma {
+    /// The source expression of the bound.
+    pub bound: String,
+    /// The value of the expression at the nominal resistor values.
+    pub nominal: f64,
+    /// The estimated standard deviation of the expression.
+    pub sigma: f64,
+}
+
+impl RRes {
+    /// Root-sum-square tolerance analysis of the `i`th result. Each resistor is treated as
+    /// independently normally distributed with its series tolerance spanning `sigmas` standard
+    /// deviations, and the resulting standard deviation of each bound's expression is estimated
+    /// from its sensitivity to each resistor.
+    /// # Example
+    /// ```
+    ///     # use resistor_calc::*;
+    ///     let op = ROpBuilder::new().bound("R1 / (R1 + R2) ~ 0.5").build();
+    ///     let res = RCalc::e24(2).calc(|rs| op.eval(rs)).unwrap();
+    ///     for b in res.rss(0, &op, 3.0) {
+    ///         println!("{}: {} ± {}", b.bound, b.nominal, 3.0 * b.sigma);
+    ///     }
+    /// ```
+    pub fn rss(&self, i: usize, op: &ROp, sigmas: f64) -> Vec<BoundSigma> {
+        let values = &(self.res[i].1).0;
+        op.gradients(values)
+            .into_iter()
+            .zip(op.quantities(values))
+            .zip(op.bounds())
+            .map(|((grad, nominal), bound)| BoundSigma {
+                bound: bound.clone(),
+                nominal,
+                sigma: grad
+                    .iter()
+                    .zip(values.iter().zip(self.pos.iter()))
+                    .map(|(g, (v, p))| (g * v * p.tolerance / sigmas).powi(2))
+                    .sum::<f64>()
+                    .sqrt(),
+            })
+            .collect()
+    }
+}
+
+/// The sensitivity of a single `~` bound to each resistor, produced by `RRes::sensitivity`.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub struct Sensitivity {
+    /// The source expression of the bound.
+    pub bound: String,
+    /// The partial derivative of the expression with respect to R1, R2, ..., Rn.
+    pub partials: Vec<f64>,
+    /// The fractional change in the expression per fractional change in R1, R2, ..., Rn, or
+    /// zero for each if the expression is zero at the nominal values.
+    pub relative: Vec<f64>,
+    /// The resistor the expression is most sensitive to, counting from R1.
+    pub most_sensitive: usize,
+}
+
+impl RRes {
+    /// Sensitivity analysis of the `i`th result, reporting how strongly each `~` bound of `op`
+    /// depends on each resistor. The position with the largest relative sensitivity is the one
+    /// most worth upgrading to a tighter tolerance part.
+    /// # Example
+    /// ```
+    ///     # use resistor_calc::*;
+    ///     let op = ROpBuilder::new().bound("0.8 * (1 + R1/R2) ~ 5.0").build();
+    ///     let res = RCalc::e24(2).calc(|rs| op.eval(rs)).unwrap();
+    ///     for s in res.sensitivity(0, &op) {
+    ///         println!("{} is most sensitive to R{}", s.bound, s.most_sensitive);
+    ///     }
+    ///     // R1 and R2 are equal at the best result, so R1 - R2 is zero there.
+    ///     let op = ROpBuilder::new().bound("R1 - R2 ~ 0").build();
+    ///     let res = RCalc::e24(2).calc(|rs| op.eval(rs)).unwrap();
+    ///     assert_eq!(res.sensitivity(0, &op)[0].relative, vec![0.0, 0.0]);
+    /// ```
+    pub fn sensitivity(&self, i: usize, op: &ROp) -> Vec<Sensitivity> {
+        let values = &(self.res[i].1).0;
+        op.gradients(values)
+            .into_iter()
+            .zip(op.quantities(values))
+            .enumerate()
+            .filter(|&(idx, _)| op.is_soft(idx))
+            .map(|(idx, (partials, nominal))| {
+                // The change per fractional change of each resistor ranks them the same as the
+                // relative sensitivity, and still does when the expression is zero.
+                let scaled: Vec<f64> = partials
+                    .iter()
+                    .zip(values.iter())
+                    .map(|(p, v)| p * v)
+                    .collect();
+                let relative = scaled
+                    .iter()
+                    .map(|s| if nominal == 0.0 { 0.0 } else { s / nominal })
+                    .collect();
+                let most_sensitive = scaled
+                    .iter()
+                    .enumerate()
+                    .fold(
+                        (0, 0.0),
+                        |best, (r, s)| {
+                            if s.abs() > best.1 {
+                                (r, s.abs())
+                            } else {
+                                best
+                            }
+                        },
+                    )
+                    .0
+                    + 1;
+                Sensitivity {
+                    bound: op.bounds()[idx].clone(),
+                    partials,
+                    relative,
+                    most_sensitive,
+                }
+            })
+            .collect()
+    }
+}
+
+/// The distribution of tolerances used when sampling values for `RRes::monte_carlo`.
+#[derive(Debug, Clone, Copy)]
+pub enum Distribution {
+    /// Values are spread evenly across the tolerance band.
+    Uniform,
+    /// Values are normally distributed, with the tolerance band spanning the given number of
+    /// standard deviations either side of the nominal value.
+    Normal(f64),
+}
+
+impl Distribution {
+    fn sample<R: Rng>(self, rng: &mut R, val: f64, tol: f64) -> f64 {
+        match self {
+            Distribution::Uniform => val * (1.0 + tol * rng.gen_range(-1.0..=1.0)),
+            Distribution::Normal(sigmas) => {
+                // Box-Muller transform
+                let u1: f64 = 1.0 - rng.gen::<f64>();
+                let u2: f64 = rng.gen();
+                let z = (-2.0 * u1.ln()).sqrt() * (2.0 * PI * u2).cos();
+                val * (1.0 + tol / sigmas * z)
+            }
+        }
+    }
+}
+
+/// Statistics of the value of a single bound's expression over a Monte Carlo run.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub struct BoundStats {
+    /// The source expression of the bound.
+    pub bound: String,
+    /// The mean value of the expression.
+    pub mean: f64,
+    /// The standard deviation of the expression.
+    pub std_dev: f64,
+    /// The lowest value of the expression seen.
+    pub min: f64,
+    /// The highest value of the expression seen.
+    pub max: f64,
+}
+
+/// The results of `RRes::monte_carlo`.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub struct MonteCarlo {
+    /// The number of samples taken.
+    pub samples: usize,
+    /// The fraction of samples for which every comparison bound held, the predicted production
+    /// yield of the result.
+    pub yield_rate: f64,
+    /// Statistics of each bound, in the order the bounds were added.
+    pub bounds: Vec<BoundStats>,
+}
+
+impl RRes {
+    /// Monte Carlo analysis of the `i`th result. Takes `n` samples, and at least one, with each
+    /// resistor drawn from `dist` across the tolerance of its series, and reports the distribution
+    /// of each bound's expression and the fraction of samples that meet all comparison bounds.
+    /// # Example
+    /// ```
+    ///     # use resistor_calc::*;
+    ///     let op = ROpBuilder::new()
+    ///         .bound("R1 / (R1 + R2) ~ 0.5")
+    ///         .bound("R1 / (R1 + R2) < 0.52")
+    ///         .build();
+    ///     let res = RCalc::e24(2).calc(|rs| op.eval(rs)).unwrap();
+    ///     let mc = res.monte_carlo(0, &op, 1000, Distribution::Normal(3.0));
+    ///     println!("Yield: {:.1}%", mc.yield_rate * 100.0);
+    ///     assert!(res.monte_carlo(0, &op, 0, Distribution::Uniform).yield_rate.is_finite());
+    /// ```
+    pub fn monte_carlo(&self, i: usize, op: &ROp, n: usize, dist: Distribution) -> MonteCarlo {
+        let n = n.max(1);
+        let values = &(self.res[i].1).0;
+        let mut rng = rand::thread_rng();
+        let mut passed = 0usize;
+        let mut stats: Vec<(f64, f64, f64, f64)> =
+            vec![(0.0, 0.0, f64::INFINITY, f64::NEG_INFINITY); op.bounds().len()];
+        for _ in 0..n {
+            let sample: Vec<f64> = values
+                .iter()
+                .zip(self.pos.iter())
+                .map(|(v, p)| dist.sample(&mut rng, *v, p.tolerance))
+                .collect();
+            let mut ok = true;
+            for (idx, (stat, val)) in stats.iter_mut().zip(op.quantities(&sample)).enumerate() {
+                ok &= op.outcome(idx, val).0;
+                stat.0 += val;
+                stat.1 += val * val;
+                stat.2 = stat.2.min(val);
+                stat.3 = stat.3.max(val);
+            }
+            if ok {
+                passed += 1;
+            }
+        }
+        let count = n as f64;
+        MonteCarlo {
+            samples: n,
+            yield_rate: passed as f64 / count,
+            bounds: stats
+                .into_iter()
+                .zip(op.bounds())
+                .map(|((sum, sum_sq, min, max), bound)| {
+                    let mean = sum / count;
+                    BoundStats {
+                        bound: bound.clone(),
+                        mean,
+                        std_dev: (sum_sq / count - mean * mean).max(0.0).sqrt(),
+                        min,
+                        max,
+                    }
+                })
+                .collect(),
+        }
+    }
+}
+
+/// The method used to predict the production yield of a result.
+#[derive(Debug, Clone, Copy)]
+pub enum YieldEstimate {
+    /// Estimate from root-sum-square analysis, with tolerances spanning the given number of
+    /// standard deviations, assuming each comparison bound fails independently.
+    Rss(f64),
+    /// Estimate from a Monte Carlo run with the given number of samples and distribution.
+    MonteCarlo(usize, Distribution),
+}
+
+impl RRes {
+    /// Predicts the fraction of built copies of the `i`th result that will meet every
+    /// comparison bound of `op`.
+    pub fn predicted_yield(&self, i: usize, op: &ROp, est: YieldEstimate) -> f64 {
+        match est {
+            YieldEstimate::Rss(sigmas) => self
+                .rss(i, op, sigmas)
+                .iter()
+                .enumerate()
+                .map(|(idx, b)| op.pass_probability(idx, b.nominal, b.sigma))
+                .product(),
+            YieldEstimate::MonteCarlo(n, dist) => self.monte_carlo(i, op, n, dist).yield_rate,
+        }
+    }
+
+    /// Re-ranks the best `n` results by predicted yield, from highest to lowest, rather than by
+    /// nominal error. Results with equal yield keep their nominal ordering, and any results
+    /// beyond the first `n` follow in their original order.
+    /// # Example
+    /// ```
+    ///     # use resistor_calc::*;
+    ///     let op = ROpBuilder::new()
+    ///         .bound("0.8 * (1 + R1/R2) ~ 5.0")
+    ///         .bound("0.8 * (1 + R1/R2) < 5.1")
+    ///         .bound("0.8 * (1 + R1/R2) > 4.9")
+    ///         .build();
+    ///     let res = RCalc::e24(2)
+    ///         .calc(|rs| op.eval(rs))
+    ///         .unwrap()
+    ///         .rank_by_yield(&op, YieldEstimate::Rss(3.0), 20);
+    /// ```
+    pub fn rank_by_yield(mut self, op: &ROp, est: YieldEstimate, n: usize) -> Self {
+        let n = n.min(self.res.len());
+        let yields: Vec<f64> = (0..n).map(|i| self.predicted_yield(i, op, est)).collect();
+        let rest = self.res.split_off(n);
+        let mut ranked: Vec<(f64, (u64, RSet))> =
+            yields.into_iter().zip(self.res.drain(..)).collect();
+        ranked.sort_by(|a, b| b.0.partial_cmp(&a.0).unwrap_or(Ordering::Equal));
+        self.res = ranked.into_iter().map(|(_, r)| r).chain(rest).collect();
+        self
+    }
+}
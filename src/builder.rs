@@ -0,0 +1,162 @@
+use alloc::borrow::Cow;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+use {in_decades, RCalc, RSeries};
+
+/// The configuration of a single resistor position for an `RCalcBuilder`: the values it can
+/// take, the range they are limited to or the value it is fixed at, and the name and package of
+/// the part it places.
+#[derive(Debug, Clone)]
+pub struct RPosition<'a> {
+    series: Cow<'a, RSeries>,
+    min: Option<f64>,
+    max: Option<f64>,
+    decades: Option<Vec<f64>>,
+    fixed: Option<f64>,
+    name: Option<String>,
+    package: Option<String>,
+}
+
+impl<'a> RPosition<'a> {
+    /// A position drawing its values from `series`.
+    pub fn series(series: &'a RSeries) -> Self {
+        RPosition {
+            series: Cow::Borrowed(series),
+            min: None,
+            max: None,
+            decades: None,
+            fixed: None,
+            name: None,
+            package: None,
+        }
+    }
+
+    /// A position that can take exactly the given values, without generating multiples.
+    pub fn values(values: &[f64]) -> Self {
+        RPosition {
+            series: Cow::Owned(RSeries::with_powers("Custom", values, &[1.0])),
+            min: None,
+            max: None,
+            decades: None,
+            fixed: None,
+            name: None,
+            package: None,
+        }
+    }
+
+    /// Only considers values of at least `min` ohms.
+    pub fn min(mut self, min: f64) -> Self {
+        self.min = Some(min);
+        self
+    }
+
+    /// Only considers values of at most `max` ohms.
+    pub fn max(mut self, max: f64) -> Self {
+        self.max = Some(max);
+        self
+    }
+
+    /// Only considers values from `min` to `max` ohms inclusive.
+    pub fn range(self, min: f64, max: f64) -> Self {
+        self.min(min).max(max)
+    }
+
+    /// Only considers values in the given decades, each named by its lowest value, such as
+    /// `1e3` for 1K to 9.1K in the E24 series. Combines with any range.
+    pub fn decades(mut self, decades: &[f64]) -> Self {
+        self.decades = Some(decades.to_vec());
+        self
+    }
+
+    /// Fixes the position at `value`, such as a part that has already been chosen, keeping the
+    /// tolerances of its series for analysis. Takes precedence over any range. The value need not
+    /// be one of the series, so that a part set by an existing design, such as a trimmed or
+    /// non-standard value, can be kept as it is.
+    pub fn fixed(mut self, value: f64) -> Self {
+        self.fixed = Some(value);
+        self
+    }
+
+    /// Names the part placed at the position, such as its designator `R12`, for exported bills
+    /// of materials. Bounds still refer to the position as R1, R2, ..., Rn.
+    pub fn name(mut self, name: &str) -> Self {
+        self.name = Some(name.to_string());
+        self
+    }
+
+    /// Sets the package of the part placed at the position, such as `0603`.
+    pub fn package(mut self, package: &str) -> Self {
+        self.package = Some(package.to_string());
+        self
+    }
+}
+
+/// Builds an `RCalc` with each resistor position configured in one place, as an alternative to
+/// `RCalc::new` for problems where positions are limited to a range, fixed, or placed as named
+/// parts.
+/// # Example
+/// ```
+///     # use resistor_calc::*;
+///     let rcalc = RCalcBuilder::new()
+///         .position(RPosition::series(&E24).range(1e3, 100e3).name("R7"))
+///         .position(RPosition::series(&E24).fixed(10e3).package("0603"))
+///         .position(RPosition::values(&[1e3, 2e3, 5e3]))
+///         .build();
+///     // 1K to 91K and 100K from the E24 series, with one value for R2 and three for R3.
+///     assert_eq!(rcalc.combinations(), (2 * 24 + 1) * 1 * 3);
+///     let res = rcalc
+///         .calc(ROpBuilder::new().bound("R1 + R2 + R3 ~ 60e3").finish())
+///         .unwrap();
+///     assert!(res.to_bom(0).contains("R7,"));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct RCalcBuilder<'a> {
+    positions: Vec<RPosition<'a>>,
+}
+
+impl<'a> RCalcBuilder<'a> {
+    /// Creates a builder with no positions.
+    pub fn new() -> Self {
+        RCalcBuilder {
+            positions: Vec::new(),
+        }
+    }
+
+    /// Adds the next position, R1 first.
+    pub fn position(mut self, position: RPosition<'a>) -> Self {
+        self.positions.push(position);
+        self
+    }
+
+    /// Adds the next position, drawing its values from `series` without further configuration.
+    pub fn series(self, series: &'a RSeries) -> Self {
+        self.position(RPosition::series(series))
+    }
+
+    /// Builds the calculator. A position whose range excludes every value of its series has no
+    /// combinations, so calculations find no results.
+    pub fn build(self) -> RCalc<'a> {
+        let mut rcalc = RCalc::new(Vec::new());
+        for (i, pos) in self.positions.into_iter().enumerate() {
+            let (min, max, decades) = (pos.min, pos.max, pos.decades);
+            let series = match pos.fixed {
+                Some(value) => Cow::Owned(pos.series.pinned(value)),
+                None if min.is_some() || max.is_some() || decades.is_some() => {
+                    Cow::Owned(pos.series.filtered(|v| {
+                        min.map_or(true, |min| v >= min)
+                            && max.map_or(true, |max| v <= max)
+                            && decades.as_ref().map_or(true, |d| in_decades(v, d))
+                    }))
+                }
+                None => pos.series,
+            };
+            rcalc.rs.push(series);
+            rcalc
+                .names
+                .push(pos.name.unwrap_or_else(|| format!("R{}", i + 1)));
+            rcalc.packages.push(pos.package);
+        }
+        rcalc
+    }
+}
@@ -0,0 +1,67 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, Mutex, OnceLock};
+
+#[cfg(feature = "expr_builder")]
+use expr_builder::BoundCache;
+use Position;
+
+/// How a search is split into parts, worked out once for each number of parts.
+#[derive(Debug)]
+pub(crate) struct Plan {
+    /// The values of R1 searched by each part.
+    pub(crate) firsts: Vec<Vec<f64>>,
+}
+
+/// Data derived from an `RCalc` that is kept between calculations, so that solving again with
+/// different bounds, such as in an interactive tool, does not repeat the setup of each search.
+///
+/// The details of the positions, the values searched for each and the plans of searches depend on
+/// the values of the positions, so they are cleared whenever the positions change and are not
+/// shared with clones. The parsed bounds depend only on their source, so they are shared with
+/// every clone and every builder from `RCalc::op_builder`.
+#[derive(Default)]
+pub(crate) struct Cache {
+    /// The details of each position stored alongside each `RRes`.
+    pub(crate) positions: OnceLock<Vec<Position>>,
+    /// The values searched for each position, as left by `fix`, `range` and `decades`.
+    pub(crate) candidates: OnceLock<Vec<Vec<f64>>>,
+    /// The plans of searches, by their number of parts.
+    pub(crate) plans: Mutex<HashMap<usize, Arc<Plan>>>,
+    /// Bounds already parsed from their source.
+    #[cfg(feature = "expr_builder")]
+    pub(crate) bounds: BoundCache,
+}
+
+impl Cache {
+    /// Forgets everything derived from the positions, after they have been changed.
+    pub(crate) fn invalidate(&mut self) {
+        self.positions = OnceLock::new();
+        self.candidates = OnceLock::new();
+        self.plans = Mutex::default();
+    }
+}
+
+impl Clone for Cache {
+    fn clone(&self) -> Self {
+        Cache {
+            positions: OnceLock::new(),
+            candidates: OnceLock::new(),
+            plans: Mutex::default(),
+            #[cfg(feature = "expr_builder")]
+            bounds: self.bounds.clone(),
+        }
+    }
+}
+
+impl fmt::Debug for Cache {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut s = f.debug_struct("Cache");
+        s.field("positions", &self.positions.get().is_some());
+        s.field("candidates", &self.candidates.get().is_some());
+        s.field("plans", &self.plans.lock().unwrap().len());
+        #[cfg(feature = "expr_builder")]
+        s.field("bounds", &self.bounds.lock().unwrap().len());
+        s.finish()
+    }
+}
@@ -0,0 +1,248 @@
+//! A C interface for the `capi` feature, allowing C and C++ tools to embed the optimiser. The
+//! declarations are in `include/resistor_calc.h`.
+//!
+//! A problem is created with `rc_problem_new`, given series and bounds, and solved with
+//! `rc_problem_solve` to produce a results handle. Both handles must be released with their free
+//! function. Functions that can fail return a negative value or null, and `rc_last_error`
+//! describes the most recent failure on the calling thread.
+//! # Example
+//! ```
+//!     # use resistor_calc::capi::*;
+//!     use std::ffi::CString;
+//!     unsafe {
+//!         let problem = rc_problem_new();
+//!         let e24 = CString::new("E24").unwrap();
+//!         rc_problem_add_series(problem, e24.as_ptr());
+//!         rc_problem_add_series(problem, e24.as_ptr());
+//!         let bound = CString::new("R2 / (R1 + R2) ~ 0.25").unwrap();
+//!         assert_eq!(rc_problem_add_bound(problem, bound.as_ptr()), 0);
+//!         let results = rc_problem_solve(problem);
+//!         assert!(rc_results_len(results) > 0);
+//!         let mut values = [0.0; 2];
+//!         rc_results_values(results, 0, values.as_mut_ptr(), 2);
+//!         rc_results_free(results);
+//!         rc_problem_free(problem);
+//!     }
+//! ```
+
+use std::cell::RefCell;
+use std::ffi::{CStr, CString};
+use std::mem;
+use std::os::raw::{c_char, c_int};
+use std::ptr;
+
+use {RCalc, ROpBuilder, RRes, RSeries};
+
+/// A problem under construction, created by `rc_problem_new`.
+pub struct RcProblem {
+    series: Vec<&'static RSeries>,
+    ops: ROpBuilder,
+}
+
+/// The results of solving a problem, created by `rc_problem_solve`.
+pub struct RcResults {
+    res: RRes,
+}
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+fn set_error(msg: &str) {
+    let msg = CString::new(msg.replace('\0', "")).unwrap();
+    LAST_ERROR.with(|e| *e.borrow_mut() = Some(msg));
+}
+
+/// Reads a C string argument, recording an error if it is null or not UTF-8.
+unsafe fn arg<'a>(s: *const c_char) -> Option<&'a str> {
+    if s.is_null() {
+        set_error("Unexpected null string");
+        return None;
+    }
+    let s = CStr::from_ptr(s).to_str();
+    if s.is_err() {
+        set_error("String is not valid UTF-8");
+    }
+    s.ok()
+}
+
+/// Describes the most recent error on the calling thread, or returns null if there has been none.
+/// The string remains valid until the next failing call on the same thread.
+#[no_mangle]
+pub extern "C" fn rc_last_error() -> *const c_char {
+    LAST_ERROR.with(|e| e.borrow().as_ref().map_or(ptr::null(), |msg| msg.as_ptr()))
+}
+
+/// Creates an empty problem, to be released with `rc_problem_free`.
+#[no_mangle]
+pub extern "C" fn rc_problem_new() -> *mut RcProblem {
+    Box::into_raw(Box::new(RcProblem {
+        series: Vec::new(),
+        ops: ROpBuilder::new(),
+    }))
+}
+
+/// Adds a position drawn from the named series, as accepted by `RSeries::by_name`. Returns 0 on
+/// success or -1 if the series is unknown.
+/// # Safety
+/// `problem` must come from `rc_problem_new` and `name` must be a valid C string.
+#[no_mangle]
+pub unsafe extern "C" fn rc_problem_add_series(
+    problem: *mut RcProblem,
+    name: *const c_char,
+) -> c_int {
+    let name = match arg(name) {
+        Some(name) => name,
+        None => return -1,
+    };
+    match RSeries::by_name(name) {
+        Some(series) => {
+            (*problem).series.push(series);
+            0
+        }
+        None => {
+            set_error(&format!("Unknown series '{}'", name));
+            -1
+        }
+    }
+}
+
+/// Adds a bound, as with `ROpBuilder::bound`. Returns 0 on success or -1 if the bound is invalid.
+/// # Safety
+/// `problem` must come from `rc_problem_new` and `expr` must be a valid C string.
+#[no_mangle]
+pub unsafe extern "C" fn rc_problem_add_bound(
+    problem: *mut RcProblem,
+    expr: *const c_char,
+) -> c_int {
+    let expr = match arg(expr) {
+        Some(expr) => expr,
+        None => return -1,
+    };
+    let problem = &mut *problem;
+    match problem.ops.clone().try_bound(expr) {
+        Ok(ops) => {
+            problem.ops = ops;
+            0
+        }
+        Err(e) => {
+            set_error(e.message());
+            -1
+        }
+    }
+}
+
+/// Declares a named constant for the bounds, as with `ROpBuilder::constant`. Returns 0 on success
+/// or -1 if the name is invalid.
+/// # Safety
+/// `problem` must come from `rc_problem_new` and `name` must be a valid C string.
+#[no_mangle]
+pub unsafe extern "C" fn rc_problem_add_constant(
+    problem: *mut RcProblem,
+    name: *const c_char,
+    value: f64,
+) -> c_int {
+    let name = match arg(name) {
+        Some(name) => name,
+        None => return -1,
+    };
+    let problem = &mut *problem;
+    problem.ops = mem::take(&mut problem.ops).constant(name, value);
+    0
+}
+
+/// Solves the problem, returning the results to be released with `rc_results_free`, or null if
+/// there are no series, a bound cannot be evaluated or no combination satisfies the bounds, with
+/// the reason given by `rc_last_error`.
+/// # Safety
+/// `problem` must come from `rc_problem_new`.
+/// # Example
+/// ```
+///     # use resistor_calc::capi::*;
+///     use std::ffi::CString;
+///     unsafe {
+///         let problem = rc_problem_new();
+///         let e24 = CString::new("E24").unwrap();
+///         rc_problem_add_series(problem, e24.as_ptr());
+///         let bound = CString::new("R1 + R2 ~ 500").unwrap();
+///         rc_problem_add_bound(problem, bound.as_ptr());
+///         // There is no R2, so the bound cannot be evaluated.
+///         assert!(rc_problem_solve(problem).is_null());
+///         assert!(!rc_last_error().is_null());
+///         rc_problem_free(problem);
+///     }
+/// ```
+#[no_mangle]
+pub unsafe extern "C" fn rc_problem_solve(problem: *const RcProblem) -> *mut RcResults {
+    let problem = &*problem;
+    let op = problem.ops.clone().build();
+    match RCalc::new(problem.series.clone()).try_calc(&op) {
+        Ok(res) => Box::into_raw(Box::new(RcResults { res })),
+        Err(e) => {
+            set_error(e.message());
+            ptr::null_mut()
+        }
+    }
+}
+
+/// Releases a problem. Passing null does nothing.
+/// # Safety
+/// `problem` must come from `rc_problem_new` and not be used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn rc_problem_free(problem: *mut RcProblem) {
+    if !problem.is_null() {
+        drop(Box::from_raw(problem));
+    }
+}
+
+/// The number of results, or 0 if `results` is null.
+/// # Safety
+/// `results` must be null or come from `rc_problem_solve`.
+#[no_mangle]
+pub unsafe extern "C" fn rc_results_len(results: *const RcResults) -> usize {
+    results.as_ref().map_or(0, |r| r.res.len())
+}
+
+/// The error of the `i`th result, counting from zero in order of increasing error, or a negative
+/// value if there is no such result.
+/// # Safety
+/// `results` must be null or come from `rc_problem_solve`.
+#[no_mangle]
+pub unsafe extern "C" fn rc_results_error(results: *const RcResults, i: usize) -> f64 {
+    results
+        .as_ref()
+        .and_then(|r| r.res.get(i))
+        .map_or(-1.0, |&(err, _)| err as f64 / 1e9)
+}
+
+/// Copies up to `len` values of R1, R2, ..., Rn of the `i`th result into `out`, returning the
+/// number of values in the result, or 0 if there is no such result.
+/// # Safety
+/// `results` must be null or come from `rc_problem_solve`, and `out` must have room for `len`
+/// values.
+#[no_mangle]
+pub unsafe extern "C" fn rc_results_values(
+    results: *const RcResults,
+    i: usize,
+    out: *mut f64,
+    len: usize,
+) -> usize {
+    match results.as_ref().and_then(|r| r.res.get(i)) {
+        Some((_, rs)) => {
+            let values = rs.values();
+            ptr::copy_nonoverlapping(values.as_ptr(), out, values.len().min(len));
+            values.len()
+        }
+        None => 0,
+    }
+}
+
+/// Releases results. Passing null does nothing.
+/// # Safety
+/// `results` must come from `rc_problem_solve` and not be used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn rc_results_free(results: *mut RcResults) {
+    if !results.is_null() {
+        drop(Box::from_raw(results));
+    }
+}
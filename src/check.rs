@@ -0,0 +1,133 @@
+use alloc::string::ToString;
+use alloc::vec::Vec;
+
+use {Error, RCalc, RRes, RSet};
+
+/// How a hand-chosen set of values fares against a problem, produced by `RCalc::check`.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub struct CheckReport {
+    /// The values checked, for R1, R2, ..., Rn.
+    pub values: RSet,
+    /// The error of the values, or `None` if a bound rejects them.
+    pub error: Option<f64>,
+    /// Whether each value is one of the values of the series of its position.
+    pub in_series: Vec<bool>,
+    /// The value of the series of each position closest to the value checked, or `None` for a
+    /// position without values.
+    pub nearest: Vec<Option<f64>>,
+}
+
+impl CheckReport {
+    /// Whether the bounds accept the values.
+    pub fn accepted(&self) -> bool {
+        self.error.is_some()
+    }
+}
+
+/// The distance between two values on a log scale, as a ratio, so that the nearest value is the
+/// same whichever decade it is in.
+fn ratio(a: f64, b: f64) -> f64 {
+    if a > b {
+        a / b
+    } else {
+        b / a
+    }
+}
+
+/// The total relative deviation of `values` from `seed`.
+fn distance(values: &[f64], seed: &[f64]) -> f64 {
+    values
+        .iter()
+        .zip(seed)
+        .map(|(&v, &s)| if v > s { (v - s) / s } else { (s - v) / s })
+        .sum()
+}
+
+impl<'a> RCalc<'a> {
+    /// Finds the combinations closest to a `seed` set of values, such as the ideal values of a
+    /// design computed analytically, among those that the bounds `f` accept. Rather than by the
+    /// error of the bounds, results are ranked by the sum of the relative deviations of each value
+    /// from the seed, which becomes the error of each result.
+    ///
+    /// Returns `Error::Parse` unless there is one positive seed value for each position, and
+    /// `Error::Solver` if the bounds accept no combination.
+    /// # Example
+    /// ```
+    ///     # use resistor_calc::*;
+    ///     let op = ROpBuilder::new().bound("R2 / (R1 + R2) <= 0.25").build();
+    ///     // The ratio of the seed is above 0.25, so R1 rises to the next E24 value.
+    ///     let res = RCalc::e24(2)
+    ///         .nearest(&[28e3, 10e3], |rs| op.eval(rs))
+    ///         .unwrap();
+    ///     let (_, best) = res.iter().next().unwrap();
+    ///     assert_eq!(best.values(), &[30e3, 10e3]);
+    ///     let res = RCalc::e24(2).nearest(&[0.0, 10e3], |rs| op.eval(rs));
+    ///     assert!(matches!(res, Err(Error::Parse(_))));
+    /// ```
+    pub fn nearest(
+        &self,
+        seed: &[f64],
+        f: impl Fn(&RSet) -> Option<f64> + Sync,
+    ) -> Result<RRes, Error> {
+        if seed.len() != self.rs.len() {
+            return Err(Error::Parse(format!(
+                "{} seed values were given for {} positions",
+                seed.len(),
+                self.rs.len()
+            )));
+        }
+        if let Some(i) = seed.iter().position(|&s| !(s > 0.0 && s.is_finite())) {
+            return Err(Error::Parse(format!(
+                "The seed value of R{} must be positive, not {}",
+                i + 1,
+                seed[i]
+            )));
+        }
+        self.calc(|rs| f(rs).map(|_| distance(rs.values(), seed)))
+            .ok_or_else(|| Error::Solver("No values satisfy the bounds".to_string()))
+    }
+
+    /// Checks a set of values, such as those of an existing schematic under review, against the
+    /// bounds `f`. The values need not be in the series of their positions; the report says
+    /// which are, along with the nearest value of each series.
+    /// # Example
+    /// ```
+    ///     # use resistor_calc::*;
+    ///     let op = ROpBuilder::new()
+    ///         .bound("R1 + R2 >= 1e4")
+    ///         .bound("R2 / (R1 + R2) ~ 0.25")
+    ///         .build();
+    ///     let report = RCalc::e24(2).check(&[30.1e3, 10e3], |rs| op.eval(rs));
+    ///     assert!(report.accepted());
+    ///     assert_eq!(report.in_series, [false, true]);
+    ///     assert_eq!(report.nearest[0], Some(30e3));
+    /// ```
+    pub fn check(&self, values: &[f64], f: impl Fn(&RSet) -> Option<f64>) -> CheckReport {
+        assert_eq!(
+            values.len(),
+            self.rs.len(),
+            "A value must be given for each position"
+        );
+        let rs = RSet::new(values);
+        CheckReport {
+            error: f(&rs),
+            values: rs,
+            in_series: values
+                .iter()
+                .zip(&self.rs)
+                .map(|(v, r)| r.values().contains(v))
+                .collect(),
+            nearest: values
+                .iter()
+                .zip(&self.rs)
+                .map(|(&v, r)| {
+                    r.values()
+                        .iter()
+                        .cloned()
+                        .min_by(|&a, &b| ratio(a, v).total_cmp(&ratio(b, v)))
+                })
+                .collect(),
+        }
+    }
+}
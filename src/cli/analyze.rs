@@ -0,0 +1,103 @@
+//! The `analyze` subcommand, which runs tolerance analysis on a given set of values.
+
+use clap::{App, Arg, ArgMatches, SubCommand};
+use resistor_calc::{Distribution, Error, RCalc, ROpBuilder, RSeries};
+
+use cli::{arg_error, bounds, fail, series, values, Exit};
+
+pub fn subcommand<'a, 'b>() -> App<'a, 'b> {
+    SubCommand::with_name("analyze")
+        .about("Runs worst-case and Monte Carlo analysis on a given set of values")
+        .arg(
+            Arg::with_name("values")
+                .long("values")
+                .short("v")
+                .takes_value(true)
+                .required(true)
+                .help("Comma separated values of R1, R2, ..., Rn, such as 13k,15k,2k"),
+        )
+        .arg(
+            Arg::with_name("bound")
+                .long("bound")
+                .short("b")
+                .takes_value(true)
+                .multiple(true)
+                .number_of_values(1)
+                .required(true)
+                .help("A bound on R1, R2, ..., Rn"),
+        )
+        .arg(
+            Arg::with_name("series")
+                .long("series")
+                .short("s")
+                .takes_value(true)
+                .default_value("E24")
+                .help("Comma separated series the values are drawn from, for their tolerances"),
+        )
+        .arg(
+            Arg::with_name("samples")
+                .long("samples")
+                .takes_value(true)
+                .default_value("10000")
+                .help("The number of Monte Carlo samples"),
+        )
+}
+
+pub fn run(matches: &ArgMatches) {
+    let values = values(matches.value_of("values").unwrap());
+    let mut series = series(matches.value_of("series").unwrap());
+    if series.len() == 1 {
+        series = vec![series[0]; values.len()];
+    }
+    if series.len() != values.len() {
+        fail(
+            Exit::Parse,
+            "The number of series must match the number of values",
+        );
+    }
+    let samples = value_t!(matches, "samples", usize).unwrap_or_else(|e| arg_error(e));
+
+    // Pin each position to its given value, keeping the tolerances of its series.
+    let fixed: Vec<RSeries> = values
+        .iter()
+        .zip(series.iter())
+        .map(|(&v, s)| {
+            RSeries::with_powers(s.name(), &[v], &[1.0])
+                .with_tolerance(s.tolerance())
+                .with_tempco(s.tempco())
+                .with_drift(s.drift())
+        })
+        .collect();
+    let op = bounds(ROpBuilder::new(), matches.values_of("bound").unwrap()).build();
+    // Validating the bounds against the values first keeps the analyses below from panicking.
+    let res = match RCalc::new(fixed.iter().collect()).try_calc(&op) {
+        Ok(res) => res,
+        Err(Error::Solver(_)) => fail(Exit::NoSolution, "The values do not satisfy the bounds"),
+        Err(e) => fail(Exit::from(&e), e.message()),
+    };
+    let (err, rs) = res.get(0).unwrap();
+
+    println!("Values: {}\nError: {:.6}\n", rs, (*err as f64) / 1e9);
+    println!("Worst case:");
+    for range in res.worst_case(0, &op) {
+        println!(
+            "  {}: {} to {} ({})",
+            range.bound,
+            range.min,
+            range.max,
+            if range.holds { "holds" } else { "fails" }
+        );
+    }
+    let mc = res.monte_carlo(0, &op, samples, Distribution::Normal(3.0));
+    println!(
+        "\nMonte Carlo ({} samples, normal with tolerance at 3 sigma):\n  Yield: {:.2}%",
+        mc.samples,
+        mc.yield_rate * 100.0
+    );
+    for stats in mc.bounds {
+        println!(
+            "  {}: mean {}, std dev {}, range {} to {}",
+            stats.bound, stats.mean, stats.std_dev, stats.min, stats.max
+        );
+    }
+}
@@ -0,0 +1,172 @@
+//! The `batch` subcommand, which solves every problem file in a directory.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread;
+
+use clap::{App, Arg, ArgMatches, SubCommand};
+
+use cli::{arg_error, fail, quiet, read_problem, Exit};
+
+pub fn subcommand<'a, 'b>() -> App<'a, 'b> {
+    SubCommand::with_name("batch")
+        .about("Solves every problem file in a directory, writing a result file for each")
+        .arg(
+            Arg::with_name("dir")
+                .required(true)
+                .help("A directory of problem files in TOML, or JSON if they end in .json"),
+        )
+        .arg(
+            Arg::with_name("out")
+                .long("out")
+                .short("o")
+                .takes_value(true)
+                .help("The directory to write results to [default: <dir>/results]"),
+        )
+        .arg(
+            Arg::with_name("jobs")
+                .long("jobs")
+                .short("j")
+                .takes_value(true)
+                .default_value("1")
+                .help("The number of problems to solve at once"),
+        )
+}
+
+/// The outcome of solving a single problem file, for the summary index.
+struct Outcome {
+    name: String,
+    status: &'static str,
+    results: usize,
+    best: Option<f64>,
+    elapsed: f64,
+    message: String,
+}
+
+fn solve(path: &Path, out: &Path) -> Outcome {
+    let name = path.file_stem().unwrap().to_string_lossy().into_owned();
+    let mut outcome = Outcome {
+        name,
+        status: "solved",
+        results: 0,
+        best: None,
+        elapsed: 0.0,
+        message: String::new(),
+    };
+    match read_problem(&path.to_string_lossy()).and_then(|p| p.solve().map_err(String::from)) {
+        Ok(report) => {
+            outcome.results = report.results.len();
+            outcome.best = report.results.first().map(|m| m.error);
+            outcome.elapsed = report.elapsed;
+            if report.results.is_empty() {
+                outcome.status = "no_solution";
+            }
+            let file = out.join(format!("{}.json", outcome.name));
+            if let Err(e) = fs::write(&file, report.to_json()) {
+                outcome.status = "io_error";
+                outcome.message = format!("Writing {}: {}", file.display(), e);
+            }
+        }
+        Err(e) => {
+            outcome.status = "parse_error";
+            outcome.message = e;
+        }
+    }
+    outcome
+}
+
+/// Quotes a field for the CSV index if it contains a separator or quote.
+fn csv_field(field: &str) -> String {
+    if field.contains(',') || field.contains('"') || field.contains('\n') {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+pub fn run(matches: &ArgMatches) {
+    let dir = Path::new(matches.value_of("dir").unwrap());
+    let out = matches
+        .value_of("out")
+        .map_or_else(|| dir.join("results"), PathBuf::from);
+    let jobs = value_t!(matches, "jobs", usize).unwrap_or_else(|e| arg_error(e));
+
+    let mut problems: Vec<PathBuf> = fs::read_dir(dir)
+        .unwrap_or_else(|e| fail(Exit::Io, &format!("{}: {}", dir.display(), e)))
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| {
+            path.is_file()
+                && path
+                    .extension()
+                    .is_some_and(|ext| ext == "toml" || ext == "json")
+        })
+        .collect();
+    problems.sort();
+    fs::create_dir_all(&out)
+        .unwrap_or_else(|e| fail(Exit::Io, &format!("{}: {}", out.display(), e)));
+
+    // Each thread takes the next unsolved problem until none are left.
+    let next = AtomicUsize::new(0);
+    let outcomes = Mutex::new(Vec::new());
+    thread::scope(|s| {
+        for _ in 0..jobs.max(1) {
+            s.spawn(|| loop {
+                let idx = next.fetch_add(1, Ordering::Relaxed);
+                let path = match problems.get(idx) {
+                    Some(path) => path,
+                    None => break,
+                };
+                let outcome = solve(path, &out);
+                if !quiet() {
+                    eprintln!("{}: {}", outcome.name, outcome.status);
+                }
+                outcomes.lock().unwrap().push((idx, outcome));
+            });
+        }
+    });
+    let mut outcomes = outcomes.into_inner().unwrap();
+    outcomes.sort_by_key(|&(idx, _)| idx);
+
+    let mut index = String::from("problem,status,results,best_error,elapsed,message\n");
+    for (_, o) in &outcomes {
+        index.push_str(&format!(
+            "{},{},{},{},{:.3},{}\n",
+            csv_field(&o.name),
+            o.status,
+            o.results,
+            o.best.map_or_else(String::new, |b| b.to_string()),
+            o.elapsed,
+            csv_field(&o.message)
+        ));
+    }
+    let index_path = out.join("index.csv");
+    fs::write(&index_path, &index).unwrap_or_else(|e| {
+        fail(
+            Exit::Io,
+            &format!("Writing {}: {}", index_path.display(), e),
+        )
+    });
+
+    let solved = outcomes
+        .iter()
+        .filter(|(_, o)| o.status == "solved")
+        .count();
+    if !quiet() {
+        println!(
+            "Solved {} of {} problems, results in {}",
+            solved,
+            outcomes.len(),
+            out.display()
+        );
+    }
+    let any = |status| outcomes.iter().any(|(_, o)| o.status == status);
+    if any("io_error") {
+        fail(Exit::Io, "Some results could not be written");
+    } else if any("parse_error") {
+        fail(Exit::Parse, "Some problems could not be parsed");
+    } else if any("no_solution") {
+        fail(Exit::NoSolution, "Some problems have no solution");
+    }
+}
@@ -0,0 +1,168 @@
+//! The `bench` subcommand, which compares the search strategies on a single problem.
+//!
+//! The strategies are those the library offers: the exhaustive search, spread across threads or
+//! keeping only the best results, and a search pruned to the ranges suggested by
+//! `RCalc::estimate`. The library has no heuristic search, so none is compared.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+use std::time::Instant;
+
+use clap::{App, Arg, ArgMatches, SubCommand};
+use resistor_calc::{Limits, RRes};
+
+use cli::{arg_error, fail, load_problem, Exit};
+
+/// The number of combinations sampled to choose the ranges of the pruned strategy.
+const SAMPLES: usize = 2000;
+
+/// The bytes currently allocated by the tool.
+static CURRENT: AtomicUsize = AtomicUsize::new(0);
+
+/// The most bytes allocated at once since it was last reset.
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+/// The system allocator, keeping count of the memory allocated so that the peak memory of each
+/// strategy is measured rather than estimated.
+struct Counting;
+
+impl Counting {
+    fn grow(size: usize) {
+        let now = CURRENT.fetch_add(size, Ordering::Relaxed) + size;
+        PEAK.fetch_max(now, Ordering::Relaxed);
+    }
+}
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            Counting::grow(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        CURRENT.fetch_sub(layout.size(), Ordering::Relaxed);
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new = System.realloc(ptr, layout, new_size);
+        if !new.is_null() {
+            if new_size > layout.size() {
+                Counting::grow(new_size - layout.size());
+            } else {
+                CURRENT.fetch_sub(layout.size() - new_size, Ordering::Relaxed);
+            }
+        }
+        new
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: Counting = Counting;
+
+/// A way of solving the problem, returning its results.
+type Strategy<'s> = Box<dyn Fn() -> Option<RRes> + 's>;
+
+pub fn subcommand<'a, 'b>() -> App<'a, 'b> {
+    SubCommand::with_name("bench")
+        .about("Solves a problem with each search strategy and compares them")
+        .arg(
+            Arg::with_name("problem")
+                .required(true)
+                .help("A problem file in TOML, or JSON if it ends in .json"),
+        )
+        .arg(
+            Arg::with_name("jobs")
+                .long("jobs")
+                .short("j")
+                .takes_value(true)
+                .help("The number of threads for the threaded strategy [default: all cores]"),
+        )
+        .arg(
+            Arg::with_name("top")
+                .long("top")
+                .short("n")
+                .takes_value(true)
+                .default_value("10")
+                .help("The number of results kept by the top-k strategy"),
+        )
+}
+
+pub fn run(matches: &ArgMatches) {
+    let problem = load_problem(matches.value_of("problem").unwrap());
+    let (rcalc, op) = problem
+        .prepare()
+        .unwrap_or_else(|e| fail(Exit::from(&e), e.message()));
+    let jobs = match matches.value_of("jobs") {
+        Some(_) => value_t!(matches, "jobs", usize).unwrap_or_else(|e| arg_error(e)),
+        None => thread::available_parallelism().map_or(1, |n| n.get()),
+    };
+    let top = value_t!(matches, "top", usize).unwrap_or_else(|e| arg_error(e));
+
+    let (rcalc, op) = (&rcalc, &op);
+    let limited = |limits: Limits| move || rcalc.calc_limited(|rs| op.eval(rs), &limits, |_| ());
+    let strategies: Vec<(String, Strategy)> = vec![
+        (
+            "exhaustive".to_string(),
+            Box::new(|| rcalc.calc(|rs| op.eval(rs))),
+        ),
+        (
+            format!("threaded ({} jobs)", jobs),
+            Box::new(limited(Limits {
+                jobs,
+                ..Default::default()
+            })),
+        ),
+        (
+            format!("top-{}", top),
+            Box::new(limited(Limits {
+                max_results: Some(top),
+                ..Default::default()
+            })),
+        ),
+        (
+            "pruned".to_string(),
+            Box::new(|| {
+                let suggestions = rcalc.estimate(|rs| op.eval(rs), SAMPLES).suggestions;
+                suggestions
+                    .iter()
+                    .fold(rcalc.clone(), |pruned, s| {
+                        pruned.range(s.position, s.min, s.max)
+                    })
+                    .calc(|rs| op.eval(rs))
+            }),
+        ),
+    ];
+
+    println!("Combinations: {}\n", rcalc.combinations());
+    println!(
+        "{:<20} {:>10} {:>12} {:>10} {:>12}",
+        "Strategy", "Time (s)", "Peak (KB)", "Results", "Best error"
+    );
+    for (name, strategy) in &strategies {
+        let base = CURRENT.load(Ordering::Relaxed);
+        PEAK.store(base, Ordering::Relaxed);
+        let start = Instant::now();
+        let res = strategy();
+        let elapsed = start.elapsed().as_secs_f64();
+        let peak = PEAK.load(Ordering::Relaxed).saturating_sub(base) as f64 / 1024.0;
+        match res {
+            Some(res) => println!(
+                "{:<20} {:>10.3} {:>12.1} {:>10} {:>12.6}",
+                name,
+                elapsed,
+                peak,
+                res.len(),
+                res.get(0).map_or(0.0, |(err, _)| *err as f64 / 1e9)
+            ),
+            None => println!(
+                "{:<20} {:>10.3} {:>12.1} {:>10} {:>12}",
+                name, elapsed, peak, 0, "-"
+            ),
+        }
+    }
+}
@@ -0,0 +1,83 @@
+//! The `code` subcommand, which converts between values, colour bands and SMD codes.
+
+use clap::{App, Arg, ArgMatches, SubCommand};
+use resistor_calc::{
+    colour_bands, format_value, from_colour_bands, from_smd_code, parse_value, smd_code, Band,
+    SmdCode,
+};
+
+use cli::{fail, Exit};
+
+pub fn subcommand<'a, 'b>() -> App<'a, 'b> {
+    SubCommand::with_name("code")
+        .about("Converts between values, colour bands and SMD codes")
+        .arg(Arg::with_name("input").required(true).multiple(true).help(
+            "A value such as 4k7, colour bands such as \"yellow violet red\", or an SMD code \
+             such as 4R7 or 01C. Bare digits such as 472 are read as ohms unless --from smd \
+             is given",
+        ))
+        .arg(
+            Arg::with_name("from")
+                .long("from")
+                .takes_value(true)
+                .possible_values(&["auto", "value", "bands", "smd"])
+                .default_value("auto")
+                .help(
+                    "How to interpret the input. auto reads colour names as bands, then tries \
+                     a value and then an SMD code",
+                ),
+        )
+}
+
+fn show(name: &str, marking: Option<String>) {
+    println!("{}: {}", name, marking.unwrap_or_else(|| "n/a".to_string()));
+}
+
+fn bands(r: f64, digits: u32) -> Option<String> {
+    colour_bands(r, digits)
+        .map(|bands| bands.iter().map(|b| b.name()).collect::<Vec<_>>().join(" "))
+}
+
+/// Splits colour bands given as one or more arguments, separated by commas or spaces.
+fn band_names<'a>(input: &[&'a str]) -> Vec<&'a str> {
+    input
+        .iter()
+        .flat_map(|s| s.split(|c: char| c == ',' || c.is_whitespace()))
+        .filter(|s| !s.is_empty())
+        .collect()
+}
+
+pub fn run(matches: &ArgMatches) {
+    let input: Vec<&str> = matches.values_of("input").unwrap().collect();
+    let names = band_names(&input);
+    let value = match matches.value_of("from").unwrap() {
+        "bands" => {
+            let bands = names
+                .iter()
+                .map(|name| {
+                    Band::from_name(name)
+                        .unwrap_or_else(|| fail(Exit::Parse, &format!("Unknown colour '{}'", name)))
+                })
+                .collect::<Vec<_>>();
+            from_colour_bands(&bands)
+        }
+        "smd" => from_smd_code(&input.join("")),
+        "value" => parse_value(&input.join("")),
+        _ => match names
+            .iter()
+            .map(|name| Band::from_name(name))
+            .collect::<Option<Vec<_>>>()
+        {
+            Some(bands) => from_colour_bands(&bands),
+            None => parse_value(&input.join("")).or_else(|| from_smd_code(&input.join(""))),
+        },
+    }
+    .unwrap_or_else(|| fail(Exit::Parse, "Could not decode the input"));
+
+    println!("Value: {} ({} ohms)", format_value(value), value);
+    show("4 band", bands(value, 2));
+    show("5 band", bands(value, 3));
+    show("SMD 3 digit", smd_code(value, SmdCode::ThreeDigit));
+    show("SMD 4 digit", smd_code(value, SmdCode::FourDigit));
+    show("SMD EIA-96", smd_code(value, SmdCode::Eia96));
+}
@@ -0,0 +1,47 @@
+//! The `estimate` subcommand, which predicts the cost of a problem before solving it.
+
+use clap::{App, Arg, ArgMatches, SubCommand};
+use resistor_calc::format_value;
+
+use cli::{arg_error, fail, load_problem, Exit};
+
+pub fn subcommand<'a, 'b>() -> App<'a, 'b> {
+    SubCommand::with_name("estimate")
+        .about("Estimates the size and runtime of a problem without solving it")
+        .arg(
+            Arg::with_name("problem")
+                .required(true)
+                .help("A problem file in TOML, or JSON if it ends in .json"),
+        )
+        .arg(
+            Arg::with_name("samples")
+                .long("samples")
+                .takes_value(true)
+                .default_value("2000")
+                .help("The number of combinations to sample"),
+        )
+}
+
+pub fn run(matches: &ArgMatches) {
+    let problem = load_problem(matches.value_of("problem").unwrap());
+    let samples = value_t!(matches, "samples", usize).unwrap_or_else(|e| arg_error(e));
+    let estimate = problem
+        .estimate(samples)
+        .unwrap_or_else(|e| fail(Exit::from(&e), e.message()));
+    println!("Combinations: {}", estimate.combinations);
+    println!(
+        "Estimated runtime: {:.1}s ({:.1}µs per combination)",
+        estimate.runtime.as_secs_f64(),
+        estimate.per_combination.as_secs_f64() * 1e6
+    );
+    println!("Sampled acceptance: {:.2}%", estimate.acceptance * 100.0);
+    for s in &estimate.suggestions {
+        println!(
+            "Restricting R{} to {}–{} reduces the space {:.1}x",
+            s.position,
+            format_value(s.min),
+            format_value(s.max),
+            s.reduction
+        );
+    }
+}
@@ -0,0 +1,83 @@
+//! The `explain` subcommand, which shows how a hand-chosen set of values fares against a problem.
+
+use clap::{App, Arg, ArgMatches, SubCommand};
+use resistor_calc::format_value;
+
+use cli::{fail, load_problem, values, Exit};
+
+pub fn subcommand<'a, 'b>() -> App<'a, 'b> {
+    SubCommand::with_name("explain")
+        .about("Shows which bounds of a problem a set of values violates, and its error")
+        .arg(
+            Arg::with_name("values")
+                .long("values")
+                .short("v")
+                .takes_value(true)
+                .required(true)
+                .help("Comma separated values of R1, R2, ..., Rn, such as 13k,15k,2k"),
+        )
+        .arg(
+            Arg::with_name("problem")
+                .required(true)
+                .help("A problem file in TOML, or JSON if it ends in .json"),
+        )
+}
+
+pub fn run(matches: &ArgMatches) {
+    let problem = load_problem(matches.value_of("problem").unwrap());
+    let values = values(matches.value_of("values").unwrap());
+    if !problem.series.is_empty() && problem.series.len() != values.len() {
+        fail(
+            Exit::Parse,
+            &format!(
+                "The problem has {} positions but {} values were given",
+                problem.series.len(),
+                values.len()
+            ),
+        );
+    }
+    let (rcalc, op) = problem
+        .prepare()
+        .unwrap_or_else(|e| fail(Exit::from(&e), e.message()));
+    let report = rcalc.check(&values, |rs| op.eval(rs));
+    let rs = &report.values;
+
+    println!("Values: {}", rs);
+    for (i, nearest) in report.nearest.iter().enumerate() {
+        if let (false, Some(nearest)) = (report.in_series[i], nearest) {
+            println!(
+                "R{} is not in the {} series, the nearest value is {}",
+                i + 1,
+                problem.series[i],
+                format_value(*nearest)
+            );
+        }
+    }
+    println!();
+    for outcome in op.explain(rs) {
+        if outcome.soft {
+            println!(
+                "{}\n  value {:.6}, error {:.6}",
+                outcome.bound, outcome.value, outcome.deviation
+            );
+        } else {
+            println!(
+                "{}\n  value {:.6}, {}",
+                outcome.bound,
+                outcome.value,
+                if outcome.holds {
+                    "holds".to_string()
+                } else {
+                    format!("violated by {:.6}", outcome.deviation)
+                }
+            );
+        }
+    }
+    match report.error {
+        Some(err) => println!("\nAccepted with total error {:.6}", err),
+        None => {
+            println!("\nRejected");
+            fail(Exit::NoSolution, "The values do not satisfy the bounds");
+        }
+    }
+}
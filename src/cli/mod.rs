@@ -0,0 +1,392 @@
+//! Support for the command line front end, kept out of the library.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::process;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+use clap::{self, ArgMatches};
+use env_logger::{Builder, Env};
+use log::LevelFilter;
+use resistor_calc::{
+    bom_values, parse_value, CalcOptions, Error, Landscape, Limits, Netlist, PriceTable, Problem,
+    Profile, RCalc, ROp, ROpBuilder, RSeries, RSet,
+};
+
+pub mod analyze;
+pub mod batch;
+pub mod bench;
+pub mod code;
+pub mod estimate;
+pub mod explain;
+pub mod nearest;
+pub mod repl;
+pub mod series;
+pub mod serve;
+pub mod solve;
+pub mod watch;
+
+/// The exit codes of the command line tool, other than 0 for success.
+#[derive(Debug, Clone, Copy)]
+pub enum Exit {
+    /// No combination satisfied the bounds.
+    NoSolution = 1,
+    /// The arguments, bounds or problem could not be understood.
+    Parse = 2,
+    /// The time limit was reached, so the results are from a partial search.
+    Timeout = 3,
+    /// A file could not be read or written.
+    Io = 4,
+    /// The search has more combinations than `--max-combinations` allows.
+    TooLarge = 5,
+}
+
+impl Exit {
+    fn name(self) -> &'static str {
+        match self {
+            Exit::NoSolution => "no_solution",
+            Exit::Parse => "parse_error",
+            Exit::Timeout => "timeout",
+            Exit::Io => "io_error",
+            Exit::TooLarge => "too_large",
+        }
+    }
+}
+
+impl<'a> From<&'a Error> for Exit {
+    /// The exit code for a failure of the library: no solution for a solver error, the error's
+    /// own code for files and guards, and a parse error for bounds that cannot be parsed or
+    /// evaluated.
+    fn from(e: &'a Error) -> Self {
+        match *e {
+            Error::Parse(_) | Error::Eval(_) => Exit::Parse,
+            Error::Solver(_) => Exit::NoSolution,
+            Error::Io(_) => Exit::Io,
+            Error::TooLarge(_) => Exit::TooLarge,
+        }
+    }
+}
+
+static JSON_ERRORS: AtomicBool = AtomicBool::new(false);
+static QUIET: AtomicBool = AtomicBool::new(false);
+
+/// Sets whether errors are reported as JSON, and whether output other than errors is suppressed.
+pub fn configure(json_errors: bool, quiet: bool) {
+    JSON_ERRORS.store(json_errors, Ordering::Relaxed);
+    QUIET.store(quiet, Ordering::Relaxed);
+}
+
+/// Whether output other than errors should be suppressed.
+pub fn quiet() -> bool {
+    QUIET.load(Ordering::Relaxed)
+}
+
+/// Reports `msg` as an error of the given kind on stderr, as JSON if requested.
+fn report(exit: Exit, msg: &str) {
+    if JSON_ERRORS.load(Ordering::Relaxed) {
+        eprintln!(
+            "{{\"error\":\"{}\",\"code\":{},\"message\":{}}}",
+            exit.name(),
+            exit as i32,
+            ::serde_json::to_string(msg).unwrap()
+        );
+    } else {
+        eprintln!("Error: {}", msg);
+    }
+}
+
+/// Reports `msg` as an error of the given kind and exits with its code.
+pub fn fail(exit: Exit, msg: &str) -> ! {
+    report(exit, msg);
+    process::exit(exit as i32);
+}
+
+/// Sends the solver's log to stderr, at a level given by the number of `--verbose` flags or by
+/// `RUST_LOG` if there are none.
+pub fn init_logging(verbose: u64) {
+    let mut logger = Builder::from_env(Env::default().default_filter_or("warn"));
+    let level = match verbose {
+        0 => None,
+        1 => Some(LevelFilter::Info),
+        2 => Some(LevelFilter::Debug),
+        _ => Some(LevelFilter::Trace),
+    };
+    if let Some(level) = level {
+        logger.filter_module("resistor_calc", level);
+    }
+    logger.init();
+}
+
+/// Exits for a command line error from clap, showing help and version output as usual.
+pub fn arg_error(e: clap::Error) -> ! {
+    match e.kind {
+        clap::ErrorKind::HelpDisplayed | clap::ErrorKind::VersionDisplayed => e.exit(),
+        _ => fail(Exit::Parse, e.message.trim_start_matches("error: ")),
+    }
+}
+
+/// Adds bounds from user input to `ops`, exiting if any cannot be parsed.
+pub fn bounds<'a>(ops: ROpBuilder, mut bounds: impl Iterator<Item = &'a str>) -> ROpBuilder {
+    bounds
+        .try_fold(ops, |ops, bound| ops.try_bound(bound))
+        .unwrap_or_else(|e| fail(Exit::Parse, e.message()))
+}
+
+/// Starts a builder with the given named constants.
+pub fn constants(constants: &BTreeMap<String, f64>) -> ROpBuilder {
+    constants
+        .iter()
+        .fold(ROpBuilder::new(), |ops, (name, &value)| {
+            ops.constant(name, value)
+        })
+}
+
+/// The value of the flag `name` if it was given, exiting with a usage error if it is invalid.
+pub fn optional<T: FromStr>(matches: &ArgMatches, name: &str) -> Option<T> {
+    matches
+        .value_of(name)
+        .map(|_| value_t!(matches, name, T).unwrap_or_else(|e| arg_error(e)))
+}
+
+/// The limit on the combinations of a search given by `--max-combinations`, if any.
+pub fn max_combinations(matches: &ArgMatches) -> Option<u64> {
+    optional(matches, "max-combinations")
+}
+
+/// The options of a search given by `--jobs`, `--max-results`, `--time-limit` and
+/// `--memory-limit`, the same whether the problem comes from the arguments or from `--json`.
+pub fn options<'o>(matches: &ArgMatches) -> CalcOptions<'o> {
+    CalcOptions::new().limits(Limits {
+        jobs: value_t!(matches, "jobs", usize).unwrap_or_else(|e| arg_error(e)),
+        max_results: optional(matches, "max-results"),
+        time_limit: optional(matches, "time-limit").map(Duration::from_secs),
+        memory_limit: optional(matches, "memory-limit").map(|mb: usize| mb * 1024 * 1024),
+    })
+}
+
+/// Assembles the problem given by the main arguments, starting from `--preset` if given and
+/// overriding it with the series, bounds and constants on the command line.
+pub fn problem(matches: &ArgMatches) -> Problem {
+    let constants = matches
+        .values_of("constant")
+        .into_iter()
+        .flatten()
+        .map(|constant| {
+            let mut parts = constant.splitn(2, '=');
+            let name = parts.next().unwrap().trim().to_string();
+            match parts.next().map(|v| v.trim().parse()) {
+                Some(Ok(value)) if !name.is_empty() => (name, value),
+                _ => fail(Exit::Parse, &format!("Invalid constant '{}'", constant)),
+            }
+        })
+        .collect();
+    let overrides = Problem {
+        series: match matches.occurrences_of("series") {
+            0 => Vec::new(),
+            _ => names(matches.value_of("series").unwrap()),
+        },
+        bounds: matches
+            .value_of("netlist")
+            .map(|path| load_netlist(path).bounds().to_vec())
+            .into_iter()
+            .flatten()
+            .chain(
+                matches
+                    .values_of("bound")
+                    .into_iter()
+                    .flatten()
+                    .map(str::to_string),
+            )
+            .collect(),
+        constants,
+        top: None,
+        max_combinations: max_combinations(matches),
+    };
+    let base = match matches.value_of("preset") {
+        Some(name) => Problem::preset(name).unwrap_or_else(|e| fail(Exit::from(&e), e.message())),
+        None => Problem::default(),
+    };
+    let mut problem = base.merge(overrides);
+    if problem.series.is_empty() {
+        problem.series = names(matches.value_of("series").unwrap());
+    }
+    problem
+}
+
+fn names(series: &str) -> Vec<String> {
+    series
+        .split(',')
+        .map(|name| name.trim().to_string())
+        .collect()
+}
+
+/// Looks up a comma separated list of series names, exiting if any are unknown.
+pub fn series(names: &str) -> Vec<&'static RSeries> {
+    names
+        .split(',')
+        .map(|name| {
+            RSeries::by_name(name.trim())
+                .unwrap_or_else(|| fail(Exit::Parse, &format!("Unknown series '{}'", name)))
+        })
+        .collect()
+}
+
+/// Parses a comma separated list of values such as `13k,15k,2k`, exiting if any are invalid.
+pub fn values(list: &str) -> Vec<f64> {
+    list.split(',')
+        .map(|v| {
+            parse_value(v.trim())
+                .unwrap_or_else(|| fail(Exit::Parse, &format!("Invalid value '{}'", v)))
+        })
+        .collect()
+}
+
+/// Restricts the positions listed in `--inventory-positions`, or all of them, to the values found
+/// in the `--inventory` BOM files, exiting if any cannot be read.
+pub fn inventory<'a>(
+    matches: &ArgMatches,
+    series: &mut [&'a RSeries],
+    stock: &'a mut Option<RSeries>,
+) {
+    let paths = match matches.values_of("inventory") {
+        Some(paths) => paths,
+        None => return,
+    };
+    let mut values = Vec::new();
+    for path in paths {
+        let text = fs::read_to_string(path)
+            .unwrap_or_else(|e| fail(Exit::Io, &format!("{}: {}", path, e)));
+        let found =
+            bom_values(&text).unwrap_or_else(|e| fail(Exit::Parse, &format!("{}: {}", path, e)));
+        values.extend(found);
+    }
+    values.sort_by(|a, b| a.total_cmp(b));
+    values.dedup();
+    if values.is_empty() {
+        fail(Exit::Parse, "No resistor values found in the inventory");
+    }
+    let stock: &RSeries = stock.get_or_insert(RSeries::inventory("Inventory", &values));
+    let positions: Vec<usize> = match matches.value_of("inventory-positions") {
+        Some(list) => list
+            .split(',')
+            .map(|p| match p.trim().parse() {
+                Ok(p) if (1..=series.len()).contains(&p) => p,
+                _ => fail(Exit::Parse, &format!("Invalid position '{}'", p)),
+            })
+            .collect(),
+        None => (1..=series.len()).collect(),
+    };
+    for p in positions {
+        series[p - 1] = stock;
+    }
+}
+
+/// Evaluates the `--derive NAME=EXPR` constants for a result, exiting if any are invalid.
+pub fn derived(matches: &ArgMatches, op: &ROp, rs: &RSet) -> Vec<(String, f64)> {
+    matches
+        .values_of("derive")
+        .into_iter()
+        .flatten()
+        .map(|derive| {
+            let mut parts = derive.splitn(2, '=');
+            let name = parts.next().unwrap().trim();
+            let valid = name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+                && name.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_');
+            match parts.next() {
+                Some(expr) if valid => match op.evaluate(expr, rs) {
+                    Ok(value) => (name.to_string(), value),
+                    Err(e) => fail(Exit::Parse, e.message()),
+                },
+                _ => fail(
+                    Exit::Parse,
+                    &format!("Invalid derived constant '{}'", derive),
+                ),
+            }
+        })
+        .collect()
+}
+
+/// Prints a `--profile` of a search to stderr, with a line for each bound.
+pub fn print_profile(profile: &Profile) {
+    eprintln!(
+        "Evaluated {} combinations, {} accepted, {} bound evaluations, {} allocations",
+        profile.evaluated, profile.accepted, profile.bound_evaluations, profile.allocations
+    );
+    eprintln!(
+        "Search {:.3}s, sort {:.3}s",
+        profile.search_secs, profile.sort_secs
+    );
+    for bound in &profile.bounds {
+        eprintln!(
+            "{:>12} evaluated {:>12} rejected  {}",
+            bound.evaluations, bound.rejects, bound.bound
+        );
+    }
+}
+
+/// Samples the `--landscape` through `best` over the `--landscape-axes`, exiting if they are not
+/// two different resistors of the calculation.
+pub fn landscape(matches: &ArgMatches, op: &ROp, rcalc: &RCalc, best: &RSet) -> Landscape {
+    let axes: Vec<usize> = matches
+        .value_of("landscape-axes")
+        .unwrap()
+        .split(',')
+        .map(|axis| axis.trim().trim_start_matches('R').parse().unwrap_or(0))
+        .collect();
+    let count = best.values().len();
+    match axes[..] {
+        [x, y] if x != y && (1..=count).contains(&x) && (1..=count).contains(&y) => {
+            op.landscape(rcalc, x, y, best)
+        }
+        _ => fail(
+            Exit::Parse,
+            "The landscape axes must be two different resistors, such as 1,2",
+        ),
+    }
+}
+
+/// Reads the `--prices` table, keeping only the `--package` prices if given, and exiting if it
+/// cannot be read.
+pub fn prices(matches: &ArgMatches) -> Option<PriceTable> {
+    let path = matches.value_of("prices")?;
+    let text =
+        fs::read_to_string(path).unwrap_or_else(|e| fail(Exit::Io, &format!("{}: {}", path, e)));
+    let prices = PriceTable::from_csv(&text)
+        .unwrap_or_else(|e| fail(Exit::Parse, &format!("{}: {}", path, e)));
+    Some(match matches.value_of("package") {
+        Some(package) => prices.package(package),
+        None => prices,
+    })
+}
+
+/// Parses the text of a problem file, as TOML or as JSON if the path ends in `.json`.
+fn parse_problem(path: &str, text: &str) -> Result<Problem, String> {
+    let problem = if path.ends_with(".json") {
+        Problem::from_json(text)
+    } else {
+        Problem::from_toml(text)
+    };
+    problem.map_err(|e| format!("{}: {}", path, e))
+}
+
+/// Reads a problem from a TOML file, or a JSON file if the path ends in `.json`.
+pub fn read_problem(path: &str) -> Result<Problem, String> {
+    let text = fs::read_to_string(path).map_err(|e| format!("{}: {}", path, e))?;
+    parse_problem(path, &text)
+}
+
+/// Reads a netlist file, exiting if it cannot be read or is invalid.
+pub fn load_netlist(path: &str) -> Netlist {
+    let text =
+        fs::read_to_string(path).unwrap_or_else(|e| fail(Exit::Io, &format!("{}: {}", path, e)));
+    Netlist::parse(&text).unwrap_or_else(|e| fail(Exit::Parse, &format!("{}: {}", path, e)))
+}
+
+/// As with `read_problem`, exiting if the problem cannot be read.
+pub fn load_problem(path: &str) -> Problem {
+    let text =
+        fs::read_to_string(path).unwrap_or_else(|e| fail(Exit::Io, &format!("{}: {}", path, e)));
+    parse_problem(path, &text).unwrap_or_else(|e| fail(Exit::Parse, &e))
+}
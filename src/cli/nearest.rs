@@ -0,0 +1,70 @@
+//! The `nearest` subcommand, which finds the closest standard values to an arbitrary value.
+
+use clap::{App, Arg, ArgMatches, SubCommand};
+use resistor_calc::{parallel, parse_value, RCalc, RSeries};
+
+use cli::{fail, series, Exit};
+
+pub fn subcommand<'a, 'b>() -> App<'a, 'b> {
+    SubCommand::with_name("nearest")
+        .about("Finds the closest standard values to a value, alone and as two part composites")
+        .arg(
+            Arg::with_name("value")
+                .required(true)
+                .help("The value to match in ohms, such as 3742 or 3k742"),
+        )
+        .arg(
+            Arg::with_name("series")
+                .long("series")
+                .short("s")
+                .takes_value(true)
+                .default_value("E24")
+                .help("The series to draw values from"),
+        )
+}
+
+fn deviation(actual: f64, target: f64) -> String {
+    format!("{:+.3}%", (actual - target) / target * 100.0)
+}
+
+fn composite(series: &RSeries, target: f64, f: fn(f64, f64) -> f64) -> Option<(f64, f64)> {
+    RCalc::new(vec![series; 2])
+        .calc(|rs| {
+            let (a, b) = (rs.r(1), rs.r(2));
+            if a <= b {
+                Some((f(a, b) - target).abs() / target)
+            } else {
+                None
+            }
+        })
+        .and_then(|res| res.get(0).map(|(_, rs)| (rs.r(1), rs.r(2))))
+}
+
+pub fn run(matches: &ArgMatches) {
+    let value = parse_value(matches.value_of("value").unwrap())
+        .unwrap_or_else(|| fail(Exit::Parse, "Value is not a number"));
+    let series = match series(matches.value_of("series").unwrap())[..] {
+        [series] => series,
+        _ => fail(Exit::Parse, "Exactly one series must be given"),
+    };
+    let (below, above) = series.nearest(value);
+    for (name, found) in &[("Below", below), ("Above", above)] {
+        match *found {
+            Some(v) => println!("{}: {} ({})", name, v, deviation(v, value)),
+            None => println!("{}: none in {}", name, series.name()),
+        }
+    }
+    if let Some((a, b)) = composite(series, value, |a, b| a + b) {
+        println!(
+            "Series: {} + {} = {} ({})",
+            a,
+            b,
+            a + b,
+            deviation(a + b, value)
+        );
+    }
+    if let Some((a, b)) = composite(series, value, parallel) {
+        let r = parallel(a, b);
+        println!("Parallel: {} || {} = {} ({})", a, b, r, deviation(r, value));
+    }
+}
@@ -0,0 +1,169 @@
+//! A line based prompt for exploring large result sets, driven by commands read from stdin.
+//!
+//! This is deliberately not a full screen terminal interface: each command prints its output
+//! below the last, so that the prompt also works through pipes and scripts.
+
+use std::fs;
+use std::io::{self, BufRead, Write};
+
+use resistor_calc::{parse_value, ROp, RRes, RSet};
+
+const PAGE: usize = 10;
+
+const HELP: &str = "Commands:
+  n, next                    show the next page of results
+  p, prev                    show the previous page of results
+  filter <Rn> <min> <max>    only show results with Rn between min and max, such as 1k and 4k7
+  clear                      remove all filters
+  show <idx>                 show the per-bound errors and tolerance analysis of a result
+  export <file> <idx>...     write the given results to a CSV file
+  q, quit                    leave the prompt";
+
+/// Explores the results `res` of the bounds `op` until the user quits or stdin closes.
+pub fn run(res: &RRes, op: &ROp) {
+    let mut filters: Vec<(usize, f64, f64)> = Vec::new();
+    let mut page = 0;
+    let stdin = io::stdin();
+    println!("{} results. Type 'help' for commands.", res.len());
+    print_page(res, &filters, page);
+    prompt();
+    for line in stdin.lock().lines() {
+        let line = match line {
+            Ok(line) => line,
+            Err(_) => break,
+        };
+        let args: Vec<&str> = line.split_whitespace().collect();
+        match args.first().cloned().unwrap_or("n") {
+            "n" | "next" => {
+                if (page + 1) * PAGE < shown(res, &filters) {
+                    page += 1;
+                    print_page(res, &filters, page);
+                } else {
+                    println!("No more results.");
+                }
+            }
+            "p" | "prev" => {
+                page = page.saturating_sub(1);
+                print_page(res, &filters, page);
+            }
+            "filter" => match parse_filter(&args[1..]) {
+                Some(filter) => {
+                    filters.push(filter);
+                    page = 0;
+                    print_page(res, &filters, page);
+                }
+                None => println!("Usage: filter <Rn> <min> <max>"),
+            },
+            "clear" => {
+                filters.clear();
+                page = 0;
+                print_page(res, &filters, page);
+            }
+            "show" => match args.get(1).and_then(|i| i.parse().ok()) {
+                Some(i) if i < res.len() => show(res, op, i),
+                _ => println!("Usage: show <idx>"),
+            },
+            "export" if args.len() > 2 => match export(res, args[1], &args[2..]) {
+                Ok(count) => println!("Wrote {} results to {}", count, args[1]),
+                Err(e) => println!("Export failed: {}", e),
+            },
+            "export" => println!("Usage: export <file> <idx>..."),
+            "q" | "quit" => break,
+            _ => println!("{}", HELP),
+        }
+        prompt();
+    }
+}
+
+fn prompt() {
+    print!("> ");
+    io::stdout().flush().ok();
+}
+
+fn parse_filter(args: &[&str]) -> Option<(usize, f64, f64)> {
+    match *args {
+        [pos, min, max] => {
+            let pos = pos.trim_start_matches(&['R', 'r'][..]).parse().ok()?;
+            Some((pos, parse_value(min)?, parse_value(max)?))
+        }
+        _ => None,
+    }
+}
+
+fn matches(rs: &RSet, filters: &[(usize, f64, f64)]) -> bool {
+    filters.iter().all(
+        |&(pos, min, max)| match rs.values().get(pos.wrapping_sub(1)) {
+            Some(&v) => v >= min && v <= max,
+            None => false,
+        },
+    )
+}
+
+/// The number of results that pass the filters.
+fn shown(res: &RRes, filters: &[(usize, f64, f64)]) -> usize {
+    res.iter().filter(|(_, rs)| matches(rs, filters)).count()
+}
+
+fn print_page(res: &RRes, filters: &[(usize, f64, f64)], page: usize) {
+    let shown: Vec<_> = res
+        .iter()
+        .enumerate()
+        .filter(|(_, (_, rs))| matches(rs, filters))
+        .skip(page * PAGE)
+        .take(PAGE)
+        .collect();
+    if shown.is_empty() {
+        println!("No more results.");
+    }
+    for (idx, (err, rs)) in shown {
+        println!("{:>6}  {:>10.6}  {}", idx, (*err as f64) / 1e9, rs);
+    }
+}
+
+fn show(res: &RRes, op: &ROp, i: usize) {
+    let (err, rs) = res.get(i).unwrap();
+    println!("Error: {:.6}\nValues: {}", (*err as f64) / 1e9, rs);
+    println!("Bounds:");
+    for outcome in op.explain(rs) {
+        println!(
+            "  {}: {} ({}, deviation {})",
+            outcome.bound,
+            outcome.value,
+            if outcome.holds { "holds" } else { "fails" },
+            outcome.deviation
+        );
+    }
+    println!("Worst case over tolerance:");
+    for range in res.worst_case(i, op) {
+        println!(
+            "  {}: {} to {} ({})",
+            range.bound,
+            range.min,
+            range.max,
+            if range.holds { "holds" } else { "fails" }
+        );
+    }
+}
+
+/// Writes the results at the indices `rows` to a CSV file as with `RRes::to_csv`, refusing to
+/// write anything if any index is not that of a result.
+fn export(res: &RRes, path: &str, rows: &[&str]) -> io::Result<usize> {
+    let invalid = |i: &str| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("'{}' is not the index of a result", i),
+        )
+    };
+    let idx = rows
+        .iter()
+        .map(|i| {
+            i.parse()
+                .ok()
+                .filter(|&i| i < res.len())
+                .ok_or_else(|| invalid(i))
+        })
+        .collect::<io::Result<Vec<usize>>>()?;
+    let selected = res.select(&idx).ok_or_else(|| invalid(&rows.join(" ")))?;
+    fs::write(path, selected.to_csv(selected.len()))?;
+    Ok(selected.len())
+}
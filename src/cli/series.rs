@@ -0,0 +1,120 @@
+//! The `series` subcommand, which lists the standard series and the values they contain.
+
+use std::cmp::Ordering;
+
+use clap::{App, AppSettings, Arg, ArgMatches, SubCommand};
+use resistor_calc::{format_value, parse_value, RSeries};
+
+use cli::{fail, Exit};
+
+pub fn subcommand<'a, 'b>() -> App<'a, 'b> {
+    SubCommand::with_name("series")
+        .about("Lists the standard series and shows the values they contain")
+        .setting(AppSettings::SubcommandRequired)
+        .subcommand(SubCommand::with_name("list").about("Lists the standard series"))
+        .subcommand(
+            SubCommand::with_name("show")
+                .about("Shows the values of a series")
+                .arg(
+                    Arg::with_name("name")
+                        .required(true)
+                        .help("The name of the series, such as E96"),
+                )
+                .arg(
+                    Arg::with_name("min")
+                        .long("min")
+                        .takes_value(true)
+                        .help("Only show values of at least this, such as 1k or 10nF"),
+                )
+                .arg(
+                    Arg::with_name("max")
+                        .long("max")
+                        .takes_value(true)
+                        .help("Only show values of at most this, such as 100k"),
+                ),
+        )
+}
+
+fn lookup(name: &str) -> &'static RSeries {
+    RSeries::by_name(name)
+        .unwrap_or_else(|| fail(Exit::Parse, &format!("Unknown series '{}'", name)))
+}
+
+const FARADS: &[(&str, f64)] = &[("p", 1e-12), ("n", 1e-9), ("u", 1e-6), ("µ", 1e-6)];
+
+/// Whether the series holds capacitors, whose values are shown in farads rather than ohms.
+fn is_capacitor(series: &RSeries) -> bool {
+    series.name().ends_with("capacitors")
+}
+
+fn show(series: &RSeries, v: f64) -> String {
+    if !is_capacitor(series) {
+        return format_value(v);
+    }
+    let &(prefix, scale) = FARADS[..3]
+        .iter()
+        .rev()
+        .find(|&&(_, scale)| v >= scale * 0.999)
+        .unwrap_or(&FARADS[0]);
+    format!("{}{}F", (v / scale * 1e3).round() / 1e3, prefix)
+}
+
+fn limit(series: &RSeries, matches: &ArgMatches, name: &str) -> Option<f64> {
+    matches.value_of(name).map(|v| {
+        let value = if is_capacitor(series) {
+            let v = v.trim_end_matches('F');
+            FARADS
+                .iter()
+                .find(|&&(prefix, _)| v.ends_with(prefix))
+                .map_or_else(
+                    || v.parse().ok(),
+                    |&(prefix, scale)| {
+                        v[..v.len() - prefix.len()]
+                            .parse()
+                            .ok()
+                            .map(|x: f64| x * scale)
+                    },
+                )
+        } else {
+            parse_value(v)
+        };
+        value.unwrap_or_else(|| fail(Exit::Parse, &format!("Invalid value '{}'", v)))
+    })
+}
+
+fn sorted(series: &RSeries) -> Vec<f64> {
+    let mut values = series.values().to_vec();
+    values.sort_by(|a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+    values
+}
+
+pub fn run(matches: &ArgMatches) {
+    if matches.subcommand_matches("list").is_some() {
+        println!(
+            "{:<8} {:<16} {:>7} {:>10} {:>10} {:>10}",
+            "Name", "Description", "Values", "Min", "Max", "Tolerance"
+        );
+        for &name in RSeries::names() {
+            let series = lookup(name);
+            let values = sorted(series);
+            println!(
+                "{:<8} {:<16} {:>7} {:>10} {:>10} {:>9}%",
+                name,
+                series.name(),
+                values.len(),
+                values
+                    .first()
+                    .map_or_else(String::new, |&v| show(series, v)),
+                values.last().map_or_else(String::new, |&v| show(series, v)),
+                series.tolerance() * 100.0
+            );
+        }
+    } else if let Some(matches) = matches.subcommand_matches("show") {
+        let series = lookup(matches.value_of("name").unwrap());
+        let min = limit(series, matches, "min").unwrap_or(f64::NEG_INFINITY);
+        let max = limit(series, matches, "max").unwrap_or(f64::INFINITY);
+        for v in sorted(series).into_iter().filter(|&v| v >= min && v <= max) {
+            println!("{}", show(series, v));
+        }
+    }
+}
@@ -0,0 +1,40 @@
+//! The `serve` subcommand, which runs the HTTP service of the `service` feature.
+
+use clap::{App, Arg, ArgMatches, SubCommand};
+
+use cli::{arg_error, fail, Exit};
+
+pub fn subcommand<'a, 'b>() -> App<'a, 'b> {
+    SubCommand::with_name("serve")
+        .about("Serves the solve and analyze endpoints over HTTP, with JSON problems and reports")
+        .arg(
+            Arg::with_name("addr")
+                .long("addr")
+                .takes_value(true)
+                .default_value("127.0.0.1:8080")
+                .help("The address to listen on"),
+        )
+        .arg(
+            Arg::with_name("workers")
+                .long("workers")
+                .takes_value(true)
+                .default_value("4")
+                .help("The number of requests to handle at once"),
+        )
+}
+
+#[cfg(feature = "service")]
+pub fn run(matches: &ArgMatches) {
+    let workers = value_t!(matches, "workers", usize).unwrap_or_else(|e| arg_error(e));
+    let addr = matches.value_of("addr").unwrap();
+    eprintln!("Listening on http://{}", addr);
+    if let Err(e) = resistor_calc::service::serve(addr, workers) {
+        fail(Exit::Io, &format!("{}: {}", addr, e));
+    }
+}
+
+#[cfg(not(feature = "service"))]
+pub fn run(matches: &ArgMatches) {
+    value_t!(matches, "workers", usize).unwrap_or_else(|e| arg_error(e));
+    fail(Exit::Parse, "Serving needs the service feature");
+}
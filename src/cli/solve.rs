@@ -0,0 +1,494 @@
+//! The main command, which solves a problem given by bounds, a netlist, a preset or JSON.
+
+use std::fs;
+use std::io::{self, Read};
+use std::time::Instant;
+
+use clap::{App, Arg, ArgMatches};
+use resistor_calc::{DecadeOrder, Error, Problem, Progress, RCalc, RRes, RSet, Report, Sourcing};
+
+use cli::{self, arg_error, fail, Exit};
+
+/// Adds the arguments of the main command to `app`.
+pub fn args<'a, 'b>(app: App<'a, 'b>) -> App<'a, 'b> {
+    app.arg(
+        Arg::with_name("series")
+            .long("series")
+            .short("s")
+            .takes_value(true)
+            .default_value("E24,E24")
+            .help("Comma separated series to draw R1, R2, ..., Rn from"),
+    )
+    .arg(
+        Arg::with_name("bound")
+            .long("bound")
+            .short("b")
+            .takes_value(true)
+            .multiple(true)
+            .number_of_values(1)
+            .required_unless_one(&["json", "preset", "list-presets", "netlist"])
+            .help("A bound on R1, R2, ..., Rn, such as \"R2 / (R1 + R2) ~ 0.25\""),
+    )
+    .arg(
+        Arg::with_name("netlist")
+            .long("netlist")
+            .takes_value(true)
+            .help("Derive bounds from the targets of a SPICE-like netlist"),
+    )
+    .arg(
+        Arg::with_name("constant")
+            .long("constant")
+            .short("c")
+            .takes_value(true)
+            .multiple(true)
+            .number_of_values(1)
+            .help("A named constant for the bounds, such as \"VREF=1.25\""),
+    )
+    .arg(
+        Arg::with_name("inventory")
+            .long("inventory")
+            .takes_value(true)
+            .multiple(true)
+            .number_of_values(1)
+            .help("Restrict values to those in a KiCad BOM or library CSV"),
+    )
+    .arg(
+        Arg::with_name("inventory-positions")
+            .long("inventory-positions")
+            .takes_value(true)
+            .requires("inventory")
+            .help("The positions to restrict to the inventory, such as 1,3 (default all)"),
+    )
+    .arg(
+        Arg::with_name("preset")
+            .long("preset")
+            .takes_value(true)
+            .help("Start from a named preset in the user config directory"),
+    )
+    .arg(
+        Arg::with_name("list-presets")
+            .long("list-presets")
+            .help("List the available presets"),
+    )
+    .arg(
+        Arg::with_name("prices")
+            .long("prices")
+            .takes_value(true)
+            .help("Annotate results with their cost from a CSV price table"),
+    )
+    .arg(
+        Arg::with_name("package")
+            .long("package")
+            .takes_value(true)
+            .requires("prices")
+            .help("Only use prices for parts in this package, such as 0603"),
+    )
+    .arg(
+        Arg::with_name("quantity")
+            .long("quantity")
+            .takes_value(true)
+            .default_value("1")
+            .help("The number of builds to cost parts for"),
+    )
+    .arg(
+        Arg::with_name("in-stock")
+            .long("in-stock")
+            .requires("prices")
+            .help("Only accept results whose parts are in stock in the price table"),
+    )
+    .arg(
+        Arg::with_name("profile")
+            .long("profile")
+            .help("Print the evaluations and rejections of each bound and the search timings"),
+    )
+    .arg(
+        Arg::with_name("minimise-cost")
+            .long("minimise-cost")
+            .takes_value(true)
+            .requires("prices")
+            .help("Rank by cost the results with at most this error"),
+    )
+    .arg(
+        Arg::with_name("cheapest")
+            .long("cheapest")
+            .requires("prices")
+            .conflicts_with("minimise-cost")
+            .help("Rank the results by cost, with the bounds as hard constraints"),
+    )
+    .arg(
+        Arg::with_name("prefer-decades")
+            .long("prefer-decades")
+            .takes_value(true)
+            .possible_values(&["low", "high"])
+            .help("Show the lowest or highest impedance of results with equal error first"),
+    )
+    .arg(
+        Arg::with_name("top")
+            .long("top")
+            .short("n")
+            .takes_value(true)
+            .default_value("5")
+            .help("The number of results to show"),
+    )
+    .arg(
+        Arg::with_name("format")
+            .long("format")
+            .short("f")
+            .takes_value(true)
+            .possible_values(&["table", "json", "csv"])
+            .default_value("table")
+            .help("The output format"),
+    )
+    .arg(
+        Arg::with_name("jobs")
+            .long("jobs")
+            .short("j")
+            .takes_value(true)
+            .default_value("1")
+            .help("The number of threads to search with"),
+    )
+    .arg(
+        Arg::with_name("max-results")
+            .long("max-results")
+            .takes_value(true)
+            .help("The maximum number of results to keep while searching"),
+    )
+    .arg(
+        Arg::with_name("time-limit")
+            .long("time-limit")
+            .takes_value(true)
+            .help("Stop searching after this many seconds and show the results so far"),
+    )
+    .arg(
+        Arg::with_name("memory-limit")
+            .long("memory-limit")
+            .takes_value(true)
+            .help("The approximate maximum memory in MB to use for results"),
+    )
+    .arg(
+        Arg::with_name("max-combinations")
+            .long("max-combinations")
+            .takes_value(true)
+            .help("Refuse searches with more combinations, suggesting how to shrink them"),
+    )
+    .arg(
+        Arg::with_name("out")
+            .long("out")
+            .short("o")
+            .takes_value(true)
+            .help("Write the results to a .csv, .json, .parquet or .arrow file"),
+    )
+    .arg(
+        Arg::with_name("bom")
+            .long("bom")
+            .takes_value(true)
+            .help("Write a bill of materials for the best result to a CSV file"),
+    )
+    .arg(
+        Arg::with_name("spice")
+            .long("spice")
+            .takes_value(true)
+            .help("Write the best result as SPICE .param lines to a file"),
+    )
+    .arg(
+        Arg::with_name("kicad")
+            .long("kicad")
+            .takes_value(true)
+            .help("Write the best result as a KiCad symbol fields table to a file"),
+    )
+    .arg(
+        Arg::with_name("rust")
+            .long("rust")
+            .takes_value(true)
+            .help("Write the best result as Rust const definitions to a file"),
+    )
+    .arg(
+        Arg::with_name("dot")
+            .long("dot")
+            .takes_value(true)
+            .help("Write a Graphviz graph of the resistors, constants and bounds to a file"),
+    )
+    .arg(
+        Arg::with_name("landscape")
+            .long("landscape")
+            .takes_value(true)
+            .help("Write the error over the values of two resistors to a .svg or .csv file"),
+    )
+    .arg(
+        Arg::with_name("landscape-axes")
+            .long("landscape-axes")
+            .takes_value(true)
+            .default_value("1,2")
+            .help("The resistors to vary for --landscape, holding the rest at the best result"),
+    )
+    .arg(
+        Arg::with_name("derive")
+            .long("derive")
+            .takes_value(true)
+            .multiple(true)
+            .number_of_values(1)
+            .requires("rust")
+            .help("A constant to derive for --rust, such as \"ADC_SCALE=R2 / (R1 + R2)\""),
+    )
+    .arg(
+        Arg::with_name("progress")
+            .long("progress")
+            .short("p")
+            .help("Show a progress bar on stderr while searching"),
+    )
+    .arg(
+        Arg::with_name("repl")
+            .long("repl")
+            .help("Explore the results with commands typed at a prompt"),
+    )
+    .arg(
+        Arg::with_name("json")
+            .long("json")
+            .help("Read the problem as JSON from stdin and write the results as JSON"),
+    )
+}
+
+fn write_file(path: &str, contents: &str) {
+    fs::write(path, contents)
+        .unwrap_or_else(|e| fail(Exit::Io, &format!("Writing {}: {}", path, e)));
+}
+
+/// Returns true if `path` names a Parquet or Arrow file, which hold all of the results.
+fn columnar(path: &str) -> bool {
+    [".parquet", ".arrow", ".feather"]
+        .iter()
+        .any(|ext| path.ends_with(ext))
+}
+
+#[cfg(feature = "columnar")]
+fn write_columnar(path: &str, res: &RRes) {
+    let file =
+        fs::File::create(path).unwrap_or_else(|e| fail(Exit::Io, &format!("{}: {}", path, e)));
+    let written = if path.ends_with(".parquet") {
+        res.write_parquet(file)
+    } else {
+        res.write_arrow(file)
+    };
+    written.unwrap_or_else(|e| fail(Exit::Io, &format!("Writing {}: {}", path, e)));
+}
+
+#[cfg(not(feature = "columnar"))]
+fn write_columnar(path: &str, _res: &RRes) {
+    fail(
+        Exit::Parse,
+        &format!("Writing {} needs the columnar feature", path),
+    );
+}
+
+fn draw_progress(p: &Progress) {
+    const WIDTH: usize = 30;
+    let filled = (p.evaluated * WIDTH as u128 / p.total.max(1)) as usize;
+    let best = p
+        .best
+        .map_or_else(|| "-".to_string(), |b| format!("{:.3}", b));
+    eprint!(
+        "\r[{}{}] {}/{} evaluated, {} accepted, best {}",
+        "#".repeat(filled),
+        " ".repeat(WIDTH - filled),
+        p.evaluated,
+        p.total,
+        p.accepted,
+        best
+    );
+}
+
+pub fn run(matches: &ArgMatches) {
+    if matches.is_present("json") {
+        let mut input = String::new();
+        io::stdin()
+            .read_to_string(&mut input)
+            .unwrap_or_else(|e| fail(Exit::Io, &e.to_string()));
+        let report = Problem::from_json(&input)
+            .and_then(|mut problem| {
+                problem.max_combinations =
+                    cli::max_combinations(matches).or(problem.max_combinations);
+                problem.solve_with(cli::options(matches))
+            })
+            .unwrap_or_else(|e| fail(Exit::from(&e), e.message()));
+        println!("{}", report.to_json());
+        if report.results.is_empty() {
+            fail(Exit::NoSolution, "No values satisfy requirements");
+        }
+        return;
+    }
+
+    if matches.is_present("list-presets") {
+        for name in Problem::presets() {
+            println!("{}", name);
+        }
+        return;
+    }
+
+    let problem = cli::problem(matches);
+    let mut stock = None;
+    let mut series = cli::series(&problem.series.join(","));
+    cli::inventory(matches, &mut series, &mut stock);
+    let top = match problem.top {
+        Some(top) if matches.occurrences_of("top") == 0 => top,
+        _ => value_t!(matches, "top", usize).unwrap_or_else(|e| arg_error(e)),
+    };
+    let ops = cli::constants(&problem.constants);
+    let ops = cli::bounds(ops, problem.bounds.iter().map(String::as_str));
+    let rcalc = RCalc::new(series);
+    let prices = cli::prices(matches);
+    let quantity = value_t!(matches, "quantity", u64).unwrap_or_else(|e| arg_error(e));
+    let max_error = if matches.is_present("minimise-cost") {
+        Some(value_t!(matches, "minimise-cost", f64).unwrap_or_else(|e| arg_error(e)))
+    } else {
+        None
+    };
+
+    let show = matches.is_present("progress");
+    let op = ops.build();
+    if let Err(e @ Error::Eval(_)) = rcalc.validate(&op) {
+        fail(Exit::Parse, e.message());
+    }
+    if let Some(path) = matches.value_of("dot") {
+        write_file(path, &op.to_dot(&rcalc));
+    }
+    let sourcing = prices
+        .as_ref()
+        .filter(|_| matches.is_present("in-stock"))
+        .map(|prices| Sourcing::new(prices, &rcalc, None, quantity));
+    let available = |rs: &RSet| sourcing.as_ref().map_or(true, |s| s.available(rs));
+    if let Some(max) = problem.max_combinations {
+        if let Err(guard) = rcalc.guard(|rs| op.eval(rs).filter(|_| available(rs)), u128::from(max))
+        {
+            fail(Exit::TooLarge, &guard.to_string());
+        }
+    }
+    // Scores a combination from the error the bounds gave it, so that a profiled search ranks
+    // the same way as a plain one.
+    let score = |rs: &RSet, err: Option<f64>| {
+        let err = err.filter(|_| available(rs));
+        match (&prices, max_error) {
+            (Some(prices), Some(max_error)) => {
+                prices.minimise_cost(|_| err, max_error, quantity)(rs)
+            }
+            _ => err,
+        }
+    };
+    let options = cli::options(matches).progress(|p| {
+        if show {
+            draw_progress(p)
+        }
+    });
+    let start = Instant::now();
+    let res = if matches.is_present("profile") {
+        let (res, profile) = rcalc.calc_profiled_with(&op, score, options);
+        cli::print_profile(&profile);
+        res
+    } else {
+        rcalc.calc_with(|rs| score(rs, op.eval(rs)), options)
+    };
+    let mut res = res.unwrap_or_else(|_| fail(Exit::NoSolution, "No values satisfy requirements"));
+    let elapsed = start.elapsed().as_secs_f64();
+    if show {
+        eprintln!();
+    }
+    match matches.value_of("prefer-decades") {
+        Some("low") => res.order_decades(DecadeOrder::Lower),
+        Some(_) => res.order_decades(DecadeOrder::Higher),
+        None => (),
+    }
+    let res = match prices.as_ref().filter(|_| matches.is_present("cheapest")) {
+        Some(prices) => prices.cheapest(&res, quantity).unwrap_or_else(|| {
+            fail(
+                Exit::NoSolution,
+                "None of the results are in the price table",
+            )
+        }),
+        None => res,
+    };
+
+    if let Some(path) = matches.value_of("out").filter(|path| columnar(path)) {
+        write_columnar(path, &res);
+    } else if let Some(path) = matches.value_of("out") {
+        let out = if path.ends_with(".json") {
+            res.to_json(top)
+        } else {
+            res.to_csv(top)
+        };
+        write_file(path, &out);
+    }
+    for &(flag, export) in &[
+        ("bom", RRes::to_bom as fn(&RRes, usize) -> String),
+        ("spice", RRes::to_spice),
+        ("kicad", RRes::to_kicad),
+    ] {
+        if let Some(path) = matches.value_of(flag) {
+            write_file(path, &export(&res, 0));
+        }
+    }
+    if let Some(path) = matches.value_of("rust") {
+        let derived = cli::derived(matches, &op, &res.get(0).unwrap().1);
+        let derived: Vec<(&str, f64)> = derived.iter().map(|(n, v)| (n.as_str(), *v)).collect();
+        write_file(path, &res.to_rust(0, &derived));
+    }
+
+    if let Some(path) = matches.value_of("landscape") {
+        let land = cli::landscape(matches, &op, &rcalc, &res.get(0).unwrap().1);
+        let out = if path.ends_with(".csv") {
+            land.to_csv()
+        } else {
+            land.to_svg()
+        };
+        write_file(path, &out);
+    }
+
+    if matches.is_present("repl") {
+        cli::repl::run(&res, &op);
+        return;
+    }
+
+    let quiet = cli::quiet();
+    let format = matches.value_of("format").unwrap();
+    let cost = |rs: &RSet| prices.as_ref().map(|prices| prices.cost(rs, quantity));
+    if format == "json" {
+        let mut report = Report::from_res(&rcalc, &op, &res, elapsed);
+        if let Some(ref prices) = prices {
+            report = report.with_sourcing(&Sourcing::new(prices, &rcalc, None, quantity));
+        }
+        report.results.truncate(top);
+        println!("{}", report.to_json());
+    } else if format == "csv" {
+        let count = res.get(0).map_or(0, |(_, rs)| rs.values().len());
+        let mut header: Vec<String> = (1..=count).map(|i| format!("R{}", i)).collect();
+        if prices.is_some() {
+            header.push("cost".to_string());
+        }
+        println!("error,{}", header.join(","));
+    } else if !quiet {
+        println!("Number of combinations: {}", rcalc.combinations());
+    }
+    for (idx, (err, rs)) in res.iter().take(top).enumerate() {
+        let err = (*err as f64) / 1e9;
+        if format == "json" {
+            break;
+        } else if format == "csv" {
+            let mut values: Vec<String> = rs.values().iter().map(|r| r.to_string()).collect();
+            if let Some(cost) = cost(rs) {
+                values.push(cost.map_or_else(String::new, |c| c.to_string()));
+            }
+            println!("{},{}", err, values.join(","));
+        } else {
+            println!("Match {}:\nError: {:.3}\nValues: {}", idx + 1, err, rs);
+            match cost(rs) {
+                Some(Some(cost)) => println!("Cost: {:.4}\n", cost),
+                Some(None) => println!("Cost: not in the price table\n"),
+                None => println!(),
+            }
+        }
+    }
+    if res.is_partial() {
+        fail(
+            Exit::Timeout,
+            "Time limit reached, results are from a partial search",
+        );
+    }
+}
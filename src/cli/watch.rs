@@ -0,0 +1,75 @@
+//! The `watch` subcommand, which re-solves a problem file whenever it changes.
+
+use std::fs;
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+use clap::{App, Arg, ArgMatches, SubCommand};
+use resistor_calc::{format_value, ReportMatch};
+
+use cli::{arg_error, read_problem};
+
+/// How often the problem file is checked for changes.
+const POLL: Duration = Duration::from_millis(500);
+
+pub fn subcommand<'a, 'b>() -> App<'a, 'b> {
+    SubCommand::with_name("watch")
+        .about("Re-solves a problem file whenever it changes, showing how the results moved")
+        .arg(
+            Arg::with_name("problem")
+                .required(true)
+                .help("A problem file in TOML, or JSON if it ends in .json"),
+        )
+        .arg(
+            Arg::with_name("top")
+                .long("top")
+                .short("n")
+                .takes_value(true)
+                .default_value("5")
+                .help("The number of results to compare between runs"),
+        )
+}
+
+fn describe(m: &ReportMatch) -> String {
+    let values: Vec<String> = m.values.iter().map(|&r| format_value(r)).collect();
+    format!("{:.6}  {}", m.error, values.join(", "))
+}
+
+pub fn run(matches: &ArgMatches) {
+    let path = matches.value_of("problem").unwrap();
+    let top = value_t!(matches, "top", usize).unwrap_or_else(|e| arg_error(e));
+    let mut last_modified: Option<SystemTime> = None;
+    let mut previous: Vec<String> = Vec::new();
+    let mut run = 0;
+    loop {
+        let modified = fs::metadata(path).and_then(|m| m.modified()).ok();
+        if modified.is_some() && modified != last_modified {
+            last_modified = modified;
+            run += 1;
+            match read_problem(path).and_then(|p| p.solve().map_err(String::from)) {
+                Ok(report) => {
+                    let current: Vec<String> =
+                        report.results.iter().take(top).map(describe).collect();
+                    println!("== Run {} ({:.2}s)", run, report.elapsed);
+                    if current.is_empty() {
+                        println!("  No values satisfy requirements");
+                    }
+                    for line in &current {
+                        let mark = if run == 1 || previous.contains(line) {
+                            ' '
+                        } else {
+                            '+'
+                        };
+                        println!("{} {}", mark, line);
+                    }
+                    for line in previous.iter().filter(|l| !current.contains(l)) {
+                        println!("- {}", line);
+                    }
+                    previous = current;
+                }
+                Err(e) => println!("== Run {} failed: {}", run, e),
+            }
+        }
+        thread::sleep(POLL);
+    }
+}
@@ -0,0 +1,45 @@
+use itertools::Itertools;
+
+use std::collections::HashSet;
+
+use {_print_r, RRes};
+
+const RESET: &str = "\x1b[0m";
+const BOLD_GREEN: &str = "\x1b[1;32m";
+const YELLOW: &str = "\x1b[33m";
+const DIM: &str = "\x1b[2m";
+
+impl RRes {
+    /// Print all combinations that share the lowest error value, as with `print_best`, using ANSI
+    /// colours. Zero error matches are highlighted in green, values that sit at either end of
+    /// their series are shown in yellow and matches that are only a decade multiple of an earlier
+    /// match are dimmed.
+    pub fn print_best_coloured(&self) {
+        let mut seen = HashSet::new();
+        for (idx, &(err, ref rs)) in self.best().enumerate() {
+            let dup = !seen.insert(rs.decade_key());
+            let (pre, post) = if dup { (DIM, RESET) } else { ("", "") };
+            println!("{}Match {}:", pre, idx + 1);
+            let err = (err as f64) / 1e9;
+            if err == 0.0 && !dup {
+                println!("Error: {}{:.3}{}", BOLD_GREEN, err, RESET);
+            } else {
+                println!("Error: {:.3}", err);
+            }
+            let values =
+                rs.0.iter()
+                    .zip(self.pos.iter())
+                    .enumerate()
+                    .map(|(i, (r, pos))| {
+                        if !dup && (*r == pos.min || *r == pos.max) {
+                            format!("R{}: {}{}{}", i + 1, YELLOW, _print_r(r), RESET)
+                        } else {
+                            format!("R{}: {}", i + 1, _print_r(r))
+                        }
+                    })
+                    .join(", ");
+            println!("Values: {}{}", values, post);
+            println!();
+        }
+    }
+}
@@ -0,0 +1,109 @@
+extern crate arrow_array;
+extern crate arrow_ipc;
+extern crate arrow_schema;
+extern crate parquet;
+
+use std::collections::HashMap;
+use std::io::Write;
+use std::sync::Arc;
+
+use self::arrow_array::{ArrayRef, Float64Array, RecordBatch};
+use self::arrow_ipc::writer::FileWriter;
+use self::arrow_schema::{DataType, Field, Schema};
+use self::parquet::arrow::ArrowWriter;
+
+use {Error, RRes};
+
+/// The number of results written in each record batch, so that large result sets are converted a
+/// part at a time.
+const BATCH_ROWS: usize = 64 * 1024;
+
+impl RRes {
+    /// The Arrow schema of the results: an `error` column followed by a column for the value of
+    /// each resistor, `R1` onwards, with the series, tolerance and range of the position in the
+    /// metadata of its field.
+    pub fn arrow_schema(&self) -> Schema {
+        let mut fields = vec![Field::new("error", DataType::Float64, false)];
+        for (i, pos) in self.pos.iter().enumerate() {
+            let metadata: HashMap<String, String> = [
+                ("series", pos.series.clone()),
+                ("tolerance", pos.tolerance.to_string()),
+                ("min", pos.min.to_string()),
+                ("max", pos.max.to_string()),
+            ]
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.clone()))
+            .collect();
+            fields.push(
+                Field::new(format!("R{}", i + 1), DataType::Float64, false).with_metadata(metadata),
+            );
+        }
+        Schema::new(fields)
+    }
+
+    /// Converts all of the results to Arrow record batches of up to 65536 rows each, with the
+    /// columns of `arrow_schema`.
+    /// # Example
+    /// ```
+    ///     # use resistor_calc::*;
+    ///     let res = RCalc::e24(2)
+    ///         .calc(ROpBuilder::new().bound("R2 / (R1 + R2) ~ 0.25").finish())
+    ///         .unwrap();
+    ///     let batches = res.to_record_batches();
+    ///     let rows: usize = batches.iter().map(|b| b.num_rows()).sum();
+    ///     assert_eq!(rows, res.len());
+    ///     assert_eq!(batches[0].num_columns(), 3);
+    /// ```
+    pub fn to_record_batches(&self) -> Vec<RecordBatch> {
+        let schema = Arc::new(self.arrow_schema());
+        self.res
+            .chunks(BATCH_ROWS)
+            .map(|chunk| {
+                let mut columns: Vec<ArrayRef> = vec![Arc::new(Float64Array::from_iter_values(
+                    chunk.iter().map(|(err, _)| *err as f64 / 1e9),
+                ))];
+                for i in 0..self.pos.len() {
+                    columns.push(Arc::new(Float64Array::from_iter_values(
+                        chunk.iter().map(|(_, rs)| rs.0[i]),
+                    )));
+                }
+                RecordBatch::try_new(schema.clone(), columns).unwrap()
+            })
+            .collect()
+    }
+
+    /// Writes all of the results as a Parquet file, with the columns of `arrow_schema`, to be
+    /// read with pandas, Polars or any other Parquet reader.
+    /// # Example
+    /// ```
+    ///     # use resistor_calc::*;
+    ///     let res = RCalc::e24(2)
+    ///         .calc(ROpBuilder::new().bound("R2 / (R1 + R2) ~ 0.25").finish())
+    ///         .unwrap();
+    ///     let mut out = Vec::new();
+    ///     res.write_parquet(&mut out).unwrap();
+    ///     assert_eq!(&out[..4], b"PAR1");
+    /// ```
+    pub fn write_parquet<W: Write + Send>(&self, out: W) -> Result<(), Error> {
+        let mut writer = ArrowWriter::try_new(out, Arc::new(self.arrow_schema()), None)
+            .map_err(|e| Error::Io(e.to_string()))?;
+        for batch in self.to_record_batches() {
+            writer.write(&batch).map_err(|e| Error::Io(e.to_string()))?;
+        }
+        writer
+            .close()
+            .map(|_| ())
+            .map_err(|e| Error::Io(e.to_string()))
+    }
+
+    /// Writes all of the results as an Arrow IPC file, also known as Feather, with the columns of
+    /// `arrow_schema`.
+    pub fn write_arrow<W: Write>(&self, out: W) -> Result<(), Error> {
+        let mut writer =
+            FileWriter::try_new(out, &self.arrow_schema()).map_err(|e| Error::Io(e.to_string()))?;
+        for batch in self.to_record_batches() {
+            writer.write(&batch).map_err(|e| Error::Io(e.to_string()))?;
+        }
+        writer.finish().map_err(|e| Error::Io(e.to_string()))
+    }
+}
@@ -0,0 +1,172 @@
+use std::fmt::Write;
+
+use {RCalc, ROp};
+
+/// The names of the variables and functions used in an expression, in order of first use, with
+/// whether each is called as a function.
+fn identifiers(expr: &str) -> Vec<(String, bool)> {
+    let mut idents: Vec<(String, bool)> = Vec::new();
+    let chars: Vec<char> = expr.chars().collect();
+    let mut i = 0;
+    while i < chars.len() {
+        let prev = if i > 0 { chars[i - 1] } else { ' ' };
+        let starts = chars[i].is_alphabetic() || chars[i] == '_';
+        // Letters within numbers, as in `1e4`, are not identifiers.
+        if starts && !(prev.is_alphanumeric() || prev == '_' || prev == '.') {
+            let start = i;
+            while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                i += 1;
+            }
+            let name: String = chars[start..i].iter().collect();
+            let call = chars[i..].iter().find(|c| !c.is_whitespace()) == Some(&'(');
+            if !idents.iter().any(|(n, _)| *n == name) {
+                idents.push((name, call));
+            }
+        } else {
+            i += 1;
+        }
+    }
+    idents
+}
+
+/// Quotes a label for DOT.
+fn quote(s: &str) -> String {
+    format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// The position numbered by a name such as `R3` or `P3`, if it has that `prefix`.
+fn position(name: &str, prefix: char) -> Option<usize> {
+    name.strip_prefix(prefix)
+        .and_then(|n| n.parse::<usize>().ok())
+        .filter(|&n| n > 0)
+}
+
+impl ROp {
+    /// Exports the problem as a Graphviz DOT graph for reviewing large sets of bounds. Each
+    /// resistor of `rcalc` is a box labelled with its series, each constant an ellipse with its
+    /// value and each bound a note, with edges from the variables to the bounds that use them.
+    /// Power (`P1`, ...) and chain current (`I_chain`) variables are diamonds fed by the
+    /// resistors they derive from. Resistors that no bound uses, and variables that are not
+    /// defined, are drawn in red. Render with `dot -Tsvg problem.dot -o problem.svg`.
+    /// # Example
+    /// ```
+    ///     # use resistor_calc::*;
+    ///     let op = ROpBuilder::new()
+    ///         .constant("VREF", 0.8)
+    ///         .bound("R1 + R2 <= 1e6")
+    ///         .bound("VREF * (1 + R1 / R2) ~ 5.0")
+    ///         .build();
+    ///     let dot = op.to_dot(&RCalc::e24(3));
+    ///     assert!(dot.starts_with("digraph problem {"));
+    ///     assert!(dot.contains("\"VREF\" -> \"bound2\""));
+    ///     assert!(dot.contains("\"R3\" [shape=box, label=\"R3\\nE24\", color=red]"));
+    /// ```
+    pub fn to_dot(&self, rcalc: &RCalc) -> String {
+        let uses: Vec<Vec<String>> = self
+            .bounds()
+            .iter()
+            .map(|bound| {
+                identifiers(bound)
+                    .into_iter()
+                    .filter(|&(ref name, call)| !call && name != "pi" && name != "e")
+                    .map(|(name, _)| name)
+                    .collect()
+            })
+            .collect();
+        let used = |name: &str| uses.iter().flatten().any(|n| n == name);
+        let count = rcalc.rs.len();
+
+        let mut out = String::from("digraph problem {\n    rankdir=LR;\n");
+        for (i, series) in rcalc.rs.iter().enumerate() {
+            let name = format!("R{}", i + 1);
+            let label = format!("{}\n{}", name, series.name());
+            let derived = used(&format!("P{}", i + 1)) || used("I_chain");
+            writeln!(
+                out,
+                "    {} [shape=box, label={}{}];",
+                quote(&name),
+                quote(&label).replace('\n', "\\n"),
+                if used(&name) || derived {
+                    ""
+                } else {
+                    ", color=red"
+                }
+            )
+            .unwrap();
+        }
+        for &(ref name, value) in self.constants() {
+            let label = format!("{} = {}", name, value);
+            writeln!(
+                out,
+                "    {} [shape=ellipse, label={}];",
+                quote(name),
+                quote(&label)
+            )
+            .unwrap();
+        }
+
+        let mut derived: Vec<&str> = Vec::new();
+        for name in uses.iter().flatten() {
+            if derived.contains(&name.as_str()) || self.constants().iter().any(|c| c.0 == *name) {
+                continue;
+            }
+            derived.push(name);
+            let feeds: Vec<usize> = if let Some(n) = position(name, 'R') {
+                if n <= count {
+                    continue;
+                }
+                vec![]
+            } else if let Some(n) = position(name, 'P') {
+                vec![n].into_iter().filter(|&n| n <= count).collect()
+            } else if name == "I_chain" {
+                (1..=count).collect()
+            } else {
+                vec![]
+            };
+            let shape = if position(name, 'R').is_some() {
+                "box"
+            } else {
+                "diamond"
+            };
+            writeln!(
+                out,
+                "    {} [shape={}{}];",
+                quote(name),
+                shape,
+                if feeds.is_empty() { ", color=red" } else { "" }
+            )
+            .unwrap();
+            for n in feeds {
+                writeln!(
+                    out,
+                    "    {} -> {} [style=dashed];",
+                    quote(&format!("R{}", n)),
+                    quote(name)
+                )
+                .unwrap();
+            }
+        }
+
+        for (idx, (bound, vars)) in self.bounds().iter().zip(&uses).enumerate() {
+            let id = quote(&format!("bound{}", idx + 1));
+            let colour = if self.is_soft(idx) {
+                "lightblue"
+            } else {
+                "mistyrose"
+            };
+            writeln!(
+                out,
+                "    {} [shape=note, style=filled, fillcolor={}, label={}];",
+                id,
+                colour,
+                quote(bound)
+            )
+            .unwrap();
+            for var in vars {
+                writeln!(out, "    {} -> {};", quote(var), id).unwrap();
+            }
+        }
+        out.push_str("}\n");
+        out
+    }
+}
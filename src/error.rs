@@ -0,0 +1,72 @@
+use alloc::string::String;
+use core::fmt;
+
+/// The errors returned by the fallible parts of the crate: parsing bounds, problems and files,
+/// evaluating expressions, solving and reading or writing files. Each carries a description
+/// suitable for showing to the user.
+///
+/// Functions that take user input have `try_` variants, or return `Result`, so that mistakes in
+/// the input are reported rather than panicking. A `String` can be made from an `Error`, so `?`
+/// also works in functions that report errors as strings.
+/// # Example
+/// ```
+///     # use resistor_calc::*;
+///     let err = ROpBuilder::new().try_bound("R1 + R2").err().unwrap();
+///     assert!(matches!(err, Error::Parse(_)));
+///     println!("{}", err);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub enum Error {
+    /// A bound, expression, value, problem or file could not be understood.
+    Parse(String),
+    /// An expression could not be evaluated, such as one using an unknown variable or function.
+    Eval(String),
+    /// The calculation could not be done or found no solution.
+    Solver(String),
+    /// A file or network connection could not be read or written.
+    Io(String),
+    /// A search was refused for having more combinations than allowed, with suggestions for
+    /// shrinking it.
+    TooLarge(String),
+}
+
+impl Error {
+    /// The description of the error, without its kind.
+    pub fn message(&self) -> &str {
+        match *self {
+            Error::Parse(ref msg)
+            | Error::Eval(ref msg)
+            | Error::Solver(ref msg)
+            | Error::Io(ref msg)
+            | Error::TooLarge(ref msg) => msg,
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.message())
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Error {}
+
+#[cfg(feature = "std")]
+impl From<std::io::Error> for Error {
+    fn from(e: std::io::Error) -> Self {
+        Error::Io(e.to_string())
+    }
+}
+
+impl From<Error> for String {
+    fn from(e: Error) -> Self {
+        match e {
+            Error::Parse(msg)
+            | Error::Eval(msg)
+            | Error::Solver(msg)
+            | Error::Io(msg)
+            | Error::TooLarge(msg) => msg,
+        }
+    }
+}
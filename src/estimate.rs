@@ -0,0 +1,149 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+use rand::Rng;
+
+use {Inline, Limits, RCalc, RSet, Stopwatch};
+
+/// A suggestion to restrict the values searched for one position, from `RCalc::estimate`.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub struct Suggestion {
+    /// The position to restrict, counting from 1 as with R1, R2, ..., Rn.
+    pub position: usize,
+    /// The lowest value seen in a sampled combination that satisfied the bounds.
+    pub min: f64,
+    /// The highest value seen in a sampled combination that satisfied the bounds.
+    pub max: f64,
+    /// The factor by which restricting the position to `min`..=`max` shrinks the search.
+    pub reduction: f64,
+}
+
+/// A prediction of the cost of a search, produced by `RCalc::estimate`.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub struct Estimate {
+    /// The number of combinations the search will evaluate.
+    pub combinations: u128,
+    /// The measured time to evaluate a single combination.
+    pub per_combination: Duration,
+    /// The predicted time for the whole search, or `Duration::MAX` if it is longer than that.
+    pub runtime: Duration,
+    /// The fraction of sampled combinations that satisfied the bounds.
+    pub acceptance: f64,
+    /// Positions whose range could be restricted to shrink the search, most effective first.
+    pub suggestions: Vec<Suggestion>,
+}
+
+impl<'a> RCalc<'a> {
+    /// Estimates the cost of searching with `f` without running the search, by timing the first
+    /// `samples` combinations of the search itself and evaluating `f` on `samples` randomly chosen
+    /// combinations. The values seen in sampled combinations that satisfied the bounds are used to
+    /// suggest narrower ranges for each position. As these are only based on a sample, the
+    /// suggestions may exclude some valid combinations.
+    /// # Example
+    /// ```
+    ///     # use resistor_calc::*;
+    ///     let rcalc = RCalc::e24(3);
+    ///     let estimate = rcalc.estimate(
+    ///         ROpBuilder::new()
+    ///             .bound("R1 + R2 + R3 <= 1e4")
+    ///             .bound("R2 / (R1 + R2 + R3) ~ 0.25")
+    ///             .finish(),
+    ///         2000,
+    ///     );
+    ///     println!("About {:?} for {} combinations", estimate.runtime, estimate.combinations);
+    ///     for s in estimate.suggestions {
+    ///         println!("Restricting R{} to {}..{} reduces the space {:.0}x",
+    ///             s.position, s.min, s.max, s.reduction);
+    ///     }
+    ///     // A position left with no values has nothing to search.
+    ///     let empty = RCalc::e24(2).range(1, 0.0, 0.5).estimate(|_| Some(0.0), 100);
+    ///     assert_eq!(empty.runtime, std::time::Duration::ZERO);
+    /// ```
+    pub fn estimate(&self, f: impl Fn(&RSet) -> Option<f64> + Sync, samples: usize) -> Estimate {
+        let combinations = self.combinations();
+        if combinations == 0 {
+            return Estimate {
+                combinations,
+                per_combination: Duration::ZERO,
+                runtime: Duration::ZERO,
+                acceptance: 0.0,
+                suggestions: Vec::new(),
+            };
+        }
+
+        // Time a slice of the real search, so that the estimate includes its own overheads.
+        let evaluated = AtomicUsize::new(0);
+        let start = Stopwatch::start();
+        self.search(
+            |rs| {
+                evaluated.fetch_add(1, Ordering::Relaxed);
+                f(rs)
+            },
+            &Limits::default(),
+            &Inline,
+            1,
+            &|| evaluated.load(Ordering::Relaxed) >= samples,
+            |_| (),
+        );
+        let secs = start.secs() / evaluated.into_inner().max(1) as f64;
+        let per_combination = Duration::from_secs_f64(secs);
+
+        let values: Vec<Vec<f64>> = self
+            .rs
+            .iter()
+            .map(|r| r.iter().cloned().collect())
+            .collect();
+        let mut rng = rand::thread_rng();
+        let mut ranges = vec![(f64::INFINITY, f64::NEG_INFINITY); values.len()];
+        let mut accepted = 0;
+        for _ in 0..samples {
+            let sample: Vec<f64> = values
+                .iter()
+                .map(|vals| vals[rng.gen_range(0..vals.len())])
+                .collect();
+            let rs = RSet(sample.into_boxed_slice());
+            if f(&rs).is_some() {
+                accepted += 1;
+                for (range, &v) in ranges.iter_mut().zip(rs.0.iter()) {
+                    *range = (range.0.min(v), range.1.max(v));
+                }
+            }
+        }
+        debug!(
+            "Searched at {:.3}us per combination, sampled {} combinations, {} accepted",
+            secs * 1e6,
+            samples,
+            accepted
+        );
+        let mut suggestions: Vec<Suggestion> = if accepted > 0 {
+            ranges
+                .iter()
+                .zip(values.iter())
+                .enumerate()
+                .map(|(idx, (&(min, max), vals))| {
+                    let kept = vals.iter().filter(|&&v| v >= min && v <= max).count();
+                    Suggestion {
+                        position: idx + 1,
+                        min,
+                        max,
+                        reduction: vals.len() as f64 / kept as f64,
+                    }
+                })
+                .filter(|s| s.reduction > 1.0)
+                .collect()
+        } else {
+            Vec::new()
+        };
+        suggestions.sort_by(|a, b| b.reduction.total_cmp(&a.reduction));
+        Estimate {
+            combinations,
+            per_combination,
+            runtime: Duration::try_from_secs_f64(secs * combinations as f64)
+                .unwrap_or(Duration::MAX),
+            acceptance: accepted as f64 / samples.max(1) as f64,
+            suggestions,
+        }
+    }
+}
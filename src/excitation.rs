@@ -0,0 +1,212 @@
+extern crate meval;
+
+#[cfg(feature = "serialize")]
+use std::convert::TryFrom;
+
+use itertools::Itertools;
+
+use expr_builder::with_context;
+use {BoundOutcome, Error, ROp, RRes, RSet};
+
+/// Describes the voltage across each resistor of a network as an expression of R1, R2, ..., Rn,
+/// allowing the power dissipated in each resistor to be calculated.
+#[derive(Default, Clone)]
+#[cfg_attr(
+    feature = "serialize",
+    derive(Serialize, Deserialize),
+    serde(into = "Vec<Option<String>>", try_from = "Vec<Option<String>>")
+)]
+pub struct Excitation {
+    across: Vec<Option<(String, meval::Expr)>>,
+}
+
+impl Excitation {
+    /// Init a new excitation, with no voltage across any resistor.
+    pub fn new() -> Self {
+        Excitation { across: Vec::new() }
+    }
+
+    /// Declares the voltage across R{idx} as a math expression using R1,...,Rn.
+    /// # Example
+    /// ```
+    ///     # use resistor_calc::*;
+    ///     let exc = Excitation::new()
+    ///         .across(1, "12 * R1 / (R1 + R2)")
+    ///         .across(2, "12 * R2 / (R1 + R2)");
+    /// ```
+    pub fn across(self, idx: usize, expr: &str) -> Self {
+        self.try_across(idx, expr).unwrap()
+    }
+
+    /// As with `across`, but returning an error rather than panicking if `idx` is not a position,
+    /// counting from 1, or `expr` cannot be parsed.
+    /// # Example
+    /// ```
+    ///     # use resistor_calc::*;
+    ///     assert!(Excitation::new().try_across(1, "12 * R1 / (R1 + R2)").is_ok());
+    ///     assert!(matches!(Excitation::new().try_across(0, "12"), Err(Error::Parse(_))));
+    ///     assert!(matches!(Excitation::new().try_across(1, "12 * (R1"), Err(Error::Parse(_))));
+    /// ```
+    pub fn try_across(mut self, idx: usize, expr: &str) -> Result<Self, Error> {
+        if idx == 0 {
+            return Err(Error::Parse(
+                "Positions count from R1, so there is no R0".to_string(),
+            ));
+        }
+        let parsed = expr
+            .parse()
+            .map_err(|_| Error::Parse(format!("Invalid excitation '{}'", expr)))?;
+        if self.across.len() < idx {
+            self.across.resize(idx, None);
+        }
+        self.across[idx - 1] = Some((expr.to_string(), parsed));
+        Ok(self)
+    }
+
+    /// Declares a simple divider, where R1, R2, ..., R{count} are in series across `vin`.
+    /// # Example
+    /// ```
+    ///     # use resistor_calc::*;
+    ///     let exc = Excitation::divider(12.0, 2);
+    ///     assert_eq!(exc.power(&[1000.0, 1000.0]), vec![0.036, 0.036]);
+    /// ```
+    pub fn divider(vin: f64, count: usize) -> Self {
+        let sum = (1..=count).map(|i| format!("R{}", i)).join(" + ");
+        (1..=count).fold(Excitation::new(), |exc, i| {
+            exc.across(i, &format!("{} * R{} / ({})", vin, i, sum))
+        })
+    }
+
+    /// Calculates the power dissipated in each resistor, in watts, for the given values.
+    /// Resistors without a declared voltage dissipate no power.
+    pub fn power(&self, values: &[f64]) -> Vec<f64> {
+        self.try_power(values).unwrap()
+    }
+
+    /// As with `power`, returning an error if a voltage expression cannot be evaluated, such as
+    /// one using a resistor beyond those of the calculation.
+    pub(crate) fn try_power(&self, values: &[f64]) -> Result<Vec<f64>, Error> {
+        with_context(values, &[], |ctx| {
+            values
+                .iter()
+                .enumerate()
+                .map(|(i, r)| match self.across.get(i) {
+                    Some(Some((src, expr))) => expr
+                        .eval_with_context(ctx)
+                        .map(|v| v.powi(2) / r)
+                        .map_err(|e| {
+                            Error::Eval(format!(
+                                "The voltage '{}' across R{} could not be evaluated for {}: {}",
+                                src,
+                                i + 1,
+                                RSet::new(values),
+                                e
+                            ))
+                        }),
+                    _ => Ok(0.0),
+                })
+                .collect()
+        })
+    }
+}
+
+/// Serialises an excitation as the source expression of the voltage across each resistor.
+#[cfg(feature = "serialize")]
+impl From<Excitation> for Vec<Option<String>> {
+    fn from(exc: Excitation) -> Self {
+        exc.across
+            .into_iter()
+            .map(|across| across.map(|(src, _)| src))
+            .collect()
+    }
+}
+
+#[cfg(feature = "serialize")]
+impl TryFrom<Vec<Option<String>>> for Excitation {
+    type Error = String;
+
+    fn try_from(srcs: Vec<Option<String>>) -> Result<Self, String> {
+        let across = srcs
+            .into_iter()
+            .map(|src| match src {
+                Some(src) => match src.parse() {
+                    Ok(expr) => Ok(Some((src, expr))),
+                    Err(_) => Err(format!("Invalid excitation '{}'", src)),
+                },
+                None => Ok(None),
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Excitation { across })
+    }
+}
+
+impl RRes {
+    /// Calculates the power dissipated in each resistor of the `i`th result, in watts.
+    /// # Example
+    /// ```
+    ///     # use resistor_calc::*;
+    ///     let res = RCalc::e24(2)
+    ///         .calc(ROpBuilder::new().bound("R2 / (R1 + R2) ~ 0.25").finish())
+    ///         .unwrap();
+    ///     let power = res.power(0, &Excitation::divider(12.0, 2));
+    /// ```
+    pub fn power(&self, i: usize, exc: &Excitation) -> Vec<f64> {
+        exc.power(&(self.res[i].1).0)
+    }
+}
+
+/// The operating point of a result after accounting for self-heating, produced by
+/// `RRes::self_heating`.
+#[derive(Debug, Clone)]
+pub struct SelfHeating {
+    /// The values of R1, R2, ..., Rn at their operating temperature.
+    pub values: Vec<f64>,
+    /// The temperature rise of each resistor in °C.
+    pub temperature_rise: Vec<f64>,
+    /// The outcome of each bound at the operating point.
+    pub bounds: Vec<BoundOutcome>,
+}
+
+impl RRes {
+    /// Models the self-heating of the `i`th result. Each resistor rises in temperature by the
+    /// power it dissipates under `exc` multiplied by the thermal resistance `theta` of its
+    /// package in °C/W, shifting its value by the temperature coefficient of its series. As the
+    /// shifted values change the power dissipated this is iterated until it settles, and the
+    /// bounds of `op` are evaluated at the final operating point.
+    /// # Example
+    /// ```
+    ///     # use resistor_calc::*;
+    ///     let series = E24.clone().with_tempco(200.0);
+    ///     let exc = Excitation::divider(48.0, 2);
+    ///     let op = ROpBuilder::new().bound("48 * R2 / (R1 + R2) ~ 12.0").build();
+    ///     let res = RCalc::new(vec![&series, &series]).calc(|rs| op.eval(rs)).unwrap();
+    ///     let heated = res.self_heating(0, &op, &exc, 250.0);
+    ///     println!("Operating error: {}", heated.bounds[0].deviation);
+    /// ```
+    pub fn self_heating(&self, i: usize, op: &ROp, exc: &Excitation, theta: f64) -> SelfHeating {
+        let nominal = &(self.res[i].1).0;
+        let mut values = nominal.to_vec();
+        let mut rise = vec![0.0; values.len()];
+        for _ in 0..100 {
+            rise = exc.power(&values).iter().map(|p| p * theta).collect();
+            let next: Vec<f64> = nominal
+                .iter()
+                .zip(rise.iter().zip(self.pos.iter()))
+                .map(|(r, (t, pos))| r * (1.0 + pos.tempco * 1e-6 * t))
+                .collect();
+            let settled = next
+                .iter()
+                .zip(values.iter())
+                .all(|(a, b)| ((a - b) / b).abs() < 1e-12);
+            values = next;
+            if settled {
+                break;
+            }
+        }
+        SelfHeating {
+            bounds: op.explain(&RSet::new(&values)),
+            values,
+            temperature_rise: rise,
+        }
+    }
+}
@@ -0,0 +1,74 @@
+use std::thread;
+use std::time::Duration;
+
+/// A part of a search, run by an `Executor`. Tasks borrow from the search that created them, so
+/// an executor must finish running them before `Executor::run` returns.
+pub type Task<'s> = Box<dyn FnOnce() + Send + 's>;
+
+/// How often `Threads` calls the waiting callback while its threads search.
+const PROGRESS_PERIOD: Duration = Duration::from_millis(100);
+
+/// Runs the parts of a search for `RCalc::calc_on`, allowing searches to be spread across a
+/// thread pool of the caller's choosing, or kept on a single thread where there are none.
+/// # Example
+/// An executor that runs the tasks one after another, counting them:
+/// ```
+///     # use resistor_calc::*;
+///     use std::cell::Cell;
+///
+///     struct Counting(Cell<usize>);
+///
+///     impl Executor for Counting {
+///         fn run<'s>(&self, tasks: Vec<Task<'s>>, wait: &mut dyn FnMut()) {
+///             for task in tasks {
+///                 task();
+///                 self.0.set(self.0.get() + 1);
+///                 wait();
+///             }
+///         }
+///     }
+///
+///     let op = ROpBuilder::new().bound("R2 / (R1 + R2) ~ 0.25").build();
+///     let limits = Limits { jobs: 3, ..Default::default() };
+///     let exec = Counting(Cell::new(0));
+///     let res = RCalc::e24(2).calc_on(|rs| op.eval(rs), &limits, &exec, |_| ());
+///     assert!(res.is_some());
+///     assert_eq!(exec.0.get(), 3);
+/// ```
+pub trait Executor {
+    /// Runs every task, returning once all of them have finished. `wait` reports the progress of
+    /// the search, and should be called every so often while the caller is waiting.
+    fn run<'s>(&self, tasks: Vec<Task<'s>>, wait: &mut dyn FnMut());
+}
+
+/// Runs each task on its own thread, the executor used by `RCalc::calc_limited`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Threads;
+
+impl Executor for Threads {
+    fn run<'s>(&self, tasks: Vec<Task<'s>>, wait: &mut dyn FnMut()) {
+        thread::scope(|s| {
+            let handles: Vec<_> = tasks.into_iter().map(|task| s.spawn(task)).collect();
+            while !handles.iter().all(|h| h.is_finished()) {
+                thread::sleep(PROGRESS_PERIOD);
+                wait();
+            }
+            for handle in handles {
+                handle.join().unwrap();
+            }
+        })
+    }
+}
+
+/// Runs the tasks one after another on the calling thread, for environments without threads.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Inline;
+
+impl Executor for Inline {
+    fn run<'s>(&self, tasks: Vec<Task<'s>>, wait: &mut dyn FnMut()) {
+        for task in tasks {
+            task();
+            wait();
+        }
+    }
+}
@@ -0,0 +1,146 @@
+use std::fmt::Write;
+
+use {_print_r, RRes};
+
+impl RRes {
+    /// Exports the best `top` results as CSV, with a header of `error,R1,R2,...,Rn` and values in
+    /// ohms.
+    /// # Example
+    /// ```
+    ///     # use resistor_calc::*;
+    ///     let res = RCalc::e3(2)
+    ///         .calc(ROpBuilder::new().bound("R1 + R2 ~ 500").finish())
+    ///         .unwrap();
+    ///     assert!(res.to_csv(1).starts_with("error,R1,R2\n"));
+    /// ```
+    pub fn to_csv(&self, top: usize) -> String {
+        let mut out = String::from("error");
+        for i in 1..=self.pos.len() {
+            write!(out, ",R{}", i).unwrap();
+        }
+        out.push('\n');
+        for (err, rs) in self.res.iter().take(top) {
+            write!(out, "{}", (*err as f64) / 1e9).unwrap();
+            for r in rs.0.iter() {
+                write!(out, ",{}", r).unwrap();
+            }
+            out.push('\n');
+        }
+        out
+    }
+
+    /// The designator of the part at position `idx`, counting from 0, as named with
+    /// `RPosition::name` or otherwise R1, R2, ..., Rn.
+    fn designator(&self, idx: usize) -> String {
+        match self.pos[idx].name.as_str() {
+            "" => format!("R{}", idx + 1),
+            name => name.to_string(),
+        }
+    }
+
+    /// Exports the `i`th result as a bill of materials in CSV, with the designator, value, series,
+    /// tolerance and package of each resistor.
+    /// # Example
+    /// ```
+    ///     # use resistor_calc::*;
+    ///     let res = RCalc::e3(2)
+    ///         .calc(ROpBuilder::new().bound("R1 + R2 ~ 500").finish())
+    ///         .unwrap();
+    ///     println!("{}", res.to_bom(0));
+    /// ```
+    pub fn to_bom(&self, i: usize) -> String {
+        let mut out = String::from("Designator,Value,Series,Tolerance,Package\n");
+        for (idx, (r, pos)) in (self.res[i].1).0.iter().zip(self.pos.iter()).enumerate() {
+            writeln!(
+                out,
+                "{},{},{},{}%,{}",
+                self.designator(idx),
+                _print_r(r),
+                pos.series,
+                pos.tolerance * 100.0,
+                pos.package.as_deref().unwrap_or("")
+            )
+            .unwrap();
+        }
+        out
+    }
+
+    /// Exports the `i`th result as a SPICE `.param` block defining R1, R2, ..., Rn, so that a
+    /// netlist can refer to the values as `{R1}`.
+    /// # Example
+    /// ```
+    ///     # use resistor_calc::*;
+    ///     let res = RCalc::e3(2)
+    ///         .calc(ROpBuilder::new().bound("R1 + R2 ~ 500").finish())
+    ///         .unwrap();
+    ///     assert!(res.to_spice(0).starts_with(".param R1="));
+    /// ```
+    pub fn to_spice(&self, i: usize) -> String {
+        (self.res[i].1)
+            .0
+            .iter()
+            .enumerate()
+            .map(|(idx, r)| format!(".param R{}={}\n", idx + 1, r))
+            .collect()
+    }
+
+    /// Exports the `i`th result as Rust `const` definitions of R1, R2, ..., Rn in ohms and a
+    /// `RESISTORS` table of them, followed by the `derived` constants such as ADC scale factors.
+    /// The definitions need neither `std` nor `alloc`, so can be compiled into firmware.
+    /// # Example
+    /// ```
+    ///     # use resistor_calc::*;
+    ///     let res = RCalc::e3(2)
+    ///         .calc(ROpBuilder::new().bound("R1 + R2 ~ 500").finish())
+    ///         .unwrap();
+    ///     let (_, rs) = res.iter().next().unwrap();
+    ///     let scale = rs.r(2) / (rs.r(1) + rs.r(2));
+    ///     let code = res.to_rust(0, &[("ADC_SCALE", scale)]);
+    ///     assert!(code.contains("pub const RESISTORS: [f64; 2] = ["));
+    ///     assert!(code.contains("pub const ADC_SCALE: f64 = "));
+    /// ```
+    pub fn to_rust(&self, i: usize, derived: &[(&str, f64)]) -> String {
+        let (err, ref rs) = self.res[i];
+        let mut out = format!(
+            "// Generated by resistor-calc, with an error of {}.\n\n",
+            (err as f64) / 1e9
+        );
+        for (idx, (r, pos)) in rs.0.iter().zip(self.pos.iter()).enumerate() {
+            writeln!(
+                out,
+                "/// {} from the {} series.\npub const R{}: f64 = {:?};",
+                _print_r(r),
+                pos.series,
+                idx + 1,
+                r
+            )
+            .unwrap();
+        }
+        let names: Vec<String> = (1..=rs.0.len()).map(|idx| format!("R{}", idx)).collect();
+        writeln!(
+            out,
+            "\n/// R1, R2, ..., Rn in ohms.\npub const RESISTORS: [f64; {}] = [{}];",
+            names.len(),
+            names.join(", ")
+        )
+        .unwrap();
+        if !derived.is_empty() {
+            out.push('\n');
+        }
+        for &(name, value) in derived {
+            writeln!(out, "pub const {}: f64 = {:?};", name, value).unwrap();
+        }
+        out
+    }
+
+    /// Exports the `i`th result in the CSV layout of the KiCad symbol fields table, so that the
+    /// values can be applied to the references of a schematic, as named with `RPosition::name`
+    /// or otherwise R1, R2, ..., Rn.
+    pub fn to_kicad(&self, i: usize) -> String {
+        let mut out = String::from("\"Reference\",\"Value\"\n");
+        for (idx, r) in (self.res[i].1).0.iter().enumerate() {
+            writeln!(out, "\"{}\",\"{}\"", self.designator(idx), _print_r(r)).unwrap();
+        }
+        out
+    }
+}
@@ -1,24 +1,71 @@
 extern crate meval;
 
+#[cfg(feature = "serialize")]
+use std::convert::TryFrom;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
 use std::{f64::EPSILON, str::FromStr};
 
-use RSet;
+use {parallel, Error, Excitation, RCalc, RRes, RSet};
 
 lazy_static!(
     static ref RNAMES: Vec<String> = (1..=100).map(|i| format!("R{}", i)).collect();
+    static ref PNAMES: Vec<String> = (1..=100).map(|i| format!("P{}", i)).collect();
 );
 
-enum Bounds {
-    Cmp(Box<Fn(f64, f64) -> bool>, meval::Expr, f64),
+#[derive(Clone, Copy)]
+pub(crate) enum Cmp {
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Eq,
+    Ne,
+}
+
+impl Cmp {
+    fn test(self, a: f64, b: f64) -> bool {
+        match self {
+            Cmp::Lt => a < b,
+            Cmp::Le => a <= b,
+            Cmp::Gt => a > b,
+            Cmp::Ge => a >= b,
+            Cmp::Eq => (a - b).abs() < EPSILON,
+            Cmp::Ne => (a - b).abs() > EPSILON,
+        }
+    }
+}
+
+/// Bounds parsed by the builders of an `RCalc`, by their source.
+pub(crate) type BoundCache = Arc<Mutex<HashMap<String, Bounds>>>;
+
+/// The most parsed bounds kept by an `RCalc`, after which the cache is emptied and starts again.
+const CACHED_BOUNDS: usize = 256;
+
+#[derive(Clone)]
+pub(crate) enum Bounds {
+    Cmp(Cmp, meval::Expr, f64),
     Err(meval::Expr, f64),
 }
 
-fn split_expr(expr: &str, pat: &str) -> (meval::Expr, f64) {
+fn split_expr(expr: &str, pat: &str) -> Result<(meval::Expr, f64), &'static str> {
     let mut split = expr.split(pat);
-    (
-        split.next().unwrap().trim().parse::<meval::Expr>().unwrap(),
-        split.next().unwrap().trim().parse::<f64>().unwrap(),
-    )
+    let ex = split
+        .next()
+        .unwrap()
+        .trim()
+        .parse::<meval::Expr>()
+        .map_err(|_| "Err: Bound expression could not be parsed")?;
+    let trg = split
+        .next()
+        .unwrap()
+        .trim()
+        .parse::<f64>()
+        .map_err(|_| "Err: Bound target must be a number")?;
+    if split.next().is_some() {
+        return Err("Err: Bound must have a single comparison");
+    }
+    Ok((ex, trg))
 }
 
 impl FromStr for Bounds {
@@ -26,33 +73,25 @@ impl FromStr for Bounds {
 
     fn from_str(s: &str) -> Result<Self, <Self as FromStr>::Err> {
         if s.contains("<=") {
-            let (ex, trg) = split_expr(s, "<=");
-            Ok(Bounds::Cmp(Box::new(|a, b| a <= b), ex, trg))
+            let (ex, trg) = split_expr(s, "<=")?;
+            Ok(Bounds::Cmp(Cmp::Le, ex, trg))
         } else if s.contains('<') {
-            let (ex, trg) = split_expr(s, "<");
-            Ok(Bounds::Cmp(Box::new(|a, b| a < b), ex, trg))
+            let (ex, trg) = split_expr(s, "<")?;
+            Ok(Bounds::Cmp(Cmp::Lt, ex, trg))
         } else if s.contains(">=") {
-            let (ex, trg) = split_expr(s, ">=");
-            Ok(Bounds::Cmp(Box::new(|a, b| a >= b), ex, trg))
+            let (ex, trg) = split_expr(s, ">=")?;
+            Ok(Bounds::Cmp(Cmp::Ge, ex, trg))
         } else if s.contains('>') {
-            let (ex, trg) = split_expr(s, ">");
-            Ok(Bounds::Cmp(Box::new(|a, b| a > b), ex, trg))
+            let (ex, trg) = split_expr(s, ">")?;
+            Ok(Bounds::Cmp(Cmp::Gt, ex, trg))
         } else if s.contains("==") {
-            let (ex, trg) = split_expr(s, "==");
-            Ok(Bounds::Cmp(
-                Box::new(|a, b| (a - b).abs() < EPSILON),
-                ex,
-                trg,
-            ))
+            let (ex, trg) = split_expr(s, "==")?;
+            Ok(Bounds::Cmp(Cmp::Eq, ex, trg))
         } else if s.contains("!=") {
-            let (ex, trg) = split_expr(s, "!=");
-            Ok(Bounds::Cmp(
-                Box::new(|a, b| (a - b).abs() > EPSILON),
-                ex,
-                trg,
-            ))
+            let (ex, trg) = split_expr(s, "!=")?;
+            Ok(Bounds::Cmp(Cmp::Ne, ex, trg))
         } else if s.contains('~') {
-            let (ex, trg) = split_expr(s, "~");
+            let (ex, trg) = split_expr(s, "~")?;
             Ok(Bounds::Err(ex, trg))
         } else {
             Err("Err: Bound must contain either <, <=, >, >=, ==, != or ~")
@@ -60,16 +99,379 @@ impl FromStr for Bounds {
     }
 }
 
+impl Bounds {
+    fn expr(&self) -> &meval::Expr {
+        match *self {
+            Bounds::Cmp(_, ref expr, _) | Bounds::Err(ref expr, _) => expr,
+        }
+    }
+
+    /// Returns whether `val` satisfies the bound, and how far it is from doing so. For `~` bounds
+    /// this is always satisfied and the distance is the error from the target.
+    fn outcome(&self, val: f64) -> (bool, f64) {
+        match *self {
+            Bounds::Cmp(op, _, target) => {
+                if op.test(val, target) {
+                    (true, 0.0)
+                } else {
+                    (false, (target - val).abs())
+                }
+            }
+            Bounds::Err(_, target) => (true, (target - val).abs()),
+        }
+    }
+}
+
+/// A compiled set of bounds produced by `ROpBuilder::build`. Unlike the function produced by
+/// `ROpBuilder::finish` this can be inspected and reused for analysis of results. With the
+/// `serialize` feature it is serialised as the source of its bounds, constants and excitation.
+#[derive(Clone)]
+#[cfg_attr(
+    feature = "serialize",
+    derive(Serialize, Deserialize),
+    serde(into = "ROpDef", try_from = "ROpDef")
+)]
+pub struct ROp {
+    ops: Vec<Bounds>,
+    srcs: Vec<String>,
+    exc: Option<Excitation>,
+    chain: Option<f64>,
+    consts: Vec<(String, f64)>,
+    distinct_weight: f64,
+    max_distinct: Option<usize>,
+    same: Vec<(Vec<usize>, f64)>,
+}
+
+/// The standard normal cumulative distribution function, using the Abramowitz and Stegun
+/// approximation of erf.
+fn normal_cdf(x: f64) -> f64 {
+    let z = x.abs() / 2f64.sqrt();
+    let t = 1.0 / (1.0 + 0.327_591_1 * z);
+    let poly = t
+        * (0.254_829_592
+            + t * (-0.284_496_736 + t * (1.421_413_741 + t * (-1.453_152_027 + t * 1.061_405_429))));
+    let erf = 1.0 - poly * (-z * z).exp();
+    if x >= 0.0 {
+        0.5 * (1.0 + erf)
+    } else {
+        0.5 * (1.0 - erf)
+    }
+}
+
+/// The number of different values among `values`.
+fn distinct(values: &[f64]) -> usize {
+    values
+        .iter()
+        .enumerate()
+        .filter(|&(i, v)| !values[..i].contains(v))
+        .count()
+}
+
+/// Runs `f` with a context binding R1, R2, ..., Rn to `values`, along with any extra `vars`.
+pub(crate) fn with_context<T>(
+    values: &[f64],
+    vars: &[(String, f64)],
+    f: impl FnOnce(&meval::Context) -> T,
+) -> T {
+    let mut ctx = meval::Context::new();
+    ctx.func2("par", parallel);
+    for (i, v) in values.iter().enumerate() {
+        ctx.var(RNAMES[i].clone(), *v);
+    }
+    for &(ref name, v) in vars {
+        ctx.var(name.clone(), v);
+    }
+    f(&ctx)
+}
+
+impl ROp {
+    /// Derived quantities made available to bound expressions alongside R1, R2, ..., Rn.
+    fn vars(&self, values: &[f64]) -> Vec<(String, f64)> {
+        let mut vars = self.consts.clone();
+        if let Some(ref exc) = self.exc {
+            vars.extend(PNAMES.iter().cloned().zip(exc.power(values)));
+        }
+        if let Some(v) = self.chain {
+            vars.push(("I_chain".to_string(), v / values.iter().sum::<f64>()));
+        }
+        vars
+    }
+
+    /// The error added for the values themselves rather than any bound, or `None` if they use
+    /// more distinct values than `ROpBuilder::max_distinct` allows.
+    fn penalty(&self, values: &[f64]) -> Option<f64> {
+        if self.distinct_weight == 0.0 && self.max_distinct.is_none() && self.same.is_empty() {
+            return Some(0.0);
+        }
+        let count = distinct(values);
+        if self.max_distinct.is_some_and(|max| count > max) {
+            return None;
+        }
+        let same: f64 = self
+            .same
+            .iter()
+            .map(|(positions, weight)| {
+                let group: Vec<f64> = positions.iter().map(|&p| values[p - 1]).collect();
+                weight * distinct(&group).saturating_sub(1) as f64
+            })
+            .sum();
+        Some(self.distinct_weight * count.saturating_sub(1) as f64 + same)
+    }
+
+    /// Changes the value of a named constant declared with `ROpBuilder::constant`, allowing
+    /// existing results to be re-evaluated with `RRes::reevaluate` under different conditions.
+    /// Returns an error if no constant of that name was declared, so that a misspelt name is not
+    /// silently ignored.
+    /// # Example
+    /// ```
+    ///     # use resistor_calc::*;
+    ///     let mut op = ROpBuilder::new().constant("VREF", 0.8).build();
+    ///     assert!(op.set_constant("VREF", 0.79).is_ok());
+    ///     assert!(matches!(op.set_constant("VERF", 0.79), Err(Error::Parse(_))));
+    /// ```
+    pub fn set_constant(&mut self, name: &str, value: f64) -> Result<(), Error> {
+        let idx = self.constant_index(name)?;
+        self.consts[idx].1 = value;
+        Ok(())
+    }
+
+    /// The index into the constants of the constant `name`, or an error if it was not declared.
+    pub(crate) fn constant_index(&self, name: &str) -> Result<usize, Error> {
+        self.consts.iter().position(|c| c.0 == name).ok_or_else(|| {
+            Error::Parse(format!("'{}' is not a constant of the bounds", name))
+        })
+    }
+
+    /// Evaluates an expression such as `"(R1 + R2) / R2"` for a set of values, with the same
+    /// variables and functions available as in the bounds. This allows quantities derived from a
+    /// result, such as the scale factor of an ADC input, to be calculated.
+    /// # Example
+    /// ```
+    ///     # use resistor_calc::*;
+    ///     let op = ROpBuilder::new().constant("VREF", 3.3).build();
+    ///     let full_scale = op.evaluate("VREF * (R1 + R2) / R2", &RSet::new(&[10e3, 10e3]));
+    ///     assert_eq!(full_scale, Ok(6.6));
+    /// ```
+    pub fn evaluate(&self, expr: &str, rs: &RSet) -> Result<f64, Error> {
+        let src = expr;
+        let expr = expr
+            .parse::<meval::Expr>()
+            .map_err(|_| Error::Parse(format!("Expression could not be parsed in '{}'", expr)))?;
+        with_context(&rs.0, &self.vars(&rs.0), |ctx| expr.eval_with_context(ctx))
+            .map_err(|e| {
+                Error::Eval(format!("'{}' could not be evaluated for {}: {}", src, rs, e))
+            })
+    }
+
+    /// The source expressions of each bound, in the order they were added.
+    pub fn bounds(&self) -> &[String] {
+        &self.srcs
+    }
+
+    /// The named constants declared with `ROpBuilder::constant`, with their current values.
+    pub fn constants(&self) -> &[(String, f64)] {
+        &self.consts
+    }
+
+    /// Evaluates the bounds against a set of values, returning `None` if any comparison bound
+    /// fails and otherwise the total error from the `~` bounds.
+    pub fn eval(&self, rs: &RSet) -> Option<f64> {
+        self.eval_values(&rs.0)
+    }
+
+    /// As with `eval`, returning an error rather than panicking if a bound cannot be evaluated
+    /// for the values, such as one using a resistor beyond those in `rs` or an unknown variable.
+    /// # Example
+    /// ```
+    ///     # use resistor_calc::*;
+    ///     let op = ROpBuilder::new().bound("R1 + R3 ~ 2e3").build();
+    ///     assert_eq!(op.try_eval(&RSet::new(&[1e3, 1e3, 1e3])), Ok(Some(0.0)));
+    ///     let err = op.try_eval(&RSet::new(&[1e3, 1e3])).unwrap_err();
+    ///     assert!(matches!(err, Error::Eval(_)));
+    ///     // Bound 1 'R1 + R3 ~ 2e3' could not be evaluated for R1: 1K, R2: 1K: ...
+    ///     assert!(err.message().starts_with("Bound 1 'R1 + R3 ~ 2e3'"));
+    /// ```
+    pub fn try_eval(&self, rs: &RSet) -> Result<Option<f64>, Error> {
+        let quantities = self.try_quantities(&rs.0)?;
+        let mut err = match self.penalty(&rs.0) {
+            Some(penalty) => penalty,
+            None => return Ok(None),
+        };
+        for (op, val) in self.ops.iter().zip(quantities) {
+            let (ok, dev) = op.outcome(val);
+            if !ok {
+                return Ok(None);
+            }
+            err += dev;
+        }
+        Ok(Some(err))
+    }
+
+    pub(crate) fn eval_values(&self, values: &[f64]) -> Option<f64> {
+        let penalty = self.penalty(values)?;
+        with_context(values, &self.vars(values), |ctx| {
+            let mut err = penalty;
+            for (idx, op) in self.ops.iter().enumerate() {
+                let val = op
+                    .expr()
+                    .eval_with_context(ctx)
+                    .unwrap_or_else(|e| self.eval_panic(idx, values, e));
+                let (ok, dev) = op.outcome(val);
+                if !ok {
+                    return None;
+                }
+                err += dev;
+            }
+            Some(err)
+        })
+    }
+
+    /// As with `eval_values`, counting the evaluations and rejections of each bound in `stats`.
+    pub(crate) fn eval_counted(&self, values: &[f64], stats: &mut [(u128, u128)]) -> Option<f64> {
+        let penalty = self.penalty(values)?;
+        with_context(values, &self.vars(values), |ctx| {
+            let mut err = penalty;
+            for (idx, (op, stat)) in self.ops.iter().zip(stats.iter_mut()).enumerate() {
+                stat.0 += 1;
+                let val = op
+                    .expr()
+                    .eval_with_context(ctx)
+                    .unwrap_or_else(|e| self.eval_panic(idx, values, e));
+                let (ok, dev) = op.outcome(val);
+                if !ok {
+                    stat.1 += 1;
+                    return None;
+                }
+                err += dev;
+            }
+            Some(err)
+        })
+    }
+
+    /// Evaluates the expression of every bound for a set of values.
+    pub(crate) fn quantities(&self, values: &[f64]) -> Vec<f64> {
+        with_context(values, &self.vars(values), |ctx| {
+            self.ops
+                .iter()
+                .enumerate()
+                .map(|(idx, op)| {
+                    op.expr()
+                        .eval_with_context(ctx)
+                        .unwrap_or_else(|e| self.eval_panic(idx, values, e))
+                })
+                .collect()
+        })
+    }
+
+    /// Describes why bound `idx` could not be evaluated for `values`, giving its position, its
+    /// source and the values, so that a bad bound can be found without removing bounds one at a
+    /// time.
+    fn eval_error(&self, idx: usize, values: &[f64], e: meval::Error) -> Error {
+        Error::Eval(format!(
+            "Bound {} '{}' could not be evaluated for {}: {}",
+            idx + 1,
+            self.srcs[idx],
+            RSet::new(values),
+            e
+        ))
+    }
+
+    /// Panics with the description of `eval_error`, for the searches that have no way to return
+    /// an error. `RCalc::try_calc` checks the bounds first to avoid this.
+    fn eval_panic(&self, idx: usize, values: &[f64], e: meval::Error) -> f64 {
+        panic!("{}", self.eval_error(idx, values, e))
+    }
+
+    /// As with `quantities`, returning an error naming the bound that cannot be evaluated.
+    pub(crate) fn try_quantities(&self, values: &[f64]) -> Result<Vec<f64>, Error> {
+        if values.len() > RNAMES.len() {
+            return Err(Error::Eval(format!(
+                "Calculations can have at most {} resistors",
+                RNAMES.len()
+            )));
+        }
+        if let Some(ref exc) = self.exc {
+            exc.try_power(values)?;
+        }
+        let mut positions = self.same.iter().flat_map(|(positions, _)| positions);
+        if let Some(p) = positions.find(|&&p| p == 0 || p > values.len()) {
+            return Err(Error::Eval(format!("R{} is not one of the resistors", p)));
+        }
+        with_context(values, &self.vars(values), |ctx| {
+            self.ops
+                .iter()
+                .enumerate()
+                .map(|(idx, op)| {
+                    op.expr()
+                        .eval_with_context(ctx)
+                        .map_err(|e| self.eval_error(idx, values, e))
+                })
+                .collect()
+        })
+    }
+
+    /// Returns whether bound `idx` is satisfied by `val` and its deviation from the target.
+    pub(crate) fn outcome(&self, idx: usize, val: f64) -> (bool, f64) {
+        self.ops[idx].outcome(val)
+    }
+
+    /// Returns the probability that bound `idx` is satisfied when its expression is normally
+    /// distributed with the given mean and standard deviation.
+    pub(crate) fn pass_probability(&self, idx: usize, mean: f64, sigma: f64) -> f64 {
+        if sigma <= 0.0 {
+            return if self.outcome(idx, mean).0 { 1.0 } else { 0.0 };
+        }
+        match self.ops[idx] {
+            Bounds::Err(..) => 1.0,
+            Bounds::Cmp(op, _, target) => {
+                let below = normal_cdf((target - mean) / sigma);
+                match op {
+                    Cmp::Lt | Cmp::Le => below,
+                    Cmp::Gt | Cmp::Ge => 1.0 - below,
+                    Cmp::Eq => 0.0,
+                    Cmp::Ne => 1.0,
+                }
+            }
+        }
+    }
+
+    /// Returns whether bound `idx` is a `~` bound.
+    pub(crate) fn is_soft(&self, idx: usize) -> bool {
+        match self.ops[idx] {
+            Bounds::Err(..) => true,
+            Bounds::Cmp(..) => false,
+        }
+    }
+}
+
 /// Builder struct used to create `f` values for `RCalc::calc` from mathematical expressions.
-#[derive(Default)]
+#[derive(Default, Clone)]
 pub struct ROpBuilder {
     ops: Vec<Bounds>,
+    srcs: Vec<String>,
+    exc: Option<Excitation>,
+    chain: Option<f64>,
+    consts: Vec<(String, f64)>,
+    distinct_weight: f64,
+    max_distinct: Option<usize>,
+    same: Vec<(Vec<usize>, f64)>,
+    cache: Option<BoundCache>,
 }
 
 impl ROpBuilder {
     /// Init a new builder.
     pub fn new() -> Self {
-        ROpBuilder { ops: Vec::new() }
+        ROpBuilder {
+            ops: Vec::new(),
+            srcs: Vec::new(),
+            exc: None,
+            chain: None,
+            consts: Vec::new(),
+            distinct_weight: 0.0,
+            max_distinct: None,
+            same: Vec::new(),
+            cache: None,
+        }
     }
 
     /// Add a new bound to the builder, this must be an expression of the form `expr op target`
@@ -78,45 +480,333 @@ impl ROpBuilder {
     /// For ~ the bound will calculate the difference between the value of expr and target and add
     /// the abs error to the resulting error. For all other ops the bound will compare the value of
     /// expr to target, and if the comparison fails, it will reject the set of proposed values.
-    pub fn bound(mut self, expr: &str) -> Self {
-        self.ops.push(expr.parse().unwrap());
-        self
+    ///
+    /// In addition to the standard functions, `par(a, b)` gives the resistance of `a` and `b` in
+    /// parallel, allowing the Thevenin resistance of a network to be bounded, e.g. the source
+    /// impedance of a divider with `"par(R1, R2) < 10e3"`.
+    /// # Example
+    /// ```
+    ///     # use resistor_calc::*;
+    ///     let res = RCalc::e24(2)
+    ///         .calc(
+    ///             ROpBuilder::new()
+    ///                 .bound("R2 / (R1 + R2) ~ 0.5")
+    ///                 .bound("par(R1, R2) < 10e3")
+    ///                 .finish(),
+    ///         )
+    ///         .unwrap();
+    ///     assert!(res.iter().all(|(_, rs)| rs.thevenin(&[1], &[2]) < 10e3));
+    /// ```
+    pub fn bound(self, expr: &str) -> Self {
+        self.try_bound(expr).unwrap()
     }
 
-    fn cmp_bound_fn(&mut self) -> Box<Fn(&meval::Context) -> Option<f64>> {
-        match self.ops.pop() {
-            Some(b) => match b {
-                Bounds::Cmp(op, expr, target) => {
-                    let inner_bound = self.cmp_bound_fn();
-                    Box::new(move |ctx| {
-                        if op(expr.eval_with_context(ctx).unwrap(), target) {
-                            inner_bound(ctx)
-                        } else {
-                            None
-                        }
-                    })
+    /// As with `bound`, but returning a description of the problem if `expr` is not a valid bound
+    /// rather than panicking, for bounds that come from user input.
+    /// # Example
+    /// ```
+    ///     # use resistor_calc::*;
+    ///     assert!(ROpBuilder::new().try_bound("R1 + R2 ~ 500").is_ok());
+    ///     assert!(ROpBuilder::new().try_bound("R1 + R2").is_err());
+    ///     assert!(ROpBuilder::new().try_bound("R1 + (R2 ~ 500").is_err());
+    ///     assert!(ROpBuilder::new().try_bound("R1 <= 1e3 <= 2e3").is_err());
+    /// ```
+    pub fn try_bound(mut self, expr: &str) -> Result<Self, Error> {
+        let cached = self
+            .cache
+            .as_ref()
+            .and_then(|cache| cache.lock().unwrap().get(expr).cloned());
+        let bound = match cached {
+            Some(bound) => bound,
+            None => {
+                let bound: Bounds = expr.parse().map_err(|e: &str| {
+                    Error::Parse(format!("{} in '{}'", e.trim_start_matches("Err: "), expr))
+                })?;
+                if let Some(ref cache) = self.cache {
+                    let mut cache = cache.lock().unwrap();
+                    if cache.len() >= CACHED_BOUNDS {
+                        cache.clear();
+                    }
+                    cache.insert(expr.to_string(), bound.clone());
                 }
-                Bounds::Err(expr, target) => {
-                    let inner_bound = self.cmp_bound_fn();
-                    Box::new(move |ctx| {
-                        let val = expr.eval_with_context(ctx).unwrap();
-                        inner_bound(ctx).map(|v| v + (target - val).abs())
-                    })
-                }
-            },
-            None => Box::new(|_| Some(0.0)),
+                bound
+            }
+        };
+        self.ops.push(bound);
+        self.srcs.push(expr.to_string());
+        Ok(self)
+    }
+
+    /// Declares the excitation of the network, making the power dissipated in each resistor
+    /// available to bounds as P1, P2, ..., Pn in watts. This allows candidates that would exceed
+    /// the power rating of a package to be rejected during the search.
+    /// # Example
+    /// ```
+    ///     # use resistor_calc::*;
+    ///     let res = RCalc::e24(2).calc(
+    ///         ROpBuilder::new()
+    ///             .excitation(Excitation::divider(12.0, 2))
+    ///             .bound("R2 / (R1 + R2) ~ 0.25")
+    ///             .bound("P1 <= 0.1")
+    ///             .bound("P2 <= 0.1")
+    ///             .finish(),
+    ///     );
+    /// ```
+    pub fn excitation(mut self, exc: Excitation) -> Self {
+        self.exc = Some(exc);
+        self
+    }
+
+    /// Declares a named constant that can be used in bound expressions, such as a reference
+    /// voltage. Unlike writing the value into each bound, constants can later be changed with
+    /// `ROp::set_constant`.
+    /// # Example
+    /// ```
+    ///     # use resistor_calc::*;
+    ///     let mut op = ROpBuilder::new()
+    ///         .constant("VREF", 0.8)
+    ///         .bound("VREF * (1 + R1/R2) ~ 5.0")
+    ///         .build();
+    ///     let res = RCalc::e24(2).calc(|rs| op.eval(rs)).unwrap();
+    ///     op.set_constant("VREF", 0.79).unwrap();
+    ///     let shifted = res.reevaluate(&op).unwrap();
+    /// ```
+    pub fn constant(mut self, name: &str, value: f64) -> Self {
+        self.consts.push((name.to_string(), value));
+        self
+    }
+
+    /// Declares that all of the resistors form a single chain with `v` volts across it, making the
+    /// current through the chain available to bounds as `I_chain` in amps.
+    /// # Example
+    /// ```
+    ///     # use resistor_calc::*;
+    ///     let res = RCalc::e24(2).calc(
+    ///         ROpBuilder::new()
+    ///             .chain_voltage(5.0)
+    ///             .bound("R2 / (R1 + R2) ~ 0.66")
+    ///             .bound("I_chain >= 100e-6")
+    ///             .bound("I_chain <= 1e-3")
+    ///             .finish(),
+    ///     );
+    /// ```
+    pub fn chain_voltage(mut self, v: f64) -> Self {
+        self.chain = Some(v);
+        self
+    }
+
+    /// Adds `weight` to the error of each combination for every distinct value it uses beyond
+    /// the first, so that solutions built from fewer different parts are preferred. A weight
+    /// comparable to the error of the `~` bounds trades a little accuracy for a shorter bill of
+    /// materials.
+    /// # Example
+    /// ```
+    ///     # use resistor_calc::*;
+    ///     let op = ROpBuilder::new()
+    ///         .bound("R1 + R2 + R3 ~ 30e3")
+    ///         .distinct_penalty(0.5)
+    ///         .build();
+    ///     assert_eq!(op.eval(&RSet::new(&[10e3, 10e3, 10e3])), Some(0.0));
+    ///     assert_eq!(op.eval(&RSet::new(&[10e3, 15e3, 5e3])), Some(1.0));
+    /// ```
+    pub fn distinct_penalty(mut self, weight: f64) -> Self {
+        self.distinct_weight = weight;
+        self
+    }
+
+    /// Rejects combinations that use more than `max` distinct values, such as a network that must
+    /// be built from only two part numbers.
+    /// # Example
+    /// ```
+    ///     # use resistor_calc::*;
+    ///     let res = RCalc::e24(3)
+    ///         .calc(
+    ///             ROpBuilder::new()
+    ///                 .bound("R3 / (R1 + R2 + R3) ~ 0.2")
+    ///                 .max_distinct(2)
+    ///                 .finish(),
+    ///         )
+    ///         .unwrap();
+    ///     let rs = res.iter().next().unwrap().1.values();
+    ///     assert!(rs[0] == rs[1] || rs[0] == rs[2] || rs[1] == rs[2]);
+    /// ```
+    pub fn max_distinct(mut self, max: usize) -> Self {
+        self.max_distinct = Some(max);
+        self
+    }
+
+    /// Prefers combinations where the given positions, counting from 1 for R1, share the same
+    /// value, such as both halves of a divider that can then come from one reel. Adds `weight` to
+    /// the error for each distinct value among them beyond the first, so unlike an `==` bound,
+    /// differing values are still found when they are sufficiently more accurate.
+    /// # Example
+    /// ```
+    ///     # use resistor_calc::*;
+    ///     let op = ROpBuilder::new()
+    ///         .bound("R1 + R2 + R3 ~ 30e3")
+    ///         .prefer_same(&[1, 2], 0.5)
+    ///         .build();
+    ///     assert_eq!(op.eval(&RSet::new(&[10e3, 10e3, 10e3])), Some(0.0));
+    ///     assert_eq!(op.eval(&RSet::new(&[10e3, 15e3, 5e3])), Some(0.5));
+    ///     assert_eq!(op.eval(&RSet::new(&[5e3, 15e3, 10e3])), Some(0.5));
+    ///     assert_eq!(op.eval(&RSet::new(&[5e3, 5e3, 20e3])), Some(0.0));
+    /// ```
+    pub fn prefer_same(mut self, positions: &[usize], weight: f64) -> Self {
+        self.same.push((positions.to_vec(), weight));
+        self
+    }
+
+    /// Finishes the building and produces an `ROp`, which can both be used for calculation and
+    /// for later analysis of the results.
+    /// # Example
+    /// ```
+    ///     # use resistor_calc::*;
+    ///     let op = ROpBuilder::new().bound("R1 + R2 ~ 500").build();
+    ///     let res = RCalc::e3(2).calc(|rs| op.eval(rs)).unwrap();
+    /// ```
+    pub fn build(self) -> ROp {
+        ROp {
+            ops: self.ops,
+            srcs: self.srcs,
+            exc: self.exc,
+            chain: self.chain,
+            consts: self.consts,
+            distinct_weight: self.distinct_weight,
+            max_distinct: self.max_distinct,
+            same: self.same,
         }
     }
 
     /// Finishes the building and converts the struct into a function suitable to be passed to calc
-    pub fn finish(mut self) -> impl Fn(&RSet) -> Option<f64> {
-        let bound = self.cmp_bound_fn();
-        move |rs: &RSet| {
-            let mut ctx = meval::Context::new();
-            for (i, v) in rs.0.iter().enumerate() {
-                ctx.var(RNAMES[i].clone(), *v as f64);
-            }
-            bound(&ctx)
+    pub fn finish(self) -> impl Fn(&RSet) -> Option<f64> {
+        let op = self.build();
+        move |rs: &RSet| op.eval(rs)
+    }
+}
+
+impl<'a> RCalc<'a> {
+    /// Creates an empty builder for bounds that keeps the expressions it parses in this
+    /// calculator, so that building bounds again, such as after changing one of them, only parses
+    /// the bounds that changed. The details of the positions stored with each result are kept in
+    /// the same way, so a tool that re-solves as the bounds are tweaked pays the setup once.
+    /// # Example
+    /// ```
+    ///     # use resistor_calc::*;
+    ///     let rcalc = RCalc::e24(2);
+    ///     for target in &[0.25, 0.3, 0.35] {
+    ///         let op = rcalc
+    ///             .op_builder()
+    ///             .bound("R1 + R2 <= 20e3")
+    ///             .bound(&format!("R2 / (R1 + R2) ~ {}", target))
+    ///             .build();
+    ///         let res = rcalc.try_calc(&op).unwrap();
+    ///         println!("{}: {}", target, res.get(0).unwrap().1);
+    ///     }
+    /// ```
+    pub fn op_builder(&self) -> ROpBuilder {
+        ROpBuilder {
+            cache: Some(self.cache.bounds.clone()),
+            ..ROpBuilder::new()
+        }
+    }
+
+    /// As with `calc` for the bounds of `op`, returning an error rather than panicking or
+    /// returning `None` when the calculation cannot be done: if there are no positions, a
+    /// position has no values, a bound cannot be evaluated, or no combination satisfies the
+    /// bounds.
+    /// # Example
+    /// ```
+    ///     # use resistor_calc::*;
+    ///     let op = ROpBuilder::new().bound("R2 / (R1 + R2) ~ 0.25").build();
+    ///     assert!(RCalc::e24(2).try_calc(&op).is_ok());
+    ///     assert!(matches!(RCalc::e24(1).try_calc(&op), Err(Error::Eval(_))));
+    ///     let op = ROpBuilder::new().bound("R1 + R2 < 1").build();
+    ///     assert!(matches!(RCalc::e24(2).try_calc(&op), Err(Error::Solver(_))));
+    /// ```
+    pub fn try_calc(&self, op: &ROp) -> Result<RRes, Error> {
+        self.validate(op)?;
+        self.calc(|rs| op.eval(rs))
+            .ok_or_else(|| Error::Solver("No values satisfy the bounds".to_string()))
+    }
+
+    /// Checks that the bounds of `op` can be evaluated for every combination, so that a search
+    /// with them cannot panic, returning the same errors as `try_calc` without searching. An
+    /// error for a bound names its position and source, and the values it was evaluated for.
+    /// # Example
+    /// ```
+    ///     # use resistor_calc::*;
+    ///     let op = ROpBuilder::new()
+    ///         .bound("R1 + R2 < 10e3")
+    ///         .bound("R2 / (R1 + R2 + R3) ~ 0.25")
+    ///         .build();
+    ///     let err = RCalc::e24(2).validate(&op).unwrap_err();
+    ///     assert!(err.message().starts_with("Bound 2 'R2 / (R1 + R2 + R3) ~ 0.25'"));
+    /// ```
+    pub fn validate(&self, op: &ROp) -> Result<(), Error> {
+        if self.rs.is_empty() {
+            return Err(Error::Solver("No resistor positions to calculate".to_string()));
+        }
+        if let Some(i) = self.rs.iter().position(|r| r.len() == 0) {
+            return Err(Error::Solver(format!("R{} has no values to choose from", i + 1)));
+        }
+        // Every combination binds the same variables, so evaluating the bounds once shows that
+        // none of them can fail during the search.
+        op.try_quantities(&self.sets().next().unwrap()).map(|_| ())
+    }
+}
+
+/// The serialised form of an `ROp`, which keeps the source of each bound so that it can be
+/// compiled again when deserialised.
+#[cfg(feature = "serialize")]
+#[derive(Serialize, Deserialize)]
+struct ROpDef {
+    bounds: Vec<String>,
+    #[serde(default)]
+    constants: Vec<(String, f64)>,
+    #[serde(default)]
+    excitation: Option<Excitation>,
+    #[serde(default)]
+    chain_voltage: Option<f64>,
+    #[serde(default)]
+    distinct_penalty: f64,
+    #[serde(default)]
+    max_distinct: Option<usize>,
+    #[serde(default)]
+    prefer_same: Vec<(Vec<usize>, f64)>,
+}
+
+#[cfg(feature = "serialize")]
+impl From<ROp> for ROpDef {
+    fn from(op: ROp) -> Self {
+        ROpDef {
+            bounds: op.srcs,
+            constants: op.consts,
+            excitation: op.exc,
+            chain_voltage: op.chain,
+            distinct_penalty: op.distinct_weight,
+            max_distinct: op.max_distinct,
+            prefer_same: op.same,
+        }
+    }
+}
+
+#[cfg(feature = "serialize")]
+impl TryFrom<ROpDef> for ROp {
+    type Error = String;
+
+    fn try_from(def: ROpDef) -> Result<Self, String> {
+        let mut ops = ROpBuilder::new();
+        for (name, value) in &def.constants {
+            ops = ops.constant(name, *value);
+        }
+        for bound in &def.bounds {
+            ops = ops.try_bound(bound)?;
         }
+        ops.exc = def.excitation;
+        ops.chain = def.chain_voltage;
+        ops.distinct_weight = def.distinct_penalty;
+        ops.max_distinct = def.max_distinct;
+        ops.same = def.prefer_same;
+        Ok(ops.build())
     }
 }
@@ -0,0 +1,149 @@
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+
+use RCalc;
+
+/// The scale of the values in a fixed-point search: values are whole numbers of milliohms.
+pub const MILLIOHMS: u64 = 1000;
+
+/// The scale of the ratios returned by `ratio_ppm`.
+pub const PPM: u64 = 1_000_000;
+
+/// The ratio `num / den` in parts per million, rounded to the nearest part, for writing bounds
+/// on dividers in integer arithmetic. Returns `u64::MAX` if `den` is zero.
+/// # Example
+/// ```
+///     # use resistor_calc::*;
+///     assert_eq!(ratio_ppm(1, 4), 250_000);
+///     assert_eq!(ratio_ppm(2, 3), 666_667);
+/// ```
+pub fn ratio_ppm(num: u64, den: u64) -> u64 {
+    if den == 0 {
+        return u64::MAX;
+    }
+    let (num, den) = (num as u128, den as u128);
+    ((num * PPM as u128 + den / 2) / den) as u64
+}
+
+/// A search over resistor values held as whole numbers of milliohms, with bounds that return
+/// their error as an integer, so that solving needs no floating point. The order of the
+/// combinations and of results with equal error is fixed, so every target gives the same results.
+///
+/// The values can be given as integers with `new`, or converted once from the series of an
+/// `RCalc` with `from_rcalc`.
+/// # Example
+/// A divider with a ratio of 0.25, with the error in parts per million:
+/// ```
+///     # use resistor_calc::*;
+///     let series = RSeries::new(&[1.0, 1.1, 2.2, 3.3, 4.7]);
+///     let calc = FixedCalc::from_rcalc(&RCalc::new(vec![&series, &series]));
+///     let res = calc
+///         .calc(|v| Some(ratio_ppm(v[1], v[0] + v[1]).abs_diff(250_000)))
+///         .unwrap();
+///     let (err, values) = res.get(0).unwrap();
+///     assert_eq!(*err, 0);
+///     assert_eq!(values[0], 3 * values[1]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct FixedCalc {
+    values: Vec<Box<[u64]>>,
+}
+
+impl FixedCalc {
+    /// Creates a search over the values of R1, R2, ..., Rn, each in milliohms.
+    pub fn new(values: Vec<Vec<u64>>) -> Self {
+        FixedCalc {
+            values: values.into_iter().map(Vec::into_boxed_slice).collect(),
+        }
+    }
+
+    /// Creates a search over the values of the series of `rcalc`, rounded to the nearest
+    /// milliohm.
+    pub fn from_rcalc(rcalc: &RCalc) -> Self {
+        FixedCalc {
+            values: rcalc
+                .rs
+                .iter()
+                .map(|series| {
+                    series
+                        .values
+                        .iter()
+                        .map(|&v| (v * MILLIOHMS as f64 + 0.5) as u64)
+                        .collect()
+                })
+                .collect(),
+        }
+    }
+
+    /// The values of each resistor, in milliohms.
+    pub fn values(&self) -> &[Box<[u64]>] {
+        &self.values
+    }
+
+    /// Returns the number of combinations of values.
+    pub fn combinations(&self) -> u128 {
+        self.values.iter().map(|v| v.len() as u128).product()
+    }
+
+    /// As with `RCalc::calc`, calling `f` with the values of each combination in milliohms.
+    /// `f` should return `None` for unsuitable combinations and their error otherwise, in any
+    /// integer unit. Results are in order of increasing error, and otherwise in the order they
+    /// were searched.
+    pub fn calc(&self, f: impl Fn(&[u64]) -> Option<u64>) -> Option<FixedRes> {
+        let mut res = Vec::new();
+        if !self.values.is_empty() && self.values.iter().all(|v| !v.is_empty()) {
+            let mut idx = vec![0; self.values.len()];
+            let mut set: Vec<u64> = self.values.iter().map(|v| v[0]).collect();
+            loop {
+                if let Some(err) = f(&set) {
+                    res.push((err, set.clone().into_boxed_slice()));
+                }
+                // Step to the next combination, varying the last resistor fastest.
+                let mut pos = idx.len();
+                loop {
+                    if pos == 0 {
+                        res.sort_by_key(|&(err, _)| err);
+                        return Some(FixedRes { res }).filter(|r| !r.is_empty());
+                    }
+                    pos -= 1;
+                    idx[pos] += 1;
+                    if idx[pos] < self.values[pos].len() {
+                        set[pos] = self.values[pos][idx[pos]];
+                        break;
+                    }
+                    idx[pos] = 0;
+                    set[pos] = self.values[pos][0];
+                }
+            }
+        }
+        None
+    }
+}
+
+/// The results of a `FixedCalc`, as pairs of the error and the values in milliohms.
+#[derive(Debug, Clone)]
+pub struct FixedRes {
+    res: Vec<(u64, Box<[u64]>)>,
+}
+
+impl FixedRes {
+    /// Returns the number of results.
+    pub fn len(&self) -> usize {
+        self.res.len()
+    }
+
+    /// Returns true if there are no results.
+    pub fn is_empty(&self) -> bool {
+        self.res.is_empty()
+    }
+
+    /// Iterates over the results, from lowest to highest error.
+    pub fn iter(&self) -> impl Iterator<Item = &(u64, Box<[u64]>)> {
+        self.res.iter()
+    }
+
+    /// Returns the `i`th result.
+    pub fn get(&self, i: usize) -> Option<&(u64, Box<[u64]>)> {
+        self.res.get(i)
+    }
+}
@@ -0,0 +1,176 @@
+use std::fmt;
+
+use {format_value, Error, RCalc, RSeries, RSet, Suggestion};
+
+/// The standard series from finest to coarsest, each suggested in place of the one before it.
+const COARSER: [&str; 6] = ["E96", "E48", "E24", "E12", "E6", "E3"];
+
+/// The number of combinations sampled to suggest narrower ranges.
+const SAMPLES: usize = 2000;
+
+/// The number of results suggested for a search that keeps only the best.
+const TOP: usize = 100;
+
+/// A way to shrink a search that is too large, from `RCalc::guard`.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub enum Remedy {
+    /// Restrict a position to the range of values seen in sampled solutions, with
+    /// `RCalc::range`.
+    Range(Suggestion),
+    /// Draw a position from a coarser series, shrinking the search by `reduction`.
+    Series {
+        /// The position, counting from 1 as with R1, R2, ..., Rn.
+        position: usize,
+        /// The name of the coarser series.
+        series: String,
+        /// The factor by which the coarser series shrinks the search.
+        reduction: f64,
+    },
+    /// Keep only the best results, with `CalcOptions::top`. This bounds the memory of the search
+    /// but not its time.
+    Top(usize),
+}
+
+impl fmt::Display for Remedy {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Remedy::Range(ref s) => write!(
+                f,
+                "Restricting R{} to {}–{} reduces the space {:.1}x",
+                s.position,
+                format_value(s.min),
+                format_value(s.max),
+                s.reduction
+            ),
+            Remedy::Series {
+                position,
+                ref series,
+                reduction,
+            } => write!(
+                f,
+                "Drawing R{} from {} reduces the space {:.1}x",
+                position, series, reduction
+            ),
+            Remedy::Top(top) => {
+                write!(f, "Keeping only the best {} results bounds its memory", top)
+            }
+        }
+    }
+}
+
+/// A search refused by `RCalc::guard` for having more combinations than allowed, with ways to
+/// shrink it.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub struct Guardrail {
+    /// The number of combinations the search would evaluate.
+    pub combinations: u128,
+    /// The most combinations allowed.
+    pub threshold: u128,
+    /// Ways to shrink the search: narrower ranges and coarser series, most effective first, then
+    /// keeping only the best results.
+    pub remedies: Vec<Remedy>,
+}
+
+impl fmt::Display for Guardrail {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "The search has {} combinations, more than the limit of {}",
+            self.combinations, self.threshold
+        )?;
+        for remedy in &self.remedies {
+            write!(f, "\n{}", remedy)?;
+        }
+        Ok(())
+    }
+}
+
+impl From<Guardrail> for Error {
+    fn from(guard: Guardrail) -> Self {
+        Error::TooLarge(guard.to_string())
+    }
+}
+
+/// The next coarser standard series than `series`, keeping only its values within the range of
+/// `series` so that a position narrowed with `range` or `decades` stays narrowed.
+fn coarser(series: &RSeries) -> Option<(&'static str, usize)> {
+    let next = COARSER.iter().position(|&name| name == series.name())? + 1;
+    let name = *COARSER.get(next)?;
+    let (min, max) = series
+        .iter()
+        .fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), &v| {
+            (min.min(v), max.max(v))
+        });
+    let kept = RSeries::by_name(name)?
+        .iter()
+        .filter(|&&v| (min..=max).contains(&v))
+        .count();
+    Some((name, kept))
+}
+
+impl<'a> RCalc<'a> {
+    /// Checks that the search has at most `threshold` combinations, so that a problem that would
+    /// run for hours is caught before it starts. A larger search is refused with a `Guardrail`
+    /// suggesting how to shrink it: narrower ranges for the positions, found by sampling `f` as
+    /// with `estimate`, coarser series, or keeping only the best results.
+    /// # Example
+    /// ```
+    ///     # use resistor_calc::*;
+    ///     let op = ROpBuilder::new()
+    ///         .bound("R1 + R2 + R3 <= 1e4")
+    ///         .bound("R2 / (R1 + R2 + R3) ~ 0.25")
+    ///         .build();
+    ///     let rcalc = RCalc::e96(3);
+    ///     assert!(rcalc.guard(|rs| op.eval(rs), 1_000_000_000).is_ok());
+    ///     let guard = rcalc.guard(|rs| op.eval(rs), 1_000_000).unwrap_err();
+    ///     assert_eq!(guard.combinations, (96 * 7u128).pow(3));
+    ///     for remedy in &guard.remedies {
+    ///         println!("{}", remedy);
+    ///     }
+    /// ```
+    pub fn guard(
+        &self,
+        f: impl Fn(&RSet) -> Option<f64> + Sync,
+        threshold: u128,
+    ) -> Result<(), Guardrail> {
+        let combinations = self.combinations();
+        if combinations <= threshold {
+            return Ok(());
+        }
+        debug!(
+            "Refusing to search {} combinations, more than {}",
+            combinations, threshold
+        );
+        let mut remedies: Vec<(f64, Remedy)> = self
+            .estimate(f, SAMPLES)
+            .suggestions
+            .into_iter()
+            .map(|s| (s.reduction, Remedy::Range(s)))
+            .collect();
+        for (idx, r) in self.rs.iter().enumerate() {
+            if let Some((series, kept)) = coarser(r).filter(|&(_, kept)| kept > 0) {
+                let reduction = r.len() as f64 / kept as f64;
+                if reduction > 1.0 {
+                    remedies.push((
+                        reduction,
+                        Remedy::Series {
+                            position: idx + 1,
+                            series: series.to_string(),
+                            reduction,
+                        },
+                    ));
+                }
+            }
+        }
+        remedies.sort_by(|a, b| b.0.total_cmp(&a.0));
+        let mut remedies: Vec<Remedy> = remedies.into_iter().map(|(_, r)| r).collect();
+        remedies.push(Remedy::Top(TOP));
+        Err(Guardrail {
+            combinations,
+            threshold,
+            remedies,
+        })
+    }
+}
@@ -0,0 +1,92 @@
+use {parse_value, Error, RSeries};
+
+/// Splits a line of CSV into its fields, allowing quoted fields that contain commas.
+pub(crate) fn fields(line: &str) -> Vec<String> {
+    let mut fields = vec![String::new()];
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                chars.next();
+                fields.last_mut().unwrap().push('"');
+            }
+            '"' => quoted = !quoted,
+            ',' if !quoted => fields.push(String::new()),
+            _ => fields.last_mut().unwrap().push(c),
+        }
+    }
+    fields.into_iter().map(|f| f.trim().to_string()).collect()
+}
+
+/// Reads the values of the resistors in a bill of materials exported from KiCad as CSV, either
+/// from the symbol fields table or a BOM plugin. The columns are found by their headers, with
+/// references under `Reference`, `Ref` or `Designator` and values under `Value`. Rows whose
+/// references are not resistors, such as `C3`, are skipped, as are values that cannot be read
+/// with `parse_value`. The values are sorted and without duplicates.
+/// # Example
+/// ```
+///     # use resistor_calc::*;
+///     let bom = "\"Reference\",\"Value\",\"Footprint\"\n\
+///                \"R1,R4\",\"10k\",\"R_0603\"\n\
+///                \"C1\",\"100n\",\"C_0603\"\n\
+///                \"R2\",\"4K7\",\"R_0603\"\n";
+///     assert_eq!(bom_values(bom).unwrap(), vec![4700.0, 10e3]);
+/// ```
+pub fn bom_values(csv: &str) -> Result<Vec<f64>, Error> {
+    let mut lines = csv.lines().filter(|line| !line.trim().is_empty());
+    let header = fields(
+        lines
+            .next()
+            .ok_or_else(|| Error::Parse("The BOM is empty".to_string()))?,
+    );
+    let column = |names: &[&str]| {
+        header
+            .iter()
+            .position(|h| names.iter().any(|name| h.eq_ignore_ascii_case(name)))
+    };
+    let refs = column(&["Reference", "References", "Ref", "Designator"])
+        .ok_or_else(|| Error::Parse("The BOM has no reference column".to_string()))?;
+    let vals = column(&["Value"])
+        .ok_or_else(|| Error::Parse("The BOM has no value column".to_string()))?;
+
+    let mut values = Vec::new();
+    for row in lines.map(fields) {
+        let (r, v) = match (row.get(refs), row.get(vals)) {
+            (Some(r), Some(v)) => (r, v),
+            _ => continue,
+        };
+        let mut chars = r.chars();
+        if chars.next() != Some('R') || !chars.next().is_some_and(|c| c.is_ascii_digit()) {
+            continue;
+        }
+        match v.split_whitespace().next().and_then(parse_value) {
+            Some(value) => values.push(value),
+            None => warn!("Skipping {} with unreadable value '{}'", r, v),
+        }
+    }
+    values.sort_by(|a, b| a.total_cmp(b));
+    values.dedup();
+    Ok(values)
+}
+
+impl RSeries {
+    /// Defines a series of exactly the given values, without generating multiples, such as the
+    /// values already on a board or in a project library. Restricting a position to this series
+    /// reuses parts that are already stocked.
+    /// # Example
+    /// ```
+    ///     # use resistor_calc::*;
+    ///     let stock = RSeries::inventory("Stock", &[1e3, 4.7e3, 10e3]);
+    ///     let rcalc = RCalc::new(vec![&stock, &E24]);
+    ///     assert_eq!(rcalc.combinations(), 3 * 24 * 7);
+    /// ```
+    pub fn inventory(name: &str, values: &[f64]) -> Self {
+        RSeries::with_powers(name, values, &[1.0])
+    }
+
+    /// Defines a series of the resistor values used in a KiCad BOM, as read by `bom_values`.
+    pub fn from_kicad_bom(csv: &str) -> Result<Self, Error> {
+        Ok(RSeries::inventory("BOM", &bom_values(csv)?))
+    }
+}
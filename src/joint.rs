@@ -0,0 +1,205 @@
+use std::fmt::Write;
+
+use {_print_r, RCalc, ROp, RSet};
+
+/// The number of best results of each network considered by default when choosing the
+/// combination that shares the most values.
+const CANDIDATES: usize = 50;
+
+/// Solves several independent networks together, such as the dividers of one board, preferring
+/// solutions that share values between them so that the board needs fewer different parts.
+///
+/// Each network is solved on its own first, and the best `candidates` results of each are then
+/// searched for the combination with the lowest total error plus `weight` for each distinct
+/// value used across all of the networks.
+/// # Example
+/// ```
+///     # use resistor_calc::*;
+///     let half = ROpBuilder::new().bound("R2 / (R1 + R2) ~ 0.5").build();
+///     let third = ROpBuilder::new().bound("R2 / (R1 + R2) ~ 0.333").build();
+///     let res = JointCalc::new(0.01)
+///         .network(RCalc::e24(2), half)
+///         .network(RCalc::e24(2), third)
+///         .calc()
+///         .unwrap();
+///     // A 2:1 divider can reuse the value of one half of the 1:1 divider.
+///     assert!(res.values.len() <= 3);
+///     assert_eq!(res.sets.len(), 2);
+///     println!("{}", res.to_bom());
+/// ```
+pub struct JointCalc<'a> {
+    networks: Vec<(RCalc<'a>, ROp)>,
+    weight: f64,
+    candidates: usize,
+}
+
+/// The result of a `JointCalc`: a set of values for each network, in the order they were added.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub struct JointRes {
+    /// The values chosen for each network.
+    pub sets: Vec<RSet>,
+    /// The designator of each position of each network, as named with `RPosition::name` or
+    /// otherwise R1, R2, ..., Rn.
+    pub designators: Vec<Vec<String>>,
+    /// The error of the bounds of each network for its values.
+    pub errors: Vec<f64>,
+    /// The distinct values used across all of the networks, in increasing order.
+    pub values: Vec<f64>,
+    /// The total error of the networks plus the weight of each distinct value.
+    pub total: f64,
+}
+
+/// The state of the search over the candidates of each network.
+struct Search<'s> {
+    candidates: &'s [Vec<(f64, &'s RSet)>],
+    /// The lowest error of each network onwards, for bounding the remaining error.
+    remaining: Vec<f64>,
+    weight: f64,
+    chosen: Vec<usize>,
+    best: Option<(f64, Vec<usize>)>,
+}
+
+impl<'s> Search<'s> {
+    fn run(&mut self, net: usize, error: f64, values: &mut Vec<f64>) {
+        let cost = error + self.weight * values.len() as f64;
+        let bound = cost + self.remaining[net];
+        if self.best.as_ref().is_some_and(|b| bound >= b.0) {
+            return;
+        }
+        if net == self.candidates.len() {
+            self.best = Some((cost, self.chosen.clone()));
+            return;
+        }
+        for (i, &(err, rs)) in self.candidates[net].iter().enumerate() {
+            let len = values.len();
+            for &v in rs.values() {
+                if !values.contains(&v) {
+                    values.push(v);
+                }
+            }
+            self.chosen.push(i);
+            self.run(net + 1, error + err, values);
+            self.chosen.pop();
+            values.truncate(len);
+        }
+    }
+}
+
+impl<'a> JointCalc<'a> {
+    /// Creates a joint calculation with no networks, adding `weight` to the total error for each
+    /// distinct value used. A weight comparable to the errors of the bounds trades a little
+    /// accuracy for shared parts, and a weight of 0 solves each network on its own.
+    pub fn new(weight: f64) -> Self {
+        JointCalc {
+            networks: Vec::new(),
+            weight,
+            candidates: CANDIDATES,
+        }
+    }
+
+    /// Adds a network, with the positions to search and the bounds to satisfy.
+    pub fn network(mut self, rcalc: RCalc<'a>, op: ROp) -> Self {
+        self.networks.push((rcalc, op));
+        self
+    }
+
+    /// Sets the number of best results of each network to consider, 50 by default. More
+    /// candidates find more sharing at the cost of a longer search.
+    pub fn candidates(mut self, candidates: usize) -> Self {
+        self.candidates = candidates.max(1);
+        self
+    }
+
+    /// Solves the networks, returning `None` if there are none or any has no solution.
+    pub fn calc(&self) -> Option<JointRes> {
+        if self.networks.is_empty() {
+            return None;
+        }
+        let results = self
+            .networks
+            .iter()
+            .map(|(rcalc, op)| rcalc.calc(|rs| op.eval(rs)))
+            .collect::<Option<Vec<_>>>()?;
+        let candidates: Vec<Vec<(f64, &RSet)>> = results
+            .iter()
+            .map(|res| {
+                res.iter()
+                    .take(self.candidates)
+                    .map(|(err, rs)| (*err as f64 / 1e9, rs))
+                    .collect()
+            })
+            .collect();
+        let mut remaining = vec![0.0; candidates.len() + 1];
+        for net in (0..candidates.len()).rev() {
+            remaining[net] = remaining[net + 1] + candidates[net][0].0;
+        }
+
+        let mut search = Search {
+            candidates: &candidates,
+            remaining,
+            weight: self.weight,
+            chosen: Vec::new(),
+            best: None,
+        };
+        search.run(0, 0.0, &mut Vec::new());
+        let (total, chosen) = search.best?;
+        debug!("Joint search chose candidates {:?}", chosen);
+
+        let picks: Vec<&(f64, &RSet)> = chosen
+            .iter()
+            .zip(&candidates)
+            .map(|(&i, cands)| &cands[i])
+            .collect();
+        let mut values: Vec<f64> = picks
+            .iter()
+            .flat_map(|(_, rs)| rs.values().iter().cloned())
+            .collect();
+        values.sort_by(|a, b| a.total_cmp(b));
+        values.dedup();
+        Some(JointRes {
+            sets: picks.iter().map(|(_, rs)| (*rs).clone()).collect(),
+            designators: self
+                .networks
+                .iter()
+                .map(|(rcalc, _)| rcalc.names.clone())
+                .collect(),
+            errors: picks.iter().map(|(err, _)| *err).collect(),
+            values,
+            total,
+        })
+    }
+}
+
+impl JointRes {
+    /// Exports the shared bill of materials as CSV, with a row for each distinct value giving the
+    /// number of parts and where they are placed, as the network, counting from 1, and the
+    /// designator within it, such as `2/R1`.
+    pub fn to_bom(&self) -> String {
+        let mut out = String::from("Value,Quantity,Designators\n");
+        for value in &self.values {
+            let places: Vec<String> = self
+                .sets
+                .iter()
+                .zip(&self.designators)
+                .enumerate()
+                .flat_map(|(net, (rs, names))| {
+                    rs.values()
+                        .iter()
+                        .zip(names)
+                        .filter(|&(v, _)| v == value)
+                        .map(move |(_, name)| format!("{}/{}", net + 1, name))
+                })
+                .collect();
+            writeln!(
+                out,
+                "{},{},{}",
+                _print_r(value),
+                places.len(),
+                places.join(" ")
+            )
+            .unwrap();
+        }
+        out
+    }
+}
@@ -0,0 +1,229 @@
+use std::fmt::Write;
+
+use {format_value, RCalc, ROp, RSet};
+
+/// The size of each cell of the SVG heat map, in pixels.
+const CELL: usize = 6;
+/// The space left for the axis labels of the SVG heat map, in pixels.
+const MARGIN: usize = 60;
+
+/// The error of a problem over the grid of values of two resistors, with any others held fixed,
+/// produced by `ROp::landscape`. Plotting it shows how sharply the error rises away from the
+/// optimum and which bounds cut off regions of the grid.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub struct Landscape {
+    /// The position varied along the x axis, counting from 1 for R1.
+    pub x: usize,
+    /// The position varied along the y axis, counting from 1 for R1.
+    pub y: usize,
+    /// The values along the x axis, in increasing order.
+    pub x_values: Vec<f64>,
+    /// The values along the y axis, in increasing order.
+    pub y_values: Vec<f64>,
+    /// The error at each point, indexed by the y and then the x index, or `None` where a
+    /// comparison bound rejects the combination.
+    pub errors: Vec<Vec<Option<f64>>>,
+}
+
+/// The distinct values of a series in increasing order.
+fn sorted(values: &[f64]) -> Vec<f64> {
+    let mut values = values.to_vec();
+    values.sort_by(|a, b| a.total_cmp(b));
+    values.dedup();
+    values
+}
+
+/// A colour from dark blue for the lowest errors through to yellow for the highest, for `t` from
+/// 0 to 1.
+fn colour(t: f64) -> String {
+    let t = t.clamp(0.0, 1.0);
+    let lerp = |a: f64, b: f64| (a + (b - a) * t).round() as u8;
+    format!(
+        "#{:02x}{:02x}{:02x}",
+        lerp(31.0, 253.0),
+        lerp(12.0, 231.0),
+        lerp(72.0, 37.0)
+    )
+}
+
+impl ROp {
+    /// Samples the error of the bounds over every combination of the values of the series of
+    /// positions `x` and `y` of `rcalc`, counting from 1 for R1, with the other resistors held
+    /// at their values in `base`. For two resistor problems `base` only needs the right length;
+    /// for larger ones it is typically a result, so the landscape is a slice through the optimum.
+    /// # Example
+    /// ```
+    ///     # use resistor_calc::*;
+    ///     let op = ROpBuilder::new()
+    ///         .bound("R1 + R2 <= 100e3")
+    ///         .bound("R2 / (R1 + R2) ~ 0.25")
+    ///         .build();
+    ///     let rcalc = RCalc::e24(2);
+    ///     let land = op.landscape(&rcalc, 1, 2, &RSet::new(&[1e3, 1e3]));
+    ///     assert_eq!(land.x_values.len(), 24 * 7);
+    ///     let (r1, r2, err) = land.best().unwrap();
+    ///     assert!(err < 1e-12);
+    ///     assert!((r1 / r2 - 3.0).abs() < 1e-9);
+    ///     println!("{}", land.to_svg());
+    /// ```
+    pub fn landscape(&self, rcalc: &RCalc, x: usize, y: usize, base: &RSet) -> Landscape {
+        assert!(
+            x != y && (1..=rcalc.rs.len()).contains(&x) && (1..=rcalc.rs.len()).contains(&y),
+            "The axes must be two different positions of the calculation"
+        );
+        assert_eq!(base.values().len(), rcalc.rs.len());
+        let x_values = sorted(rcalc.rs[x - 1].values());
+        let y_values = sorted(rcalc.rs[y - 1].values());
+        let mut values = base.values().to_vec();
+        let errors = y_values
+            .iter()
+            .map(|&yv| {
+                x_values
+                    .iter()
+                    .map(|&xv| {
+                        values[x - 1] = xv;
+                        values[y - 1] = yv;
+                        self.eval_values(&values)
+                    })
+                    .collect()
+            })
+            .collect();
+        Landscape {
+            x,
+            y,
+            x_values,
+            y_values,
+            errors,
+        }
+    }
+}
+
+impl Landscape {
+    /// The values of the x and y resistors with the lowest error, and that error, or `None` if
+    /// every point is rejected.
+    pub fn best(&self) -> Option<(f64, f64, f64)> {
+        self.points()
+            .filter_map(|(x, y, err)| err.map(|err| (x, y, err)))
+            .min_by(|a, b| a.2.total_cmp(&b.2))
+    }
+
+    /// Iterates over every point as the x and y values and the error.
+    pub fn points<'s>(&'s self) -> impl Iterator<Item = (f64, f64, Option<f64>)> + 's {
+        self.y_values
+            .iter()
+            .zip(&self.errors)
+            .flat_map(move |(&y, row)| {
+                self.x_values
+                    .iter()
+                    .zip(row)
+                    .map(move |(&x, &err)| (x, y, err))
+            })
+    }
+
+    /// Exports the points as CSV with a row for each, with columns for the two values and the
+    /// error, which is left empty where the combination is rejected.
+    pub fn to_csv(&self) -> String {
+        let mut out = format!("R{},R{},error\n", self.x, self.y);
+        for (x, y, err) in self.points() {
+            write!(out, "{},{},", x, y).unwrap();
+            if let Some(err) = err {
+                write!(out, "{}", err).unwrap();
+            }
+            out.push('\n');
+        }
+        out
+    }
+
+    /// Renders the landscape as an SVG heat map, with values increasing to the right and upwards.
+    /// Errors are coloured on a log scale from dark blue for the lowest to yellow for the
+    /// highest, rejected combinations are grey and the best point is circled in red.
+    pub fn to_svg(&self) -> String {
+        let (cols, rows) = (self.x_values.len(), self.y_values.len());
+        let (width, height) = (MARGIN + cols * CELL + 10, rows * CELL + MARGIN + 10);
+        let logs: Vec<f64> = self
+            .points()
+            .filter_map(|(_, _, err)| err)
+            .map(|err| (err + 1e-12).log10())
+            .collect();
+        let lo = logs.iter().cloned().fold(f64::INFINITY, f64::min);
+        let hi = logs.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+        let cell_x = |i: usize| MARGIN + i * CELL;
+        let cell_y = |j: usize| 10 + (rows - 1 - j) * CELL;
+
+        let mut out = format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" \
+             font-family=\"sans-serif\" font-size=\"10\">\n",
+            width, height
+        );
+        for (j, row) in self.errors.iter().enumerate() {
+            for (i, err) in row.iter().enumerate() {
+                let fill = match *err {
+                    Some(err) if hi > lo => colour(((err + 1e-12).log10() - lo) / (hi - lo)),
+                    Some(_) => colour(0.0),
+                    None => "#cccccc".to_string(),
+                };
+                writeln!(
+                    out,
+                    "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"{}\"/>",
+                    cell_x(i),
+                    cell_y(j),
+                    CELL,
+                    CELL,
+                    fill
+                )
+                .unwrap();
+            }
+        }
+        if let Some((bx, by, _)) = self.best() {
+            let i = self.x_values.iter().position(|&v| v == bx).unwrap();
+            let j = self.y_values.iter().position(|&v| v == by).unwrap();
+            writeln!(
+                out,
+                "<circle cx=\"{}\" cy=\"{}\" r=\"{}\" fill=\"none\" stroke=\"red\"/>",
+                cell_x(i) + CELL / 2,
+                cell_y(j) + CELL / 2,
+                CELL
+            )
+            .unwrap();
+        }
+        // Label about ten ticks along each axis.
+        for i in (0..cols).step_by((cols / 10).max(1)) {
+            writeln!(
+                out,
+                "<text x=\"{}\" y=\"{}\" text-anchor=\"middle\">{}</text>",
+                cell_x(i) + CELL / 2,
+                height - MARGIN + 24,
+                format_value(self.x_values[i])
+            )
+            .unwrap();
+        }
+        for j in (0..rows).step_by((rows / 10).max(1)) {
+            writeln!(
+                out,
+                "<text x=\"{}\" y=\"{}\" text-anchor=\"end\">{}</text>",
+                MARGIN - 4,
+                cell_y(j) + CELL,
+                format_value(self.y_values[j])
+            )
+            .unwrap();
+        }
+        writeln!(
+            out,
+            "<text x=\"{}\" y=\"{}\" text-anchor=\"middle\">R{}</text>",
+            MARGIN + cols * CELL / 2,
+            height - 8,
+            self.x
+        )
+        .unwrap();
+        writeln!(
+            out,
+            "<text x=\"12\" y=\"{}\" text-anchor=\"middle\">R{}</text>",
+            10 + rows * CELL / 2,
+            self.y
+        )
+        .unwrap();
+        out.push_str("</svg>\n");
+        out
+    }
+}
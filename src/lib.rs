@@ -48,37 +48,247 @@
 //! Error: 0.000
 //! Values: R1: 130K, R2: 150K, R3: 20K
 //!```
+//!
+//! # `no_std`
+//! Without the default `std` feature the crate is `no_std` and needs only `alloc`, for use on
+//! microcontrollers and in other constrained environments. This keeps `RSeries`, `RSet` and the
+//! exhaustive search of `RCalc::calc` with bounds given as closures. The standard series
+//! constants, the string expression parser of `ROpBuilder` and the analyses of results need
+//! `std`. For targets without an FPU, `FixedCalc` searches values held as whole milliohms with
+//! bounds that compute their error in integers.
+
+#![cfg_attr(not(feature = "std"), no_std)]
 
+#[cfg(not(feature = "std"))]
+#[macro_use]
+extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std as alloc;
+#[cfg(feature = "std")]
+extern crate core;
 extern crate itertools;
+#[cfg(feature = "std")]
 #[macro_use]
 extern crate lazy_static;
+#[macro_use]
+extern crate log;
+#[cfg(feature = "python")]
+extern crate pyo3;
+extern crate rand;
+#[cfg(feature = "serialize")]
+extern crate serde;
+#[cfg(feature = "serialize")]
+#[macro_use]
+extern crate serde_derive;
+#[cfg(feature = "report")]
+extern crate toml;
 
 use itertools::Itertools;
 
-use std::fmt;
+use alloc::borrow::Cow;
+use alloc::boxed::Box;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt;
+#[cfg(feature = "std")]
+use std::time::Instant;
 
+#[cfg(feature = "expr_builder")]
+mod analysis;
+mod builder;
+#[cfg(feature = "std")]
+mod cache;
+mod check;
+mod error;
+#[cfg(feature = "std")]
+mod colour;
+#[cfg(feature = "columnar")]
+mod columnar;
+#[cfg(feature = "std")]
+mod estimate;
+#[cfg(feature = "expr_builder")]
+mod excitation;
+#[cfg(feature = "expr_builder")]
+mod dot;
+#[cfg(feature = "std")]
+mod executor;
+#[cfg(feature = "std")]
+mod guard;
+#[cfg(feature = "std")]
+mod export;
 #[cfg(feature = "expr_builder")]
 mod expr_builder;
+mod fixed;
+#[cfg(feature = "std")]
+mod inventory;
+#[cfg(feature = "expr_builder")]
+mod joint;
+#[cfg(feature = "expr_builder")]
+mod landscape;
+#[cfg(feature = "std")]
+mod marking;
+#[cfg(feature = "expr_builder")]
+mod netlist;
+#[cfg(feature = "std")]
+mod network;
+#[cfg(feature = "expr_builder")]
+mod notebook;
+#[cfg(feature = "std")]
+mod options;
+#[cfg(feature = "std")]
+mod price;
+#[cfg(feature = "std")]
+mod provider;
+#[cfg(feature = "expr_builder")]
+mod profile;
+#[cfg(feature = "expr_builder")]
+mod pulse;
+#[cfg(feature = "std")]
+mod ratio;
+#[cfg(feature = "std")]
+mod search;
+#[cfg(feature = "expr_builder")]
+pub mod templates;
+#[cfg(feature = "std")]
+mod upgrade;
 
 #[cfg(feature = "expr_builder")]
-pub use expr_builder::ROpBuilder;
+pub use analysis::{
+    BoundOutcome, BoundRange, BoundSigma, BoundStats, Distribution, MonteCarlo, Sensitivity,
+    YieldEstimate,
+};
+pub use builder::{RCalcBuilder, RPosition};
+pub use check::CheckReport;
+pub use error::Error;
+#[cfg(feature = "std")]
+pub use estimate::{Estimate, Suggestion};
+#[cfg(feature = "expr_builder")]
+pub use excitation::{Excitation, SelfHeating};
+#[cfg(feature = "expr_builder")]
+pub use expr_builder::{ROp, ROpBuilder};
+#[cfg(feature = "std")]
+pub use executor::{Executor, Inline, Task, Threads};
+#[cfg(feature = "std")]
+pub use guard::{Guardrail, Remedy};
+pub use fixed::{ratio_ppm, FixedCalc, FixedRes, MILLIOHMS, PPM};
+#[cfg(feature = "std")]
+pub use inventory::bom_values;
+#[cfg(feature = "expr_builder")]
+pub use joint::{JointCalc, JointRes};
+#[cfg(feature = "expr_builder")]
+pub use landscape::Landscape;
+#[cfg(feature = "std")]
+pub use marking::{
+    colour_bands, format_value, from_colour_bands, from_smd_code, parse_value, smd_code, Band,
+    SmdCode,
+};
+#[cfg(feature = "expr_builder")]
+pub use netlist::Netlist;
+#[cfg(feature = "expr_builder")]
+pub use notebook::HtmlTable;
+#[cfg(feature = "std")]
+pub use network::{johnson_noise, parallel};
+#[cfg(feature = "std")]
+pub use options::{CalcOptions, Dedup};
+#[cfg(feature = "std")]
+pub use price::{Price, PriceTable};
+#[cfg(feature = "expr_builder")]
+pub use profile::{BoundProfile, Profile};
+#[cfg(feature = "std")]
+pub use provider::{PartsProvider, Sourcing};
+#[cfg(feature = "expr_builder")]
+pub use pulse::{Package, PulseCheck};
+#[cfg(feature = "std")]
+pub use ratio::Ratio;
+#[cfg(feature = "std")]
+pub use search::Limits;
+#[cfg(feature = "std")]
+pub use upgrade::Upgrade;
+
+#[cfg(feature = "report")]
+mod preset;
+#[cfg(feature = "report")]
+mod report;
+
+#[cfg(feature = "report")]
+pub use report::{Problem, Report, ReportMatch};
+
+#[cfg(feature = "capi")]
+pub mod capi;
+#[cfg(feature = "python")]
+pub mod python;
+#[cfg(feature = "service")]
+pub mod service;
+#[cfg(feature = "units")]
+mod units;
+#[cfg(feature = "wasm")]
+pub mod wasm;
 
 const POWERS: &[f64] = &[1e0, 1e1, 1e2, 1e3, 1e4, 1e5, 1e6];
+#[cfg(feature = "std")]
+const CAP_POWERS: &[f64] = &[1e-12, 1e-11, 1e-10, 1e-9, 1e-8, 1e-7, 1e-6, 1e-5, 1e-4];
 
+#[cfg(feature = "std")]
 lazy_static! {
-    /// RSeries constant for the E3 standard series
-    pub static ref E3: RSeries = RSeries::new(&[1.0, 2.2, 4.7]);
-    /// RSeries constant for the E6 standard series
-    pub static ref E6: RSeries = RSeries::extend(&E3, &[1.5, 3.3, 6.8]);
-    /// RSeries constant for the E12 standard series
-    pub static ref E12: RSeries = RSeries::extend(&E6, &[1.2, 1.8, 2.7, 3.9, 5.6, 8.2]);
-    /// RSeries constant for the E24 standard series
+    /// RSeries constant for the E3 standard series, ±40% tolerance
+    pub static ref E3: RSeries = RSeries::named("E3", &[1.0, 2.2, 4.7]).with_tolerance(0.4);
+    /// RSeries constant for the E6 standard series, ±20% tolerance
+    pub static ref E6: RSeries = RSeries::extend("E6", &E3, &[1.5, 3.3, 6.8]).with_tolerance(0.2);
+    /// RSeries constant for the E12 standard series, ±10% tolerance
+    pub static ref E12: RSeries = RSeries::extend(
+        "E12",
+        &E6,
+        &[1.2, 1.8, 2.7, 3.9, 5.6, 8.2]
+    ).with_tolerance(0.1);
+    /// RSeries constant for the E24 standard series, ±5% tolerance
     pub static ref E24: RSeries = RSeries::extend(
+        "E24",
         &E12,
         &[1.1, 1.3, 1.6, 2.0, 2.4, 3.0, 3.6, 4.3, 5.1, 6.2, 7.5, 9.1]
-    );
+    ).with_tolerance(0.05);
+    /// RSeries constant for the E48 standard series, ±2% tolerance
+    pub static ref E48: RSeries = RSeries::named(
+        "E48",
+        &[
+            1.00, 1.05, 1.10, 1.15, 1.21, 1.27, 1.33, 1.40, 1.47, 1.54, 1.62, 1.69, 1.78, 1.87,
+            1.96, 2.05, 2.15, 2.26, 2.37, 2.49, 2.61, 2.74, 2.87, 3.01, 3.16, 3.32, 3.48, 3.65,
+            3.83, 4.02, 4.22, 4.42, 4.64, 4.87, 5.11, 5.36, 5.62, 5.90, 6.19, 6.49, 6.81, 7.15,
+            7.50, 7.87, 8.25, 8.66, 9.09, 9.53
+        ]
+    ).with_tolerance(0.02);
+    /// RSeries constant for the E96 standard series, ±1% tolerance
+    pub static ref E96: RSeries = RSeries::extend(
+        "E96",
+        &E48,
+        &[
+            1.02, 1.07, 1.13, 1.18, 1.24, 1.30, 1.37, 1.43, 1.50, 1.58, 1.65, 1.74, 1.82, 1.91,
+            2.00, 2.10, 2.21, 2.32, 2.43, 2.55, 2.67, 2.80, 2.94, 3.09, 3.24, 3.40, 3.57, 3.74,
+            3.92, 4.12, 4.32, 4.53, 4.75, 4.99, 5.23, 5.49, 5.76, 6.04, 6.34, 6.65, 6.98, 7.32,
+            7.68, 8.06, 8.45, 8.87, 9.31, 9.76
+        ]
+    ).with_tolerance(0.01);
+    /// RSeries constant for current sense resistors, the E24 values from 1mΩ to 910mΩ with ±1%
+    /// tolerance
+    pub static ref SHUNT: RSeries = RSeries::with_powers(
+        "Shunt",
+        &E24.decade(),
+        &[1e-3, 1e-2, 1e-1]
+    ).with_tolerance(0.01);
+    /// Series constant for capacitors from the E6 series, from 1pF to 680µF with ±20% tolerance
+    pub static ref C_E6: RSeries = RSeries::with_powers(
+        "E6 capacitors",
+        &E6.decade(),
+        CAP_POWERS
+    ).with_tolerance(0.2);
+    /// Series constant for capacitors from the E12 series, from 1pF to 820µF with ±10% tolerance
+    pub static ref C_E12: RSeries = RSeries::with_powers(
+        "E12 capacitors",
+        &E12.decade(),
+        CAP_POWERS
+    ).with_tolerance(0.1);
 }
 
+#[cfg(feature = "expr_builder")]
 pub(crate) fn _test_calc() -> RRes {
     let r = RCalc::e3(2);
     r.calc(ROpBuilder::new()
@@ -88,8 +298,13 @@ pub(crate) fn _test_calc() -> RRes {
 }
 
 /// A series of resistor values, constants are provided for standard resistor array values.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
 pub struct RSeries {
+    name: String,
+    tolerance: f64,
+    tempco: f64,
+    drift: f64,
     values: Box<[f64]>,
 }
 
@@ -102,18 +317,67 @@ impl RSeries {
     ///     let piher = RSeries::new(&[1.0, 2.0, 2.2, 2.5, 4.7, 5.0]);
     /// ```
     pub fn new(series: &[f64]) -> Self {
+        Self::named("Custom", series)
+    }
+
+    /// Defines a new series of resistor values as with `new`, but with a name that is used when
+    /// describing the series in reports.
+    /// # Example
+    /// ```
+    ///     # use resistor_calc::*;
+    ///     let piher = RSeries::named("Piher", &[1.0, 2.0, 2.2, 2.5, 4.7, 5.0]);
+    ///     assert_eq!(piher.name(), "Piher");
+    /// ```
+    pub fn named(name: &str, series: &[f64]) -> Self {
+        Self::with_powers(name, series, POWERS)
+    }
+
+    /// Defines a new named series of values as with `named`, but with the multiples to generate
+    /// given explicitly rather than Val * 1 to Val * 1M. This allows series outside of the usual
+    /// range, such as milliohm current sense resistors.
+    /// # Example
+    /// ```
+    ///     # use resistor_calc::*;
+    ///     let shunts = RSeries::with_powers("Shunt", &[1.0, 2.0, 5.0], &[1e-3, 1e-2]);
+    /// ```
+    pub fn with_powers(name: &str, series: &[f64], powers: &[f64]) -> Self {
         RSeries {
+            name: name.to_string(),
+            tolerance: 0.0,
+            tempco: 0.0,
+            drift: 0.0,
             values: series
                 .iter()
-                .cartesian_product(POWERS.iter())
+                .cartesian_product(powers.iter())
                 .map(|(val, pow)| val * pow)
                 .collect::<Vec<f64>>()
                 .into_boxed_slice(),
         }
     }
 
-    fn extend(base: &RSeries, add: &[f64]) -> Self {
+    /// A copy of the series with only the values that `keep` accepts.
+    fn filtered(&self, keep: impl Fn(f64) -> bool) -> Self {
+        RSeries {
+            values: self.iter().cloned().filter(|&v| keep(v)).collect(),
+            ..self.clone()
+        }
+    }
+
+    /// A copy of the series with `value` as its only value, keeping its name and tolerances.
+    fn pinned(&self, value: f64) -> Self {
         RSeries {
+            values: vec![value].into_boxed_slice(),
+            ..self.clone()
+        }
+    }
+
+    #[cfg(feature = "std")]
+    fn extend(name: &str, base: &RSeries, add: &[f64]) -> Self {
+        RSeries {
+            name: name.to_string(),
+            tolerance: base.tolerance,
+            tempco: base.tempco,
+            drift: base.drift,
             values: base.iter()
                 .cloned()
                 .chain(
@@ -126,6 +390,133 @@ impl RSeries {
         }
     }
 
+    /// Sets the fractional tolerance of parts drawn from this series, e.g. `0.01` for ±1%. This
+    /// is used by the tolerance analyses on `RRes`. Custom series default to a tolerance of zero.
+    /// # Example
+    /// ```
+    ///     # use resistor_calc::*;
+    ///     let piher = RSeries::new(&[1.0, 2.0, 2.2, 2.5, 4.7, 5.0]).with_tolerance(0.2);
+    ///     assert_eq!(piher.tolerance(), 0.2);
+    /// ```
+    pub fn with_tolerance(mut self, tolerance: f64) -> Self {
+        self.tolerance = tolerance;
+        self
+    }
+
+    /// Sets the temperature coefficient of parts drawn from this series in ppm/°C, used by
+    /// `RRes::temperature_drift`. All series default to a temperature coefficient of zero.
+    /// # Example
+    /// ```
+    ///     # use resistor_calc::*;
+    ///     let thick_film = E24.clone().with_tempco(200.0);
+    ///     assert_eq!(thick_film.tempco(), 200.0);
+    /// ```
+    pub fn with_tempco(mut self, ppm: f64) -> Self {
+        self.tempco = ppm;
+        self
+    }
+
+    /// Sets the fractional long-term drift of parts drawn from this series over their lifetime,
+    /// e.g. `0.005` for ±0.5%, used by `RRes::end_of_life`. All series default to zero drift.
+    /// # Example
+    /// ```
+    ///     # use resistor_calc::*;
+    ///     let aged = E96.clone().with_drift(0.005);
+    ///     assert_eq!(aged.drift(), 0.005);
+    /// ```
+    pub fn with_drift(mut self, drift: f64) -> Self {
+        self.drift = drift;
+        self
+    }
+
+    /// The name of the series, such as `E24`.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The fractional tolerance of parts drawn from this series.
+    pub fn tolerance(&self) -> f64 {
+        self.tolerance
+    }
+
+    /// The temperature coefficient of parts drawn from this series in ppm/°C.
+    pub fn tempco(&self) -> f64 {
+        self.tempco
+    }
+
+    /// The fractional long-term drift of parts drawn from this series.
+    pub fn drift(&self) -> f64 {
+        self.drift
+    }
+
+    /// The names of the standard series constants accepted by `RSeries::by_name`.
+    /// # Example
+    /// ```
+    ///     # use resistor_calc::*;
+    ///     for name in RSeries::names() {
+    ///         let series = RSeries::by_name(name).unwrap();
+    ///         println!("{}: {} values", series.name(), series.values().len());
+    ///     }
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn names() -> &'static [&'static str] {
+        &["E3", "E6", "E12", "E24", "E48", "E96", "SHUNT", "C_E6", "C_E12"]
+    }
+
+    /// The values of the series, in the order they are searched.
+    pub fn values(&self) -> &[f64] {
+        &self.values
+    }
+
+    /// Looks up one of the standard series constants by its name, ignoring case. Capacitor
+    /// series are named as in `C_E6`.
+    /// # Example
+    /// ```
+    ///     # use resistor_calc::*;
+    ///     assert_eq!(RSeries::by_name("e96").unwrap().name(), "E96");
+    ///     assert!(RSeries::by_name("E7").is_none());
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn by_name(name: &str) -> Option<&'static RSeries> {
+        match name.to_uppercase().as_str() {
+            "E3" => Some(&E3),
+            "E6" => Some(&E6),
+            "E12" => Some(&E12),
+            "E24" => Some(&E24),
+            "E48" => Some(&E48),
+            "E96" => Some(&E96),
+            "SHUNT" => Some(&SHUNT),
+            "C_E6" => Some(&C_E6),
+            "C_E12" => Some(&C_E12),
+            _ => None,
+        }
+    }
+
+    /// Returns the closest values of the series at or below, and at or above, `value`.
+    /// # Example
+    /// ```
+    ///     # use resistor_calc::*;
+    ///     assert_eq!(E12.nearest(3742.0), (Some(3300.0), Some(3900.0)));
+    /// ```
+    pub fn nearest(&self, value: f64) -> (Option<f64>, Option<f64>) {
+        let closest = |keep: fn(f64, f64) -> f64, ok: &dyn Fn(f64) -> bool| {
+            self.iter()
+                .cloned()
+                .filter(|&v| ok(v))
+                .fold(None, |best: Option<f64>, v| Some(best.map_or(v, |b| keep(b, v))))
+        };
+        (
+            closest(f64::max, &|v| v <= value),
+            closest(f64::min, &|v| v >= value),
+        )
+    }
+
+    /// The base values of the series within a single decade.
+    #[cfg(feature = "std")]
+    fn decade(&self) -> Vec<f64> {
+        self.iter().cloned().filter(|v| *v >= 1.0 && *v < 10.0).collect()
+    }
+
     fn iter(&self) -> impl Iterator<Item = &f64> + Clone {
         self.values.iter()
     }
@@ -133,6 +524,23 @@ impl RSeries {
     fn len(&self) -> usize {
         self.values.len()
     }
+
+    fn position(&self, name: &str, package: Option<&str>) -> Position {
+        let (min, max) = self.iter()
+            .fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), &v| {
+                (min.min(v), max.max(v))
+            });
+        Position {
+            name: name.to_string(),
+            package: package.map(ToString::to_string),
+            series: self.name.clone(),
+            min,
+            max,
+            tolerance: self.tolerance,
+            tempco: self.tempco,
+            drift: self.drift,
+        }
+    }
 }
 
 fn _format_rval(r: f64, unit: &str) -> String {
@@ -155,16 +563,30 @@ fn _print_r(r: &f64) -> String {
     }
 }
 
+#[cfg(feature = "std")]
 fn _print_res(r: &(u64, RSet)) {
     let &(r, ref v) = r;
     println!("Error: {:.3}\nValues: {}", (r as f64) / 1e9, v);
 }
 
 /// A binding of values to the set of resistors in a calculation.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
 pub struct RSet(Box<[f64]>);
 
 impl RSet {
+    /// Creates a set from explicit values for R1, R2, ..., Rn. The values need not come from any
+    /// series, which allows hand-picked sets to be checked against bounds.
+    /// # Example
+    /// ```
+    ///     # use resistor_calc::*;
+    ///     let rs = RSet::new(&[13e3, 15e3, 2e3]);
+    ///     assert_eq!(rs.r(2), 15e3);
+    /// ```
+    pub fn new(values: &[f64]) -> Self {
+        RSet(values.to_vec().into_boxed_slice())
+    }
+
     /// Retrieves the value of R{idx}, starting from R1, R2, ..., Rn
     /// # Examples
     /// ```
@@ -185,42 +607,144 @@ impl RSet {
         self.0[idx - 1]
     }
 
+    /// Returns the values of R1, R2, ..., Rn.
+    pub fn values(&self) -> &[f64] {
+        &self.0
+    }
+
     /// Returns the sum of all the values in the set. Good for presenting overall bounds on dividers.
     pub fn sum(&self) -> f64 {
         self.0.iter().sum()
     }
+
+    /// The values scaled so that R1 lies in the decade from 1 to 10, rounded to a millionth, a key
+    /// shared by all sets whose values differ only by a common power of ten.
+    #[cfg(feature = "std")]
+    pub(crate) fn decade_key(&self) -> Vec<u64> {
+        let scale = 10f64.powi(-self.0[0].log10().floor() as i32);
+        self.0
+            .iter()
+            .map(|v| (v * scale * 1e6).round() as u64)
+            .collect()
+    }
 }
 
 impl fmt::Display for RSet {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         let sep = if f.alternate() { "\n" } else { ", " };
-        write!(
-            f,
-            "{}",
-            self.0
-                .iter()
-                .enumerate()
-                .map(|(i, r)| format!("R{}: {}", i + 1, _print_r(r)))
-                .join(sep)
-        )
+        for (i, r) in self.0.iter().enumerate() {
+            if i > 0 {
+                f.write_str(sep)?;
+            }
+            write!(f, "R{}: {}", i + 1, _print_r(r))?;
+        }
+        Ok(())
     }
 }
 
+/// Measures the time taken by a calculation. There is no clock on `wasm32-unknown-unknown` or
+/// without `std`, so there it always reads zero.
+#[cfg(feature = "std")]
+pub(crate) struct Stopwatch(Option<Instant>);
+#[cfg(not(feature = "std"))]
+pub(crate) struct Stopwatch;
+
+#[cfg(feature = "std")]
+impl Stopwatch {
+    pub(crate) fn start() -> Self {
+        if cfg!(target_arch = "wasm32") {
+            Stopwatch(None)
+        } else {
+            Stopwatch(Some(Instant::now()))
+        }
+    }
+
+    /// The time since the stopwatch was started, in seconds.
+    pub(crate) fn secs(&self) -> f64 {
+        self.0.map_or(0.0, |start| start.elapsed().as_secs_f64())
+    }
+}
+
+#[cfg(not(feature = "std"))]
+impl Stopwatch {
+    pub(crate) fn start() -> Self {
+        Stopwatch
+    }
+
+    pub(crate) fn secs(&self) -> f64 {
+        0.0
+    }
+}
+
+/// Converts an error to the parts in a billion stored in results. `f64::round` needs `std`, but
+/// errors are never negative, so otherwise adding a half and truncating gives the same result.
+fn ppb(err: f64) -> u64 {
+    #[cfg(feature = "std")]
+    {
+        (err * 1e9).round() as u64
+    }
+    #[cfg(not(feature = "std"))]
+    {
+        (err * 1e9 + 0.5) as u64
+    }
+}
+
+/// How many combinations between calls of the progress callback of `RCalc::calc_with_progress`.
+#[cfg(not(feature = "std"))]
+const PROGRESS_INTERVAL: usize = 10_000;
+
+/// The progress of a search, passed to the callback of `RCalc::calc_with_progress`.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub struct Progress {
+    /// The number of combinations evaluated so far.
+    pub evaluated: u128,
+    /// The number of combinations that satisfied the bounds so far.
+    pub accepted: u128,
+    /// The total number of combinations that will be evaluated.
+    pub total: u128,
+    /// The lowest error found so far, if any combination has satisfied the bounds.
+    pub best: Option<f64>,
+}
+
+/// Details of the series a resistor position was drawn from, retained for analysis of results.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+#[cfg_attr(not(feature = "std"), allow(dead_code))]
+struct Position {
+    #[cfg_attr(feature = "serialize", serde(default))]
+    name: String,
+    #[cfg_attr(feature = "serialize", serde(default))]
+    package: Option<String>,
+    series: String,
+    min: f64,
+    max: f64,
+    tolerance: f64,
+    tempco: f64,
+    drift: f64,
+}
+
 /// Stores the result of a calculation.
 #[derive(Debug)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
 pub struct RRes {
     res: Vec<(u64, RSet)>,
+    #[cfg_attr(not(feature = "std"), allow(dead_code))]
+    pos: Vec<Position>,
+    partial: bool,
 }
 
 impl RRes {
+    #[cfg(feature = "std")]
+    fn best(&self) -> impl Iterator<Item = &(u64, RSet)> {
+        let best_err = self.res[0].0;
+        self.res.iter().take_while(move |(err, _)| *err == best_err)
+    }
+
     /// Print all combinations that share the lowest error value.
+    #[cfg(feature = "std")]
     pub fn print_best(&self) {
-        let best_err = self.res[0].0;
-        for (idx, res) in self.res
-            .iter()
-            .take_while(|(err, _)| *err == best_err)
-            .enumerate()
-        {
+        for (idx, res) in self.best().enumerate() {
             println!("Match {}:", idx + 1);
             _print_res(res);
             println!();
@@ -234,12 +758,104 @@ impl RRes {
     pub fn iter(&self) -> impl Iterator<Item = &(u64, RSet)> {
         self.res.iter()
     }
+
+    /// Returns the number of results.
+    pub fn len(&self) -> usize {
+        self.res.len()
+    }
+
+    /// Returns true if there are no results.
+    pub fn is_empty(&self) -> bool {
+        self.res.is_empty()
+    }
+
+    /// Returns true if the search was stopped early by a time limit, so better combinations may
+    /// exist than those found.
+    pub fn is_partial(&self) -> bool {
+        self.partial
+    }
+
+    /// Returns the `i`th result, counting from zero in the same order as `iter`.
+    pub fn get(&self, i: usize) -> Option<&(u64, RSet)> {
+        self.res.get(i)
+    }
+
+    /// The results at the indices `idx`, counting from zero as with `get`, in the order given,
+    /// such as a few picked out for export. Returns `None` if any index is not that of a result.
+    /// # Example
+    /// ```
+    ///     # use resistor_calc::*;
+    ///     let res = RCalc::e24(2)
+    ///         .calc(ROpBuilder::new().bound("R2 / (R1 + R2) ~ 0.25").finish())
+    ///         .unwrap();
+    ///     let picked = res.select(&[3, 0]).unwrap();
+    ///     assert_eq!(picked.get(1).unwrap().1.values(), res.get(0).unwrap().1.values());
+    ///     assert!(res.select(&[res.len()]).is_none());
+    /// ```
+    pub fn select(&self, idx: &[usize]) -> Option<RRes> {
+        Some(RRes {
+            res: idx
+                .iter()
+                .map(|&i| self.res.get(i).cloned())
+                .collect::<Option<_>>()?,
+            pos: self.pos.clone(),
+            partial: self.partial,
+        })
+    }
+
+    /// Orders results of equal error, such as the same ratio scaled to different decades, by
+    /// their total resistance: the low impedance variants first for noise sensitive designs, or
+    /// the high impedance ones first for designs that must draw little current.
+    /// # Example
+    /// ```
+    ///     # use resistor_calc::*;
+    ///     let mut res = RCalc::e24(2)
+    ///         .calc(ROpBuilder::new().bound("R2 / (R1 + R2) ~ 0.25").finish())
+    ///         .unwrap();
+    ///     res.order_decades(DecadeOrder::Higher);
+    ///     assert_eq!(res.get(0).unwrap().1.values(), &[3.9e6, 1.3e6]);
+    ///     res.order_decades(DecadeOrder::Lower);
+    ///     assert_eq!(res.get(0).unwrap().1.values(), &[3.0, 1.0]);
+    /// ```
+    pub fn order_decades(&mut self, order: DecadeOrder) {
+        self.res.sort_by(|(a_err, a), (b_err, b)| {
+            let by_total = match order {
+                DecadeOrder::Lower => a.sum().total_cmp(&b.sum()),
+                DecadeOrder::Higher => b.sum().total_cmp(&a.sum()),
+            };
+            a_err.cmp(b_err).then(by_total)
+        });
+    }
+}
+
+/// Which results of equal error `RRes::order_decades` puts first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub enum DecadeOrder {
+    /// The lowest total resistance first.
+    Lower,
+    /// The highest total resistance first.
+    Higher,
 }
 
 /// Main calculator struct
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct RCalc<'a> {
-    rs: Vec<&'a RSeries>,
+    rs: Vec<Cow<'a, RSeries>>,
+    names: Vec<String>,
+    packages: Vec<Option<String>>,
+    #[cfg(feature = "std")]
+    cache: cache::Cache,
+}
+
+/// Whether `value` lies in one of `decades`, each named by its lowest value. The bounds are
+/// widened slightly so that values computed as a multiple of a power of ten fall in the decade
+/// they are written in.
+fn in_decades(value: f64, decades: &[f64]) -> bool {
+    decades.iter().any(|&decade| {
+        let lo = decade * (1.0 - 1e-9);
+        (lo..lo * 10.0).contains(&value)
+    })
 }
 
 impl<'a> RCalc<'a> {
@@ -253,29 +869,147 @@ impl<'a> RCalc<'a> {
     ///     let rcal = RCalc::new(vec![&E24, &E24, &E6, &E12]);
     /// ```
     pub fn new(rs: Vec<&'a RSeries>) -> Self {
-        RCalc { rs }
+        RCalc {
+            names: (1..=rs.len()).map(|i| format!("R{}", i)).collect(),
+            packages: vec![None; rs.len()],
+            rs: rs.into_iter().map(Cow::Borrowed).collect(),
+            #[cfg(feature = "std")]
+            cache: cache::Cache::default(),
+        }
     }
 
     /// Creates a new RCalc with `count` resistors drawn from the E3 series.
+    #[cfg(feature = "std")]
     pub fn e3(count: usize) -> Self {
         Self::new(vec![&E3; count])
     }
 
     /// Creates a new RCalc with `count` resistors drawn from the E6 series.
+    #[cfg(feature = "std")]
     pub fn e6(count: usize) -> Self {
         Self::new(vec![&E6; count])
     }
 
     /// Creates a new RCalc with `count` resistors drawn from the E12 series.
+    #[cfg(feature = "std")]
     pub fn e12(count: usize) -> Self {
         Self::new(vec![&E12; count])
     }
 
     /// Creates a new RCalc with `count` resistors drawn from the E24 series.
+    #[cfg(feature = "std")]
     pub fn e24(count: usize) -> Self {
         Self::new(vec![&E24; count])
     }
 
+    /// Creates a new RCalc with `count` resistors drawn from the E48 series.
+    #[cfg(feature = "std")]
+    pub fn e48(count: usize) -> Self {
+        Self::new(vec![&E48; count])
+    }
+
+    /// Creates a new RCalc with `count` resistors drawn from the E96 series.
+    #[cfg(feature = "std")]
+    pub fn e96(count: usize) -> Self {
+        Self::new(vec![&E96; count])
+    }
+
+    /// Fixes position `idx`, counting from 1 for R1, at `value`, such as a resistor that has
+    /// already been chosen or is set by an existing design, so that only the remaining positions
+    /// are searched. The tolerances of the position's series are kept for analysis.
+    /// # Example
+    /// ```
+    ///     # use resistor_calc::*;
+    ///     let rcalc = RCalc::e24(3).fix(2, 10e3);
+    ///     assert_eq!(rcalc.combinations(), (24 * 7) * (24 * 7));
+    ///     let res = rcalc
+    ///         .calc(ROpBuilder::new().bound("R1 + R2 + R3 ~ 40e3").finish())
+    ///         .unwrap();
+    ///     assert!(res.iter().all(|(_, rs)| rs.values()[1] == 10e3));
+    /// ```
+    pub fn fix(self, idx: usize, value: f64) -> Self {
+        self.try_fix(idx, value).unwrap()
+    }
+
+    /// As with `fix`, but returning an error rather than panicking if `idx` is not a position,
+    /// for positions that come from user input.
+    /// # Example
+    /// ```
+    ///     # use resistor_calc::*;
+    ///     assert!(RCalc::e24(2).try_fix(2, 10e3).is_ok());
+    ///     assert!(matches!(RCalc::e24(2).try_fix(3, 10e3), Err(Error::Parse(_))));
+    /// ```
+    pub fn try_fix(mut self, idx: usize, value: f64) -> Result<Self, Error> {
+        let i = self.position_index(idx)?;
+        self.rs[i] = Cow::Owned(self.rs[i].pinned(value));
+        #[cfg(feature = "std")]
+        self.cache.invalidate();
+        Ok(self)
+    }
+
+    /// Limits position `idx`, counting from 1 for R1, to the values of its series from `min` to
+    /// `max` ohms inclusive. The values are removed before the search rather than rejected by a
+    /// bound, so each range shrinks the number of combinations searched.
+    /// # Example
+    /// ```
+    ///     # use resistor_calc::*;
+    ///     let rcalc = RCalc::e24(2).range(2, 1e3, 10e3);
+    ///     // 1K to 9.1K and 10K.
+    ///     assert_eq!(rcalc.combinations(), (24 * 7) * (24 + 1));
+    ///     let res = rcalc
+    ///         .calc(ROpBuilder::new().bound("R2 / (R1 + R2) ~ 0.25").finish())
+    ///         .unwrap();
+    ///     assert!(res.iter().all(|(_, rs)| (1e3..=10e3).contains(&rs.values()[1])));
+    /// ```
+    pub fn range(self, idx: usize, min: f64, max: f64) -> Self {
+        self.try_range(idx, min, max).unwrap()
+    }
+
+    /// As with `range`, but returning an error rather than panicking if `idx` is not a position.
+    pub fn try_range(mut self, idx: usize, min: f64, max: f64) -> Result<Self, Error> {
+        let i = self.position_index(idx)?;
+        self.rs[i] = Cow::Owned(self.rs[i].filtered(|v| (min..=max).contains(&v)));
+        #[cfg(feature = "std")]
+        self.cache.invalidate();
+        Ok(self)
+    }
+
+    /// Limits position `idx`, counting from 1 for R1, to the values of its series in the given
+    /// decades, each named by its lowest value, such as `1e3` for 1K to 9.1K in the E24 series.
+    /// As with `range`, the other values are removed before the search.
+    /// # Example
+    /// ```
+    ///     # use resistor_calc::*;
+    ///     // R3 only in the kilohm decades, R1 in the 10K decade.
+    ///     let rcalc = RCalc::e24(3).decades(3, &[1e3, 10e3, 100e3]).decades(1, &[10e3]);
+    ///     assert_eq!(rcalc.combinations(), 24 * (24 * 7) * (24 * 3));
+    /// ```
+    pub fn decades(self, idx: usize, decades: &[f64]) -> Self {
+        self.try_decades(idx, decades).unwrap()
+    }
+
+    /// As with `decades`, but returning an error rather than panicking if `idx` is not a
+    /// position.
+    pub fn try_decades(mut self, idx: usize, decades: &[f64]) -> Result<Self, Error> {
+        let i = self.position_index(idx)?;
+        self.rs[i] = Cow::Owned(self.rs[i].filtered(|v| in_decades(v, decades)));
+        #[cfg(feature = "std")]
+        self.cache.invalidate();
+        Ok(self)
+    }
+
+    /// The index into `rs` of position `idx`, counting from 1 for R1.
+    fn position_index(&self, idx: usize) -> Result<usize, Error> {
+        if (1..=self.rs.len()).contains(&idx) {
+            Ok(idx - 1)
+        } else {
+            Err(Error::Parse(format!(
+                "R{} is not a position of the calculation",
+                idx
+            )))
+        }
+    }
+
     /// Returns the number of combinations of values that exist for the configured resistors and
     /// series. This will fairly directly map to the amount of time taken to calculate value
     /// combinations.
@@ -283,24 +1017,133 @@ impl<'a> RCalc<'a> {
         self.rs.iter().map(|r| r.len() as u128).product()
     }
 
+    /// Details of the series of each position, for storing alongside results. These are kept
+    /// between calculations, as they depend only on the positions.
+    #[cfg(feature = "std")]
+    fn positions(&self) -> Vec<Position> {
+        self.cache
+            .positions
+            .get_or_init(|| self.describe_positions())
+            .clone()
+    }
+
+    #[cfg(not(feature = "std"))]
+    fn positions(&self) -> Vec<Position> {
+        self.describe_positions()
+    }
+
+    fn describe_positions(&self) -> Vec<Position> {
+        self.rs
+            .iter()
+            .zip(&self.names)
+            .zip(&self.packages)
+            .map(|((r, name), package)| r.position(name, package.as_deref()))
+            .collect()
+    }
+
+    /// Iterates over every combination of values for the configured resistors, varying the last
+    /// resistor fastest.
+    fn sets<'s>(&'s self) -> impl Iterator<Item = Vec<f64>> + 's {
+        let mut idx = vec![0; self.rs.len()];
+        let mut done = self.rs.is_empty() || self.rs.iter().any(|r| r.len() == 0);
+        core::iter::from_fn(move || {
+            if done {
+                return None;
+            }
+            let v = idx.iter().zip(&self.rs).map(|(&i, r)| r.values[i]).collect();
+            done = true;
+            for (i, r) in idx.iter_mut().zip(&self.rs).rev() {
+                *i += 1;
+                if *i < r.len() {
+                    done = false;
+                    break;
+                }
+                *i = 0;
+            }
+            Some(v)
+        })
+    }
+
     /// Given a testing function `f` thats maps from a set of input resistors to `Option<f64>` this
     /// will calculate the results for the resistors and series configured and return the result as
     /// an `RRes`. `f` should map combinations that are unsuitable to `None` and combinations that
     /// are suitable to `Some(err)` where `err` is a `f64` describing how far from perfect the
     /// combination is. `f` is often supplied with the use of the `ROpBuilder` struct.
-    pub fn calc(&self, f: impl Fn(&RSet) -> Option<f64>) -> Option<RRes> {
-        let mut res: Vec<(u64, RSet)> = self.rs
-            .iter()
-            .map(|r| r.iter().cloned())
-            .multi_cartesian_product()
-            .filter_map(|v| {
-                let rs = RSet(v.into_boxed_slice());
-                f(&rs).map(|err| ((err * 1e9).round() as u64, rs))
-            })
-            .collect();
+    pub fn calc(&self, f: impl Fn(&RSet) -> Option<f64> + Sync) -> Option<RRes> {
+        self.calc_with_progress(f, |_| ())
+    }
+
+    /// As with `calc`, but `progress` is called periodically during the search and once at the
+    /// end with how far it has got, allowing long searches to report their progress.
+    /// # Example
+    /// ```
+    ///     # use resistor_calc::*;
+    ///     let res = RCalc::e24(2).calc_with_progress(
+    ///         ROpBuilder::new().bound("R2 / (R1 + R2) ~ 0.25").finish(),
+    ///         |p| eprint!("\r{}/{} evaluated", p.evaluated, p.total),
+    ///     );
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn calc_with_progress(
+        &self,
+        f: impl Fn(&RSet) -> Option<f64> + Sync,
+        progress: impl FnMut(&Progress),
+    ) -> Option<RRes> {
+        self.calc_with(f, CalcOptions::new().progress(progress)).ok()
+    }
+
+    /// As with `calc`, but `progress` is called periodically during the search and once at the
+    /// end with how far it has got. Without `std` there are no threads or clocks, so the search
+    /// is a plain loop over the combinations.
+    #[cfg(not(feature = "std"))]
+    pub fn calc_with_progress(
+        &self,
+        f: impl Fn(&RSet) -> Option<f64> + Sync,
+        mut progress: impl FnMut(&Progress),
+    ) -> Option<RRes> {
+        let mut state = Progress {
+            evaluated: 0,
+            accepted: 0,
+            total: self.combinations(),
+            best: None,
+        };
+        debug!(
+            "Searching {} combinations of {} positions",
+            state.total,
+            self.rs.len()
+        );
+        let start = Stopwatch::start();
+        let mut res: Vec<(u64, RSet)> = Vec::new();
+        let mut countdown = PROGRESS_INTERVAL;
+        for v in self.sets() {
+            let rs = RSet(v.into_boxed_slice());
+            state.evaluated += 1;
+            if let Some(err) = f(&rs) {
+                state.accepted += 1;
+                state.best = Some(state.best.map_or(err, |best: f64| best.min(err)));
+                res.push((ppb(err), rs));
+            }
+            countdown -= 1;
+            if countdown == 0 {
+                progress(&state);
+                countdown = PROGRESS_INTERVAL;
+            }
+        }
+        progress(&state);
+        info!(
+            "Searched {} combinations in {:.3}s, {} accepted, best error {:?}",
+            state.evaluated,
+            start.secs(),
+            state.accepted,
+            state.best
+        );
         res.sort_by_key(|(err, _rs)| *err);
         if !res.is_empty() {
-            Some(RRes { res })
+            Some(RRes {
+                res,
+                pos: self.positions(),
+                partial: false,
+            })
         } else {
             None
         }
@@ -1,22 +1,73 @@
+#[macro_use]
+extern crate clap;
+extern crate env_logger;
+extern crate log;
 extern crate resistor_calc;
+extern crate serde_json;
 
-use resistor_calc::*;
+use std::env;
 
-fn main() {
-    let rcalc = RCalc::new(vec![&E24, &E6, &E24]);
+use clap::{App, AppSettings, Arg};
+
+mod cli;
 
-    println!("Number of combinations: {}", rcalc.combinations());
+use cli::arg_error;
 
-    let res = rcalc
-        .calc(
-            ROpBuilder::new()
-                .bound("R1+R2+R3 <= 1e6")
-                .bound("R1+R2+R3 >= 1e4")
-                .bound("0.8 * (1 + R1/R3) ~ 6.0")
-                .bound("0.8 * (1 + (R1+R2)/R3) ~ 12.0")
-                .finish(),
+fn main() {
+    // Argument errors are reported before the matches exist, so look for the flag directly.
+    cli::configure(env::args().any(|arg| arg == "--json-errors"), false);
+    let app = App::new("resistor-calc")
+        .version(crate_version!())
+        .about("Finds standard resistor values that satisfy a set of bounds.")
+        .setting(AppSettings::SubcommandsNegateReqs);
+    let matches = cli::solve::args(app)
+        .subcommand(cli::nearest::subcommand())
+        .subcommand(cli::code::subcommand())
+        .subcommand(cli::analyze::subcommand())
+        .subcommand(cli::estimate::subcommand())
+        .subcommand(cli::watch::subcommand())
+        .subcommand(cli::bench::subcommand())
+        .subcommand(cli::explain::subcommand())
+        .subcommand(cli::series::subcommand())
+        .subcommand(cli::batch::subcommand())
+        .subcommand(cli::serve::subcommand())
+        .arg(
+            Arg::with_name("quiet")
+                .long("quiet")
+                .short("q")
+                .help("Only print results and errors"),
+        )
+        .arg(
+            Arg::with_name("verbose")
+                .long("verbose")
+                .short("v")
+                .multiple(true)
+                .help("Log the progress of the solver to stderr, repeat for more detail"),
+        )
+        .arg(
+            Arg::with_name("json-errors")
+                .long("json-errors")
+                .help("Report errors on stderr as JSON objects"),
         )
-        .expect("Error: No values satisfy requirements");
+        .get_matches_safe()
+        .unwrap_or_else(|e| arg_error(e));
+    cli::init_logging(matches.occurrences_of("verbose"));
+    cli::configure(
+        matches.is_present("json-errors"),
+        matches.is_present("quiet"),
+    );
 
-    res.print_best();
-}
\ No newline at end of file
+    match matches.subcommand() {
+        ("nearest", Some(matches)) => cli::nearest::run(matches),
+        ("code", Some(matches)) => cli::code::run(matches),
+        ("analyze", Some(matches)) => cli::analyze::run(matches),
+        ("estimate", Some(matches)) => cli::estimate::run(matches),
+        ("watch", Some(matches)) => cli::watch::run(matches),
+        ("bench", Some(matches)) => cli::bench::run(matches),
+        ("explain", Some(matches)) => cli::explain::run(matches),
+        ("series", Some(matches)) => cli::series::run(matches),
+        ("batch", Some(matches)) => cli::batch::run(matches),
+        ("serve", Some(matches)) => cli::serve::run(matches),
+        _ => cli::solve::run(&matches),
+    }
+}
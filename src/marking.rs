@@ -0,0 +1,297 @@
+use {_print_r, E96};
+
+/// A colour band of a through-hole resistor marking.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Band {
+    Black,
+    Brown,
+    Red,
+    Orange,
+    Yellow,
+    Green,
+    Blue,
+    Violet,
+    Grey,
+    White,
+    Gold,
+    Silver,
+}
+
+const DIGITS: [Band; 10] = [
+    Band::Black,
+    Band::Brown,
+    Band::Red,
+    Band::Orange,
+    Band::Yellow,
+    Band::Green,
+    Band::Blue,
+    Band::Violet,
+    Band::Grey,
+    Band::White,
+];
+
+impl Band {
+    /// Looks up a band by its colour name, ignoring case. "Gray" is accepted for grey.
+    pub fn from_name(name: &str) -> Option<Band> {
+        match name.to_lowercase().as_str() {
+            "gold" => Some(Band::Gold),
+            "silver" => Some(Band::Silver),
+            "gray" => Some(Band::Grey),
+            name => DIGITS.iter().cloned().find(|b| b.name() == name),
+        }
+    }
+
+    /// The colour name of the band, in lower case.
+    pub fn name(self) -> &'static str {
+        match self {
+            Band::Black => "black",
+            Band::Brown => "brown",
+            Band::Red => "red",
+            Band::Orange => "orange",
+            Band::Yellow => "yellow",
+            Band::Green => "green",
+            Band::Blue => "blue",
+            Band::Violet => "violet",
+            Band::Grey => "grey",
+            Band::White => "white",
+            Band::Gold => "gold",
+            Band::Silver => "silver",
+        }
+    }
+
+    fn digit(self) -> Option<u32> {
+        DIGITS.iter().position(|&b| b == self).map(|d| d as u32)
+    }
+
+    fn multiplier(self) -> i32 {
+        match self {
+            Band::Gold => -1,
+            Band::Silver => -2,
+            b => b.digit().unwrap() as i32,
+        }
+    }
+}
+
+/// The style of an SMD resistor code.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SmdCode {
+    /// Two significant digits and a multiplier, such as "472" for 4.7K.
+    ThreeDigit,
+    /// Three significant digits and a multiplier, such as "4701" for 4.7K.
+    FourDigit,
+    /// The EIA-96 code of an E96 value and a multiplier letter, such as "01C" for 10K.
+    Eia96,
+}
+
+/// Formats a value in ohms in the same notation used when printing results, such as "4K7".
+/// # Example
+/// ```
+///     # use resistor_calc::*;
+///     assert_eq!(format_value(4700.0), "4K7");
+/// ```
+pub fn format_value(r: f64) -> String {
+    _print_r(&r)
+}
+
+/// Parses a value in ohms written either as a plain number or with a multiplier letter in place
+/// of the decimal point, such as "4K7", "4.7k", "100R", "R47" or "2M2". A trailing "Ω" or "ohm"
+/// is ignored.
+/// # Example
+/// ```
+///     # use resistor_calc::*;
+///     assert_eq!(parse_value("4k7"), Some(4700.0));
+///     assert_eq!(parse_value("R47"), Some(0.47));
+///     assert_eq!(parse_value("1.5M"), Some(1.5e6));
+///     assert_eq!(parse_value("10 ohm"), Some(10.0));
+/// ```
+pub fn parse_value(s: &str) -> Option<f64> {
+    let s = s.trim().trim_end_matches("ohms").trim_end_matches("ohm");
+    let s = s.trim_end_matches('Ω').trim();
+    match s.find(|c: char| c.is_ascii_alphabetic()) {
+        Some(idx) => {
+            let scale = match &s[idx..=idx] {
+                "R" | "r" => 1.0,
+                "m" => 1e-3,
+                "k" | "K" => 1e3,
+                "M" => 1e6,
+                "G" | "g" => 1e9,
+                _ => return None,
+            };
+            let (int, frac) = (&s[..idx], &s[idx + 1..]);
+            if int.contains('.') && !frac.is_empty() {
+                return None;
+            }
+            let num = format!("{}.{}", if int.is_empty() { "0" } else { int }, frac);
+            let num = num.trim_end_matches('.');
+            num.parse::<f64>().ok().map(|v| v * scale)
+        }
+        None => s.parse().ok(),
+    }
+}
+
+/// Splits `r` into `digits` significant digits and a power of ten, if it can be represented
+/// exactly that way.
+fn significant(r: f64, digits: u32) -> Option<(u32, i32)> {
+    if r <= 0.0 {
+        return None;
+    }
+    let exp = r.log10().floor() as i32 - (digits as i32 - 1);
+    let mantissa = (r / 10f64.powi(exp)).round();
+    if ((mantissa * 10f64.powi(exp) - r) / r).abs() > 1e-9 {
+        return None;
+    }
+    Some((mantissa as u32, exp))
+}
+
+/// Returns the colour bands that mark `r` with the given number of significant digits, 2 for a
+/// four band marking or 3 for a five band marking, without the tolerance band. Returns `None` if
+/// the value cannot be marked with that many digits.
+/// # Example
+/// ```
+///     # use resistor_calc::*;
+///     assert_eq!(
+///         colour_bands(4700.0, 2),
+///         Some(vec![Band::Yellow, Band::Violet, Band::Red])
+///     );
+/// ```
+pub fn colour_bands(r: f64, digits: u32) -> Option<Vec<Band>> {
+    let (mantissa, exp) = significant(r, digits)?;
+    let multiplier = match exp {
+        -2 => Band::Silver,
+        -1 => Band::Gold,
+        0..=9 => DIGITS[exp as usize],
+        _ => return None,
+    };
+    let mut bands: Vec<Band> = mantissa
+        .to_string()
+        .chars()
+        .map(|c| DIGITS[c.to_digit(10).unwrap() as usize])
+        .collect();
+    bands.push(multiplier);
+    Some(bands)
+}
+
+/// Decodes the value in ohms of a colour band marking. Three bands are two digits and a
+/// multiplier, four bands add a tolerance band, five bands are three digits, a multiplier and a
+/// tolerance band, and six bands add a temperature coefficient band.
+/// # Example
+/// ```
+///     # use resistor_calc::*;
+///     let bands = [Band::Brown, Band::Black, Band::Red, Band::Gold];
+///     assert_eq!(from_colour_bands(&bands), Some(1000.0));
+/// ```
+pub fn from_colour_bands(bands: &[Band]) -> Option<f64> {
+    let digits = match bands.len() {
+        3 | 4 => 2,
+        5 | 6 => 3,
+        _ => return None,
+    };
+    let mantissa = bands[..digits]
+        .iter()
+        .try_fold(0, |acc, b| b.digit().map(|d| acc * 10 + d))?;
+    Some(f64::from(mantissa) * 10f64.powi(bands[digits].multiplier()))
+}
+
+/// The three significant digits of each E96 value, in ascending order, so that the EIA-96 code
+/// of a value is its position in this list.
+fn eia96_values() -> Vec<u32> {
+    let mut values: Vec<u32> = E96
+        .decade()
+        .iter()
+        .map(|v| (v * 100.0).round() as u32)
+        .collect();
+    values.sort();
+    values
+}
+
+const EIA96_LETTERS: [(char, i32); 9] = [
+    ('Z', -3),
+    ('Y', -2),
+    ('X', -1),
+    ('A', 0),
+    ('B', 1),
+    ('C', 2),
+    ('D', 3),
+    ('E', 4),
+    ('F', 5),
+];
+
+/// Places the decimal point of `digits` significant digits with an exponent of `exp` as an "R",
+/// as used for SMD codes of small values.
+fn r_notation(mantissa: u32, digits: u32, exp: i32) -> Option<String> {
+    let text = mantissa.to_string();
+    let point = digits as i32 + exp;
+    if point < 0 {
+        None
+    } else {
+        let (int, frac) = text.split_at(point as usize);
+        Some(format!("{}R{}", int, frac))
+    }
+}
+
+/// Returns the SMD code that marks `r` in the given style, or `None` if the value cannot be
+/// marked in that style.
+/// # Example
+/// ```
+///     # use resistor_calc::*;
+///     assert_eq!(smd_code(4700.0, SmdCode::ThreeDigit), Some("472".to_string()));
+///     assert_eq!(smd_code(4.7, SmdCode::ThreeDigit), Some("4R7".to_string()));
+///     assert_eq!(smd_code(4700.0, SmdCode::FourDigit), Some("4701".to_string()));
+///     assert_eq!(smd_code(10e3, SmdCode::Eia96), Some("01C".to_string()));
+/// ```
+pub fn smd_code(r: f64, style: SmdCode) -> Option<String> {
+    match style {
+        SmdCode::ThreeDigit | SmdCode::FourDigit => {
+            let digits = if style == SmdCode::ThreeDigit { 2 } else { 3 };
+            let (mantissa, exp) = significant(r, digits)?;
+            match exp {
+                0..=9 => Some(format!("{}{}", mantissa, exp)),
+                _ if exp < 0 => r_notation(mantissa, digits, exp),
+                _ => None,
+            }
+        }
+        SmdCode::Eia96 => {
+            let (mantissa, exp) = significant(r, 3)?;
+            let code = eia96_values().iter().position(|&v| v == mantissa)? + 1;
+            let letter = EIA96_LETTERS.iter().find(|&&(_, e)| e == exp)?.0;
+            Some(format!("{:02}{}", code, letter))
+        }
+    }
+}
+
+/// Decodes the value in ohms of an SMD code in any of the styles of `SmdCode`. The alternative
+/// EIA-96 letters R, S and H are accepted for Y, X and B.
+/// # Example
+/// ```
+///     # use resistor_calc::*;
+///     assert_eq!(from_smd_code("472"), Some(4700.0));
+///     assert_eq!(from_smd_code("4R7"), Some(4.7));
+///     assert_eq!(from_smd_code("01C"), Some(10e3));
+/// ```
+pub fn from_smd_code(code: &str) -> Option<f64> {
+    let code = code.trim().to_uppercase();
+    if code.contains('R') && code.chars().filter(|c| c.is_ascii_digit()).count() + 1 == code.len() {
+        return code.replace('R', ".").parse().ok();
+    }
+    match (code.len(), code.chars().last()) {
+        (3, Some(letter)) if letter.is_ascii_alphabetic() => {
+            let letter = match letter {
+                'R' => 'Y',
+                'S' => 'X',
+                'H' => 'B',
+                l => l,
+            };
+            let exp = EIA96_LETTERS.iter().find(|&&(l, _)| l == letter)?.1;
+            let idx: usize = code[..2].parse().ok()?;
+            let mantissa = *eia96_values().get(idx.checked_sub(1)?)?;
+            Some(f64::from(mantissa) * 10f64.powi(exp))
+        }
+        (3, _) | (4, _) => {
+            let (sig, exp) = code.split_at(code.len() - 1);
+            let sig: u32 = sig.parse().ok()?;
+            let exp: i32 = exp.parse().ok()?;
+            Some(f64::from(sig) * 10f64.powi(exp))
+        }
+        _ => None,
+    }
+}
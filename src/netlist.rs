@@ -0,0 +1,364 @@
+use std::collections::BTreeMap;
+
+use {parse_value, Error, ROpBuilder};
+
+/// A node of a netlist, once the sources have been applied.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Node {
+    Ground,
+    Driven(f64),
+    Free(usize),
+}
+
+/// A resistor of a netlist, either one of the positions being searched or of a fixed value.
+#[derive(Debug, Clone)]
+enum Resistor {
+    Position(usize),
+    Fixed(f64),
+}
+
+impl Resistor {
+    /// The expression for the conductance of the resistor.
+    fn conductance(&self) -> String {
+        match *self {
+            Resistor::Position(idx) => format!("1/R{}", idx),
+            Resistor::Fixed(r) => format!("1/{}", r),
+        }
+    }
+}
+
+/// A matrix of expressions, where `None` is zero.
+type Matrix = Vec<Vec<Option<String>>>;
+
+/// A resistive network read from a simple SPICE-like netlist, from which the expressions for the
+/// voltage and Thevenin resistance of each node are derived by nodal analysis. This removes the
+/// algebra from describing a network as bounds.
+///
+/// Each line of a netlist is one of:
+///
+/// * `R<n> <node> <node>`, the resistor being searched for as Rn.
+/// * `<name> <node> <node> <value>`, a resistor of a fixed value such as `RL OUT 0 10k`.
+/// * `V<name> <node> <node> <volts>`, a voltage source from its first node to its second, one of
+///   which must be ground.
+/// * `I<name> <node> <node> <amps>`, a current source, flowing through the source from its first
+///   node to its second.
+/// * `.target <node> <volts>`, `.min <node> <volts>` or `.max <node> <volts>`, bounding the
+///   voltage of a node.
+/// * `.rth <node> <ohms>`, bounding the Thevenin resistance seen at a node.
+///
+/// The ground node is `0` or `GND`. Lines starting with `*` are comments, as is anything after a
+/// `;`.
+/// # Example
+/// ```
+///     # use resistor_calc::*;
+///     let net = Netlist::parse(
+///         "* 12V to 3.3V divider feeding an ADC
+///          V1 IN 0 12
+///          R1 IN OUT
+///          R2 OUT 0
+///          .target OUT 3.3
+///          .rth OUT 10k",
+///     ).unwrap();
+///     assert_eq!(net.positions(), 2);
+///     let res = RCalc::e24(2).calc(net.builder().finish()).unwrap();
+///     let (_, best) = res.iter().next().unwrap();
+///     assert!((12.0 * best.r(2) / (best.r(1) + best.r(2)) - 3.3).abs() < 0.05);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Netlist {
+    names: BTreeMap<String, Node>,
+    resistors: Vec<(Node, Node, Resistor)>,
+    currents: Vec<(Node, Node, f64)>,
+    free: usize,
+    positions: usize,
+    bounds: Vec<String>,
+}
+
+fn is_ground(node: &str) -> bool {
+    node == "0" || node.eq_ignore_ascii_case("GND")
+}
+
+/// Parses a value as with `parse_value`, also allowing the small scales `u`, `n` and `p` used
+/// for currents such as `10u`.
+fn value(s: &str) -> Result<f64, String> {
+    let small = [('u', 1e-6), ('n', 1e-9), ('p', 1e-12)]
+        .iter()
+        .find(|&&(suffix, _)| s.ends_with(suffix))
+        .and_then(|&(_, scale)| s[..s.len() - 1].parse::<f64>().ok().map(|v| v * scale));
+    small
+        .or_else(|| parse_value(s))
+        .ok_or_else(|| format!("Invalid value '{}'", s))
+}
+
+impl Netlist {
+    /// Reads a netlist, returning a description of the problem with the line it is on if it is
+    /// invalid.
+    pub fn parse(src: &str) -> Result<Netlist, Error> {
+        Netlist::read(src).map_err(Error::Parse)
+    }
+
+    fn read(src: &str) -> Result<Netlist, String> {
+        let lines: Vec<(usize, Vec<&str>)> = src
+            .lines()
+            .enumerate()
+            .map(|(i, line)| (i + 1, line.split(';').next().unwrap().trim()))
+            .filter(|(_, line)| !line.is_empty() && !line.starts_with('*'))
+            .map(|(i, line)| (i, line.split_whitespace().collect()))
+            .collect();
+        let at = |line: usize| move |e: String| format!("Line {}: {}", line, e);
+
+        // The sources fix the voltages of nodes, which must be known before the free nodes are
+        // numbered.
+        let mut names = BTreeMap::new();
+        for (line, words) in lines.iter().filter(|(_, w)| w[0].starts_with(['V', 'v'])) {
+            let (a, b, volts) = match words[..] {
+                [_, a, b, volts] => (a, b, value(volts).map_err(at(*line))?),
+                _ => return Err(at(*line)("Expected 'V<name> <node> <node> <volts>'".into())),
+            };
+            let (node, volts) = match (is_ground(a), is_ground(b)) {
+                (false, true) => (a, volts),
+                (true, false) => (b, -volts),
+                _ => {
+                    return Err(at(*line)(
+                        "Voltage sources must have exactly one node on ground".into(),
+                    ))
+                }
+            };
+            if names
+                .insert(node.to_string(), Node::Driven(volts))
+                .is_some()
+            {
+                return Err(at(*line)(format!("Node '{}' is driven twice", node)));
+            }
+        }
+
+        let mut net = Netlist {
+            names,
+            resistors: Vec::new(),
+            currents: Vec::new(),
+            free: 0,
+            positions: 0,
+            bounds: Vec::new(),
+        };
+        let mut directives = Vec::new();
+        for (line, words) in &lines {
+            let first = words[0].chars().next().unwrap().to_ascii_uppercase();
+            match (first, &words[..]) {
+                ('V', _) => (),
+                ('.', _) => directives.push((*line, words)),
+                ('I', &[_, a, b, amps]) => {
+                    let amps = value(amps).map_err(at(*line))?;
+                    let (a, b) = (net.node(a), net.node(b));
+                    net.currents.push((a, b, amps));
+                }
+                ('I', _) => {
+                    return Err(at(*line)("Expected 'I<name> <node> <node> <amps>'".into()))
+                }
+                (_, &[name, a, b]) => {
+                    let idx = name[1..]
+                        .parse::<usize>()
+                        .ok()
+                        .filter(|&idx| first == 'R' && (1..=100).contains(&idx))
+                        .ok_or_else(|| {
+                            at(*line)(format!(
+                                "'{}' needs a value, or to be named R1 to R100",
+                                name
+                            ))
+                        })?;
+                    net.positions = net.positions.max(idx);
+                    let (a, b) = (net.node(a), net.node(b));
+                    net.resistors.push((a, b, Resistor::Position(idx)));
+                }
+                (_, &[_, a, b, ohms]) => {
+                    let ohms = value(ohms).map_err(at(*line))?;
+                    let (a, b) = (net.node(a), net.node(b));
+                    net.resistors.push((a, b, Resistor::Fixed(ohms)));
+                }
+                _ => return Err(at(*line)(format!("Unrecognised element '{}'", words[0]))),
+            }
+        }
+
+        for (line, words) in directives {
+            if let Some(bound) = net.directive(words).map_err(at(line))? {
+                net.bounds.push(bound);
+            }
+        }
+        Ok(net)
+    }
+
+    /// The bound given by a directive, if it is one that gives a bound.
+    fn directive(&self, words: &[&str]) -> Result<Option<String>, String> {
+        Ok(Some(match *words {
+            [".end"] => return Ok(None),
+            [".target", node, v] => format!("{} ~ {}", self.voltage(node)?, value(v)?),
+            [".min", node, v] => format!("{} >= {}", self.voltage(node)?, value(v)?),
+            [".max", node, v] => format!("{} <= {}", self.voltage(node)?, value(v)?),
+            [".rth", node, r] => format!("{} <= {}", self.thevenin(node)?, value(r)?),
+            _ => return Err(format!("Unrecognised directive '{}'", words.join(" "))),
+        }))
+    }
+
+    /// Looks up a node by name, numbering it if it is new and neither ground nor driven.
+    fn node(&mut self, name: &str) -> Node {
+        if is_ground(name) {
+            return Node::Ground;
+        }
+        let free = self.free;
+        let node = *self
+            .names
+            .entry(name.to_string())
+            .or_insert(Node::Free(free));
+        if node == Node::Free(free) {
+            self.free += 1;
+        }
+        node
+    }
+
+    /// Looks up a node that is neither ground nor driven by a source.
+    fn free_node(&self, name: &str) -> Result<usize, String> {
+        match self.names.get(name) {
+            Some(&Node::Free(idx)) => Ok(idx),
+            Some(_) => Err(format!("Node '{}' is driven by a source", name)),
+            None if is_ground(name) => Err(format!("Node '{}' is ground", name)),
+            None => Err(format!("Unknown node '{}'", name)),
+        }
+    }
+
+    /// The number of resistors being searched for, as the highest n of the resistors Rn.
+    pub fn positions(&self) -> usize {
+        self.positions
+    }
+
+    /// The bounds given by the directives of the netlist.
+    pub fn bounds(&self) -> &[String] {
+        &self.bounds
+    }
+
+    /// Produces a builder with the bounds given by the directives of the netlist, to which
+    /// further bounds can be added.
+    pub fn builder(&self) -> ROpBuilder {
+        self.bounds
+            .iter()
+            .fold(ROpBuilder::new(), |ops, bound| ops.bound(bound))
+    }
+
+    /// The nodal equations of the network, as the conductance matrix of the free nodes and the
+    /// currents into them.
+    fn equations(&self) -> (Matrix, Vec<Option<String>>) {
+        let mut g = vec![vec![Vec::new(); self.free]; self.free];
+        let mut i = vec![Vec::new(); self.free];
+        for (a, b, r) in &self.resistors {
+            let cond = r.conductance();
+            for &(this, other) in &[(a, b), (b, a)] {
+                if let Node::Free(n) = *this {
+                    g[n][n].push(cond.clone());
+                    match *other {
+                        Node::Free(m) => g[n][m].push(format!("-{}", cond)),
+                        Node::Driven(v) => i[n].push(format!("{}*{}", v, cond)),
+                        Node::Ground => (),
+                    }
+                }
+            }
+        }
+        for &(a, b, amps) in &self.currents {
+            if let Node::Free(n) = a {
+                i[n].push(format!("{}", -amps));
+            }
+            if let Node::Free(n) = b {
+                i[n].push(format!("{}", amps));
+            }
+        }
+        let sum = |terms: Vec<String>| match terms.len() {
+            0 => None,
+            _ => Some(format!("({})", terms.join(" + "))),
+        };
+        (
+            g.into_iter()
+                .map(|row| row.into_iter().map(sum).collect())
+                .collect(),
+            i.into_iter().map(sum).collect(),
+        )
+    }
+
+    /// The expression for the voltage of `node` in terms of R1, R2, ..., Rn.
+    /// # Example
+    /// ```
+    ///     # use resistor_calc::*;
+    ///     let net = Netlist::parse("V1 IN 0 10\nR1 IN OUT\nR2 OUT 0").unwrap();
+    ///     let op = ROpBuilder::new()
+    ///         .bound(&format!("{} ~ 2.5", net.voltage("OUT").unwrap()))
+    ///         .build();
+    ///     assert!(op.eval(&RSet::new(&[3e3, 1e3])).unwrap() < 1e-9);
+    /// ```
+    pub fn voltage(&self, node: &str) -> Result<String, Error> {
+        match self.names.get(node) {
+            Some(&Node::Driven(v)) => return Ok(format!("{}", v)),
+            None if is_ground(node) => return Ok("0".to_string()),
+            _ => (),
+        }
+        let idx = self.free_node(node).map_err(Error::Parse)?;
+        let (g, i) = self.equations();
+        let mut replaced = g.clone();
+        for (row, current) in replaced.iter_mut().zip(i) {
+            row[idx] = current;
+        }
+        Ok(match (det(&replaced), det(&g)) {
+            (None, _) => "0".to_string(),
+            (Some(num), Some(den)) => format!("{} / {}", num, den),
+            (Some(_), None) => {
+                return Err(Error::Parse(format!("Node '{}' is not connected", node)))
+            }
+        })
+    }
+
+    /// The expression for the Thevenin resistance seen at `node` in terms of R1, R2, ..., Rn,
+    /// with every source replaced by its internal resistance.
+    pub fn thevenin(&self, node: &str) -> Result<String, Error> {
+        let idx = self.free_node(node).map_err(Error::Parse)?;
+        let (g, _) = self.equations();
+        match (det(&minor(&g, idx, idx)), det(&g)) {
+            (Some(num), Some(den)) => Ok(format!("{} / {}", num, den)),
+            _ => Err(Error::Parse(format!("Node '{}' is not connected", node))),
+        }
+    }
+}
+
+/// The matrix without row `r` and column `c`.
+fn minor(m: &Matrix, r: usize, c: usize) -> Matrix {
+    m.iter()
+        .enumerate()
+        .filter(|&(i, _)| i != r)
+        .map(|(_, row)| {
+            row.iter()
+                .enumerate()
+                .filter(|&(j, _)| j != c)
+                .map(|(_, e)| e.clone())
+                .collect()
+        })
+        .collect()
+}
+
+/// The determinant of a matrix of expressions by cofactor expansion, or `None` if it is zero.
+/// Zero entries are skipped, which keeps the expressions of sparse networks small.
+fn det(m: &Matrix) -> Option<String> {
+    if m.is_empty() {
+        return Some("1".to_string());
+    }
+    let terms: Vec<String> = m[0]
+        .iter()
+        .enumerate()
+        .filter_map(|(j, e)| {
+            let e = e.as_ref()?;
+            let sign = if j % 2 == 0 { "" } else { "-" };
+            Some(match det(&minor(m, 0, j)) {
+                Some(ref cof) if cof == "1" => format!("{}{}", sign, e),
+                Some(cof) => format!("{}{}*{}", sign, e, cof),
+                None => return None,
+            })
+        })
+        .collect();
+    match terms.len() {
+        0 => None,
+        _ => Some(format!("({})", terms.join(" + "))),
+    }
+}
@@ -0,0 +1,93 @@
+use {DecadeOrder, RRes, RSet};
+
+/// Boltzmann constant in J/K.
+const BOLTZMANN: f64 = 1.380_649e-23;
+
+/// Returns the resistance of `a` and `b` in parallel.
+pub fn parallel(a: f64, b: f64) -> f64 {
+    a * b / (a + b)
+}
+
+/// Returns the RMS thermal (Johnson) noise voltage of a resistance `r` over `bandwidth` in Hz at
+/// a temperature of `temp` in °C.
+/// # Example
+/// ```
+///     # use resistor_calc::*;
+///     // Roughly 1.3µV for 10k over 10kHz at room temperature.
+///     let vn = johnson_noise(10e3, 10e3, 25.0);
+///     assert!((vn - 1.28e-6).abs() < 0.01e-6);
+/// ```
+pub fn johnson_noise(r: f64, bandwidth: f64, temp: f64) -> f64 {
+    (4.0 * BOLTZMANN * (temp + 273.15) * r * bandwidth).sqrt()
+}
+
+impl RSet {
+    /// Returns the Thevenin resistance seen at the junction of two legs of a network, where the
+    /// `top` leg is formed from the listed resistors in series, as is the `bottom` leg. For a
+    /// simple divider of R1 over R2 this is `rset.thevenin(&[1], &[2])`.
+    /// # Examples
+    /// ```
+    ///     # let ret = {
+    ///     #     use resistor_calc::{RCalc, ROpBuilder};
+    ///     #     let r = RCalc::e3(2);
+    ///     #     r.calc(ROpBuilder::new()
+    ///     #         .bound("R1 + R2 ~ 500")
+    ///     #         .finish()
+    ///     #     ).unwrap()
+    ///     # };
+    ///     for (err, rset) in ret.iter() {
+    ///         println!("Source impedance: {}", rset.thevenin(&[1], &[2]));
+    ///     }
+    /// ```
+    pub fn thevenin(&self, top: &[usize], bottom: &[usize]) -> f64 {
+        let leg = |idxs: &[usize]| idxs.iter().map(|&i| self.r(i)).sum();
+        parallel(leg(top), leg(bottom))
+    }
+
+    /// Returns the RMS thermal noise voltage at the tap of a divider formed from R{top} and
+    /// R{bottom}, over `bandwidth` in Hz at `temp` in °C. This is the noise of the Thevenin
+    /// resistance of the divider, the two resistors in parallel.
+    /// # Examples
+    /// ```
+    ///     # let ret = {
+    ///     #     use resistor_calc::{RCalc, ROpBuilder};
+    ///     #     let r = RCalc::e3(2);
+    ///     #     r.calc(ROpBuilder::new()
+    ///     #         .bound("R1 + R2 ~ 500")
+    ///     #         .finish()
+    ///     #     ).unwrap()
+    ///     # };
+    ///     for (err, rset) in ret.iter() {
+    ///         println!("Noise: {}V", rset.divider_noise(1, 2, 10e3, 25.0));
+    ///     }
+    /// ```
+    pub fn divider_noise(&self, top: usize, bottom: usize, bandwidth: f64, temp: f64) -> f64 {
+        johnson_noise(self.thevenin(&[top], &[bottom]), bandwidth, temp)
+    }
+}
+
+impl RRes {
+    /// Returns the standby current drawn from `supply` volts by the `i`th result, assuming all of
+    /// the resistors form a single chain across the supply.
+    pub fn standby_current(&self, i: usize, supply: f64) -> f64 {
+        supply / self.res[i].1.sum()
+    }
+
+    /// Re-orders results that share the same error so that those drawing the least standby
+    /// current, with the largest total resistance, come first. Useful for battery powered
+    /// designs where any of several equally accurate networks would do. This is
+    /// `order_decades` with `DecadeOrder::Higher`.
+    /// # Example
+    /// ```
+    ///     # use resistor_calc::*;
+    ///     let res = RCalc::e24(2)
+    ///         .calc(ROpBuilder::new().bound("R2 / (R1 + R2) ~ 0.25").finish())
+    ///         .unwrap()
+    ///         .prefer_low_current();
+    ///     println!("Drain: {}A", res.standby_current(0, 3.7));
+    /// ```
+    pub fn prefer_low_current(mut self) -> Self {
+        self.order_decades(DecadeOrder::Higher);
+        self
+    }
+}
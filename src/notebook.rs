@@ -0,0 +1,120 @@
+use std::fmt;
+
+use {format_value, ROp, RRes};
+
+/// Sorts the rows of a table by the column of the clicked header, toggling between ascending and
+/// descending order. Cells are sorted by their `data-v` attribute.
+const SORT: &str = "var t=this.closest('table'),b=t.tBodies[0],i=this.cellIndex,\
+d=this.dataset.dir=this.dataset.dir==='a'?'d':'a';\
+Array.from(b.rows).sort(function(x,y){var p=+x.cells[i].dataset.v,q=+y.cells[i].dataset.v;\
+return d==='a'?p-q:q-p}).forEach(function(r){b.appendChild(r)})";
+
+fn escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// An HTML table of results for display in a notebook, created by `RRes::html`. Clicking a
+/// column header sorts the table by that column.
+///
+/// In an [evcxr](https://github.com/evcxr/evcxr) Jupyter notebook, results are shown as a table
+/// by ending a cell with the `RRes` or the `HtmlTable`.
+#[derive(Clone, Copy)]
+pub struct HtmlTable<'a> {
+    res: &'a RRes,
+    op: Option<&'a ROp>,
+    top: usize,
+}
+
+impl<'a> HtmlTable<'a> {
+    /// Adds a column for each of the bounds of `op`, with the error of `~` bounds and how far
+    /// other bounds are past their target.
+    pub fn bounds(mut self, op: &'a ROp) -> Self {
+        self.op = Some(op);
+        self
+    }
+
+    /// Shows only the first `top` results, rather than the default of 20.
+    pub fn top(mut self, top: usize) -> Self {
+        self.top = top;
+        self
+    }
+
+    /// Displays the table in an evcxr notebook.
+    pub fn evcxr_display(&self) {
+        println!("EVCXR_BEGIN_CONTENT text/html\n{}\nEVCXR_END_CONTENT", self);
+    }
+}
+
+impl<'a> fmt::Display for HtmlTable<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let header = |f: &mut fmt::Formatter, name: &str| {
+            write!(
+                f,
+                "<th onclick=\"{}\" style=\"cursor:pointer\">{}</th>",
+                SORT,
+                escape(name)
+            )
+        };
+        write!(f, "<table>\n<thead><tr>")?;
+        header(f, "#")?;
+        header(f, "Error")?;
+        for (i, pos) in self.res.pos.iter().enumerate() {
+            header(f, &format!("R{} ({})", i + 1, pos.series))?;
+        }
+        for bound in self.op.map_or(&[][..], |op| op.bounds()) {
+            header(f, bound)?;
+        }
+        writeln!(f, "</tr></thead>\n<tbody>")?;
+        for (rank, (err, rs)) in self.res.iter().take(self.top).enumerate() {
+            let err = *err as f64 / 1e9;
+            write!(
+                f,
+                "<tr><td data-v=\"{0}\">{0}</td><td data-v=\"{1}\">{1:.6}</td>",
+                rank + 1,
+                err
+            )?;
+            for &r in rs.values() {
+                write!(f, "<td data-v=\"{}\">{}</td>", r, format_value(r))?;
+            }
+            if let Some(op) = self.op {
+                for outcome in op.explain(rs) {
+                    write!(
+                        f,
+                        "<td data-v=\"{0}\" title=\"{1}\">{0:.6}</td>",
+                        outcome.deviation, outcome.value
+                    )?;
+                }
+            }
+            writeln!(f, "</tr>")?;
+        }
+        write!(f, "</tbody>\n</table>")
+    }
+}
+
+impl RRes {
+    /// Renders the results as a sortable HTML table, for notebooks and reports.
+    /// # Example
+    /// ```
+    ///     # use resistor_calc::*;
+    ///     let op = ROpBuilder::new().bound("R2 / (R1 + R2) ~ 0.25").build();
+    ///     let res = RCalc::e24(2).calc(|rs| op.eval(rs)).unwrap();
+    ///     let html = res.html().bounds(&op).top(5).to_string();
+    ///     assert!(html.starts_with("<table>"));
+    ///     assert_eq!(html.matches("<tr>").count(), 6);
+    /// ```
+    pub fn html(&self) -> HtmlTable<'_> {
+        HtmlTable {
+            res: self,
+            op: None,
+            top: 20,
+        }
+    }
+
+    /// Displays the first 20 results as a table in an evcxr notebook.
+    pub fn evcxr_display(&self) {
+        self.html().evcxr_display();
+    }
+}
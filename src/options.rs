@@ -0,0 +1,289 @@
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+use {
+    DecadeOrder, Error, Executor, Guardrail, Inline, Limits, Progress, RCalc, RRes, RSet, Threads,
+};
+
+/// Which results `RCalc::calc_with` treats as duplicates, keeping only the best of each.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub enum Dedup {
+    /// Keeps every result.
+    Off,
+    /// Keeps one result of each set of values that differ only by a power of ten, such as 1K and
+    /// 3K and 10K and 30K.
+    Decades,
+}
+
+/// A callback reporting the progress of a search.
+type ProgressFn<'o> = Box<dyn FnMut(&Progress) + 'o>;
+
+/// A callback deciding whether to run a search larger than a guard allows.
+type GuardFn<'o> = Box<dyn FnMut(&Guardrail) -> bool + 'o>;
+
+/// The options of a search with `RCalc::calc_with`, gathering the threads, limits, filtering,
+/// ordering, progress reporting and cancellation of the other `calc_*` methods in one place,
+/// along with a guard against searches too large to finish.
+/// # Example
+/// ```
+///     # use resistor_calc::*;
+///     use std::sync::atomic::AtomicBool;
+///     let cancel = AtomicBool::new(false);
+///     let options = CalcOptions::new()
+///         .jobs(4)
+///         .top(20)
+///         .max_error(0.01)
+///         .dedup(Dedup::Decades)
+///         .tie_break(DecadeOrder::Lower)
+///         .progress(|p| eprint!("\r{}/{} evaluated", p.evaluated, p.total))
+///         .cancel(&cancel);
+/// ```
+pub struct CalcOptions<'o> {
+    limits: Limits,
+    max_error: Option<f64>,
+    max_results: Option<usize>,
+    dedup: Dedup,
+    tie_break: Option<DecadeOrder>,
+    progress: Option<ProgressFn<'o>>,
+    cancel: Option<&'o AtomicBool>,
+    guard: Option<(u128, GuardFn<'o>)>,
+    executor: Option<&'o dyn Executor>,
+}
+
+impl<'o> Default for CalcOptions<'o> {
+    fn default() -> Self {
+        CalcOptions {
+            limits: Limits::default(),
+            max_error: None,
+            max_results: None,
+            dedup: Dedup::Off,
+            tie_break: None,
+            progress: None,
+            cancel: None,
+            guard: None,
+            executor: None,
+        }
+    }
+}
+
+impl<'o> CalcOptions<'o> {
+    /// Options for a search on one thread keeping every result, as with `RCalc::calc`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Spreads the search across `jobs` threads, or `jobs` tasks of the `executor`.
+    pub fn jobs(mut self, jobs: usize) -> Self {
+        self.limits.jobs = jobs;
+        self
+    }
+
+    /// Keeps only the `top` results with the lowest error, bounding the memory of the search.
+    pub fn top(mut self, top: usize) -> Self {
+        self.limits.max_results = Some(top);
+        self
+    }
+
+    /// Rejects combinations with an error above `max_error`.
+    pub fn max_error(mut self, max_error: f64) -> Self {
+        self.max_error = Some(max_error);
+        self
+    }
+
+    /// Stops the search once `max` results have been found, returning them as partial results.
+    /// Unlike `top` these are the first `max` combinations accepted rather than the best,
+    /// though they are still ordered by their error. When the search is spread across threads,
+    /// which combinations are accepted first varies from run to run.
+    /// # Example
+    /// ```
+    ///     # use resistor_calc::*;
+    ///     let op = ROpBuilder::new().bound("R2 / (R1 + R2) ~ 0.25").build();
+    ///     let res = RCalc::e24(2)
+    ///         .calc_with(|rs| op.eval(rs), CalcOptions::new().max_results(5))
+    ///         .unwrap();
+    ///     assert_eq!(res.len(), 5);
+    ///     // The first accepted have R1 at 1R, the first value searched.
+    ///     assert!(res.iter().all(|(_, rs)| rs.values()[0] == 1.0));
+    /// ```
+    pub fn max_results(mut self, max: usize) -> Self {
+        self.max_results = Some(max);
+        self
+    }
+
+    /// Applies the resource limits of `limits`, replacing any set by `jobs` or `top`.
+    pub fn limits(mut self, limits: Limits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// Drops results that duplicate a better one, as chosen by `dedup`.
+    pub fn dedup(mut self, dedup: Dedup) -> Self {
+        self.dedup = dedup;
+        self
+    }
+
+    /// Orders results of equal error by decade, as with `RRes::order_decades`. This is applied
+    /// before any `dedup`, so it also chooses which of each set of duplicates is kept.
+    pub fn tie_break(mut self, order: DecadeOrder) -> Self {
+        self.tie_break = Some(order);
+        self
+    }
+
+    /// Calls `progress` periodically during the search and once at the end.
+    pub fn progress(mut self, progress: impl FnMut(&Progress) + 'o) -> Self {
+        self.progress = Some(Box::new(progress));
+        self
+    }
+
+    /// Stops the search early, with partial results, once `cancel` is set, such as from another
+    /// thread or a signal handler.
+    pub fn cancel(mut self, cancel: &'o AtomicBool) -> Self {
+        self.cancel = Some(cancel);
+        self
+    }
+
+    /// Runs the parts of the search with `executor`, such as an existing thread pool, rather than
+    /// on threads of its own. The search is split into as many tasks as there are `jobs`.
+    pub fn executor(mut self, executor: &'o dyn Executor) -> Self {
+        self.executor = Some(executor);
+        self
+    }
+
+    /// Checks the size of the search with `RCalc::guard` before starting it. If there are more
+    /// than `threshold` combinations `action` is called with the suggestions of the guard, and the
+    /// search runs only if it returns true, so that it can either refuse the search or warn and
+    /// carry on. A refused search returns `Error::TooLarge`.
+    /// # Example
+    /// ```
+    ///     # use resistor_calc::*;
+    ///     let op = ROpBuilder::new().bound("R2 / (R1 + R2 + R3) ~ 0.25").build();
+    ///     let mut refused = None;
+    ///     let res = RCalc::e96(3).calc_with(
+    ///         |rs| op.eval(rs),
+    ///         CalcOptions::new().guard(1_000_000, |guard| {
+    ///             refused = Some(guard.clone());
+    ///             false
+    ///         }),
+    ///     );
+    ///     assert!(matches!(res, Err(Error::TooLarge(_))));
+    ///     assert!(!refused.unwrap().remedies.is_empty());
+    /// ```
+    pub fn guard(mut self, threshold: u128, action: impl FnMut(&Guardrail) -> bool + 'o) -> Self {
+        self.guard = Some((threshold, Box::new(action)));
+        self
+    }
+
+    /// Takes the progress callback, leaving none, so that it can be wrapped by another.
+    pub(crate) fn take_progress(&mut self) -> Option<ProgressFn<'o>> {
+        self.progress.take()
+    }
+}
+
+impl<'a> RCalc<'a> {
+    /// Calculates the results for the bounds `f` with the given `options`. This is the general
+    /// form of the other `calc_*` methods, which are shorthands for it, so that new options can
+    /// be added without more of them. A search of one job runs on the calling thread.
+    ///
+    /// Returns `Error::TooLarge` if a guard refused the search, and `Error::Solver` if no
+    /// combination satisfied the bounds.
+    /// # Example
+    /// ```
+    ///     # use resistor_calc::*;
+    ///     let op = ROpBuilder::new().bound("R2 / (R1 + R2) ~ 0.25").build();
+    ///     let res = RCalc::e24(2)
+    ///         .calc_with(
+    ///             |rs| op.eval(rs),
+    ///             CalcOptions::new()
+    ///                 .jobs(2)
+    ///                 .max_error(0.01)
+    ///                 .dedup(Dedup::Decades)
+    ///                 .tie_break(DecadeOrder::Lower),
+    ///         )
+    ///         .unwrap();
+    ///     // Each exact divider, such as 3R and 1R, is kept once rather than in every decade.
+    ///     assert_eq!(res.get(0).unwrap().1.values(), &[3.0, 1.0]);
+    ///     assert_eq!(res.iter().filter(|(err, _)| *err == 0).count(), 4);
+    /// ```
+    pub fn calc_with(
+        &self,
+        f: impl Fn(&RSet) -> Option<f64> + Sync,
+        options: CalcOptions,
+    ) -> Result<RRes, Error> {
+        self.calc_with_timed(f, options).0
+    }
+
+    /// As with `calc_with`, also returning the time spent sorting the results, in seconds.
+    pub(crate) fn calc_with_timed(
+        &self,
+        f: impl Fn(&RSet) -> Option<f64> + Sync,
+        options: CalcOptions,
+    ) -> (Result<RRes, Error>, f64) {
+        let CalcOptions {
+            limits,
+            max_error,
+            max_results,
+            dedup,
+            tie_break,
+            progress,
+            cancel,
+            guard,
+            executor,
+        } = options;
+        if let Some((threshold, mut action)) = guard {
+            if let Err(guard) = self.guard(&f, threshold) {
+                if !action(&guard) {
+                    return (Err(Error::TooLarge(guard.to_string())), 0.0);
+                }
+                warn!("{}", guard);
+            }
+        }
+        let found = AtomicUsize::new(0);
+        let full = AtomicBool::new(false);
+        let bounds = |rs: &RSet| {
+            let err = f(rs).filter(|&err| max_error.map_or(true, |max| err <= max));
+            match (err, max_results) {
+                (Some(_), Some(max)) => {
+                    // The search only checks for cancellation now and then, so combinations
+                    // accepted after the first `max` are rejected until it stops.
+                    let count = found.fetch_add(1, Ordering::Relaxed) + 1;
+                    if count >= max {
+                        full.store(true, Ordering::Relaxed);
+                    }
+                    err.filter(|_| count <= max)
+                }
+                _ => err,
+            }
+        };
+        let cancelled =
+            || full.load(Ordering::Relaxed) || cancel.is_some_and(|c| c.load(Ordering::Relaxed));
+        let mut progress = progress.unwrap_or_else(|| Box::new(|_| ()));
+        // On the calling thread each value of R1 is a task of its own, so that progress is
+        // reported as the search goes rather than only at the end.
+        let (executor, parts): (&dyn Executor, usize) = match executor {
+            Some(executor) => (executor, limits.jobs),
+            None if limits.jobs > 1 => (&Threads, limits.jobs),
+            None => (&Inline, self.rs.first().map_or(1, |r| r.len())),
+        };
+        let (res, sort_secs) =
+            self.search_timed(bounds, &limits, executor, parts, &cancelled, |p| {
+                progress(p)
+            });
+        let mut res = match res {
+            Some(res) => res,
+            None => {
+                let err = Error::Solver("No values satisfy the bounds".to_string());
+                return (Err(err), sort_secs);
+            }
+        };
+
+        if let Some(order) = tie_break {
+            res.order_decades(order);
+        }
+        if dedup == Dedup::Decades {
+            let mut seen = HashSet::new();
+            res.res.retain(|(_, rs)| seen.insert(rs.decade_key()));
+        }
+        (Ok(res), sort_secs)
+    }
+}
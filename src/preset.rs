@@ -0,0 +1,110 @@
+use std::env;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use {Error, Problem};
+
+impl Problem {
+    /// The directory named presets are read from. This is `$RESISTOR_CALC_PRESETS` if set,
+    /// otherwise `resistor-calc/presets` within the user's config directory, which is
+    /// `$XDG_CONFIG_HOME`, `~/.config` or `%APPDATA%`.
+    pub fn preset_dir() -> Option<PathBuf> {
+        if let Some(dir) = env::var_os("RESISTOR_CALC_PRESETS") {
+            return Some(PathBuf::from(dir));
+        }
+        let config = env::var_os("XDG_CONFIG_HOME")
+            .map(PathBuf::from)
+            .or_else(|| env::var_os("HOME").map(|home| Path::new(&home).join(".config")))
+            .or_else(|| env::var_os("APPDATA").map(PathBuf::from))?;
+        Some(config.join("resistor-calc").join("presets"))
+    }
+
+    /// Loads the preset `name` from `Problem::preset_dir`, see `Problem::preset_in`.
+    pub fn preset(name: &str) -> Result<Self, Error> {
+        let dir = Problem::preset_dir()
+            .ok_or_else(|| Error::Io("Could not find the user config directory".to_string()))?;
+        Problem::preset_in(&dir, name)
+    }
+
+    /// Loads the preset `name` from `dir`, where it is stored as a TOML problem in `name.toml`.
+    /// Presets may leave out any of the fields of a problem, to be filled in with
+    /// `Problem::merge`. A preset that does not exist is an `Error::Parse`, other failures to
+    /// read it are an `Error::Io`.
+    /// # Example
+    /// ```
+    ///     # use resistor_calc::*;
+    ///     # use std::fs;
+    ///     let dir = std::env::temp_dir().join("resistor-calc-doc-presets");
+    ///     fs::create_dir_all(&dir).unwrap();
+    ///     fs::write(dir.join("lm317.toml"), r#"
+    ///         series = ["E24", "E24"]
+    ///         bounds = ["VREF * (1 + R2 / R1) ~ 5.0", "R1 <= 240"]
+    ///         [constants]
+    ///         VREF = 1.25
+    ///     "#).unwrap();
+    ///     let lm317 = Problem::preset_in(&dir, "lm317").unwrap();
+    ///     assert_eq!(lm317.constants["VREF"], 1.25);
+    ///     assert!(matches!(Problem::preset_in(&dir, "lm318"), Err(Error::Parse(_))));
+    /// ```
+    pub fn preset_in(dir: &Path, name: &str) -> Result<Self, Error> {
+        let path = dir.join(format!("{}.toml", name));
+        let text = fs::read_to_string(&path).map_err(|e| match e.kind() {
+            io::ErrorKind::NotFound => Error::Parse(format!("Unknown preset '{}'", name)),
+            _ => Error::Io(format!("Preset '{}' ({}): {}", name, path.display(), e)),
+        })?;
+        Problem::from_toml(&text).map_err(|e| Error::Parse(format!("Preset '{}': {}", name, e)))
+    }
+
+    /// The names of the presets available in `Problem::preset_dir`, in alphabetical order.
+    pub fn presets() -> Vec<String> {
+        let entries = Problem::preset_dir().and_then(|dir| fs::read_dir(dir).ok());
+        let mut names: Vec<String> = entries
+            .into_iter()
+            .flatten()
+            .filter_map(|entry| {
+                let path = entry.ok()?.path();
+                match path.extension() {
+                    Some(ext) if ext == "toml" => Some(path.file_stem()?.to_str()?.to_string()),
+                    _ => None,
+                }
+            })
+            .collect();
+        names.sort();
+        names
+    }
+
+    /// Overrides this problem with `other`, as when customising a preset for a single use. The
+    /// series and top of `other` replace those of this problem if given, its constants replace
+    /// any of the same name, and its bounds are added to the existing bounds.
+    /// # Example
+    /// ```
+    ///     # use resistor_calc::*;
+    ///     let divider = Problem::from_toml(r#"
+    ///         series = ["E24", "E24"]
+    ///         bounds = ["VIN * R2 / (R1 + R2) ~ 3.3", "R1 + R2 >= 10e3"]
+    ///         [constants]
+    ///         VIN = 12.0
+    ///     "#).unwrap();
+    ///     let overrides = Problem::from_toml(r#"
+    ///         bounds = ["R1 + R2 <= 100e3"]
+    ///         top = 1
+    ///         [constants]
+    ///         VIN = 24.0
+    ///     "#).unwrap();
+    ///     let problem = divider.merge(overrides);
+    ///     assert_eq!(problem.bounds.len(), 3);
+    ///     assert_eq!(problem.constants["VIN"], 24.0);
+    ///     let report = problem.solve().unwrap();
+    /// ```
+    pub fn merge(mut self, other: Problem) -> Self {
+        if !other.series.is_empty() {
+            self.series = other.series;
+        }
+        self.bounds.extend(other.bounds);
+        self.constants.extend(other.constants);
+        self.top = other.top.or(self.top);
+        self.max_combinations = other.max_combinations.or(self.max_combinations);
+        self
+    }
+}
@@ -0,0 +1,236 @@
+use inventory::fields;
+use {parse_value, ppb, Error, RRes, RSet};
+
+/// The price of a part of one value and package, as listed in a `PriceTable`.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub struct Price {
+    /// The resistance of the part in ohms.
+    pub value: f64,
+    /// The package of the part, such as `0603`.
+    pub package: String,
+    /// The cost of a single part.
+    pub unit_cost: f64,
+    /// The minimum order quantity.
+    pub moq: u64,
+    /// The number of parts in stock, if known.
+    pub stock: Option<u64>,
+    /// The fractional tolerance of the part, if known.
+    pub tolerance: Option<f64>,
+}
+
+impl Price {
+    /// The cost of ordering `qty` parts, rounded up to the minimum order quantity.
+    pub fn order_cost(&self, qty: u64) -> f64 {
+        qty.max(self.moq) as f64 * self.unit_cost
+    }
+}
+
+/// A table of part prices, used to annotate results with their cost or to search for the
+/// cheapest combination that is accurate enough.
+/// # Example
+/// ```
+///     # use resistor_calc::*;
+///     let prices = PriceTable::from_csv(
+///         "value,package,unit_cost,moq\n\
+///          10k,0603,0.002,100\n\
+///          4k7,0603,0.003,100\n\
+///          4k7,0805,0.004,10\n",
+///     ).unwrap();
+///     let rs = RSet::new(&[10e3, 4.7e3]);
+///     // 100 of the 10k, and 10 of the 4k7 in 0805 as that is cheaper than 100 in 0603.
+///     assert!((prices.cost(&rs, 1).unwrap() - 0.24).abs() < 1e-9);
+///     assert!((prices.package("0603").cost(&rs, 1).unwrap() - 0.5).abs() < 1e-9);
+/// ```
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub struct PriceTable {
+    prices: Vec<Price>,
+}
+
+impl PriceTable {
+    /// Creates a table from a list of prices.
+    pub fn new(prices: Vec<Price>) -> Self {
+        PriceTable { prices }
+    }
+
+    /// Reads a table from CSV with a header naming the `value`, `package`, `unit_cost` and
+    /// optionally `moq`, `stock` and `tolerance` columns, in any order. Values may be written as
+    /// accepted by `parse_value`, such as `4k7`, tolerances as a fraction or a percentage such as
+    /// `1%`, and the minimum order quantity defaults to 1.
+    pub fn from_csv(csv: &str) -> Result<Self, Error> {
+        let mut lines = csv.lines().filter(|line| !line.trim().is_empty());
+        let header = fields(
+            lines
+                .next()
+                .ok_or_else(|| Error::Parse("The price table is empty".to_string()))?,
+        );
+        let column = |names: &[&str]| {
+            header.iter().position(|h| {
+                names
+                    .iter()
+                    .any(|name| h.replace(' ', "_").eq_ignore_ascii_case(name))
+            })
+        };
+        let missing = |name| Error::Parse(format!("The price table has no {} column", name));
+        let value = column(&["value"]).ok_or_else(|| missing("value"))?;
+        let package = column(&["package"]).ok_or_else(|| missing("package"))?;
+        let cost = column(&["unit_cost", "cost", "price"]).ok_or_else(|| missing("unit_cost"))?;
+        let moq = column(&["moq"]);
+        let stock = column(&["stock"]);
+        let tolerance = column(&["tolerance"]);
+
+        let mut prices = Vec::new();
+        for (line, row) in lines.map(fields).enumerate() {
+            let field = |i: usize| row.get(i).map(String::as_str).unwrap_or("");
+            let invalid = |name, i| {
+                Error::Parse(format!("Row {}: Invalid {} '{}'", line + 1, name, field(i)))
+            };
+            prices.push(Price {
+                value: parse_value(field(value)).ok_or_else(|| invalid("value", value))?,
+                package: field(package).to_string(),
+                unit_cost: field(cost).parse().map_err(|_| invalid("cost", cost))?,
+                moq: match moq {
+                    Some(m) if !field(m).is_empty() => {
+                        field(m).parse().map_err(|_| invalid("moq", m))?
+                    }
+                    _ => 1,
+                },
+                stock: match stock {
+                    Some(st) if !field(st).is_empty() => {
+                        Some(field(st).parse().map_err(|_| invalid("stock", st))?)
+                    }
+                    _ => None,
+                },
+                tolerance: match tolerance {
+                    Some(t) if !field(t).is_empty() => {
+                        let tol = field(t);
+                        let frac = match tol.strip_suffix('%') {
+                            Some(pct) => pct.trim().parse::<f64>().map(|p| p / 100.0),
+                            None => tol.parse(),
+                        };
+                        Some(frac.map_err(|_| invalid("tolerance", t))?)
+                    }
+                    _ => None,
+                },
+            });
+        }
+        Ok(PriceTable { prices })
+    }
+
+    /// The prices in the table.
+    pub fn prices(&self) -> &[Price] {
+        &self.prices
+    }
+
+    /// Returns the table of only the prices for parts in `package`, ignoring case.
+    pub fn package(&self, package: &str) -> Self {
+        PriceTable {
+            prices: self
+                .prices
+                .iter()
+                .filter(|p| p.package.eq_ignore_ascii_case(package))
+                .cloned()
+                .collect(),
+        }
+    }
+
+    /// Returns the cheapest price for ordering `qty` parts of `value`, if the table has any.
+    pub fn lookup(&self, value: f64, qty: u64) -> Option<&Price> {
+        self.prices
+            .iter()
+            .filter(|p| ((p.value - value) / value).abs() < 1e-6)
+            .min_by(|a, b| a.order_cost(qty).total_cmp(&b.order_cost(qty)))
+    }
+
+    /// The cost of the parts for `quantity` builds using the values of `rs`, where parts of the
+    /// same value are ordered together. Returns `None` if any value is not in the table.
+    pub fn cost(&self, rs: &RSet, quantity: u64) -> Option<f64> {
+        let mut counts: Vec<(f64, u64)> = Vec::new();
+        for &r in rs.values() {
+            match counts.iter_mut().find(|(v, _)| *v == r) {
+                Some((_, n)) => *n += 1,
+                None => counts.push((r, 1)),
+            }
+        }
+        counts
+            .into_iter()
+            .map(|(v, n)| {
+                self.lookup(v, n * quantity)
+                    .map(|p| p.order_cost(n * quantity))
+            })
+            .sum()
+    }
+
+    /// Turns the bounds `f` into an objective that minimises the cost of `quantity` builds,
+    /// accepting only combinations that satisfy `f` with an error of at most `max_error` and
+    /// whose values are all priced. The error of each result is then its cost.
+    /// # Example
+    /// ```
+    ///     # use resistor_calc::*;
+    ///     let prices = PriceTable::from_csv(
+    ///         "value,package,unit_cost\n1k,0603,0.01\n3k,0603,0.05\n3k3,0603,0.01\n",
+    ///     ).unwrap();
+    ///     let op = ROpBuilder::new().bound("R2 / (R1 + R2) ~ 0.75").build();
+    ///     let res = RCalc::e24(2)
+    ///         .calc(prices.minimise_cost(|rs| op.eval(rs), 0.02, 1))
+    ///         .unwrap();
+    ///     let (_, best) = res.iter().next().unwrap();
+    ///     assert_eq!(format_value(best.r(2)), "3K3");
+    /// ```
+    pub fn minimise_cost<'t>(
+        &'t self,
+        f: impl Fn(&RSet) -> Option<f64> + 't,
+        max_error: f64,
+        quantity: u64,
+    ) -> impl Fn(&RSet) -> Option<f64> + 't {
+        move |rs: &RSet| {
+            f(rs)
+                .filter(|&err| err <= max_error)
+                .and_then(|_| self.cost(rs, quantity))
+        }
+    }
+
+    /// Ranks results by the cost of `quantity` builds rather than their accuracy, dropping any
+    /// whose values are not all priced, or returns `None` if none are. Every result already
+    /// satisfies the bounds, so accuracy requirements are best written as comparison bounds, such
+    /// as `R2 / (R1 + R2) >= 0.245`, which then act as hard constraints. Results of equal cost
+    /// keep their order of accuracy, and the error of each is then its cost.
+    /// # Example
+    /// ```
+    ///     # use resistor_calc::*;
+    ///     let prices = PriceTable::from_csv(
+    ///         "value,package,unit_cost\n1k,0603,0.05\n3k,0603,0.05\n\
+    ///          2k,0603,0.01\n6k2,0603,0.01\n",
+    ///     ).unwrap();
+    ///     let res = RCalc::e24(2)
+    ///         .calc(
+    ///             ROpBuilder::new()
+    ///                 .bound("abs(R2 / (R1 + R2) - 0.75) <= 0.01")
+    ///                 .bound("R2 / (R1 + R2) ~ 0.75")
+    ///                 .finish(),
+    ///         )
+    ///         .unwrap();
+    ///     // 1K and 3K are exact, but 2K and 6K2 are cheaper and close enough.
+    ///     let cheapest = prices.cheapest(&res, 1).unwrap();
+    ///     let (cost, best) = cheapest.iter().next().unwrap();
+    ///     assert_eq!(best.values(), &[2e3, 6.2e3]);
+    ///     assert_eq!(*cost, 20_000_000);
+    /// ```
+    pub fn cheapest(&self, res: &RRes, quantity: u64) -> Option<RRes> {
+        let mut ranked: Vec<(u64, RSet)> = res
+            .iter()
+            .filter_map(|(_, rs)| self.cost(rs, quantity).map(|cost| (ppb(cost), rs.clone())))
+            .collect();
+        if ranked.is_empty() {
+            return None;
+        }
+        // A stable sort, so results of equal cost stay in order of accuracy.
+        ranked.sort_by_key(|(cost, _)| *cost);
+        Some(RRes {
+            res: ranked,
+            pos: res.pos.clone(),
+            partial: res.partial,
+        })
+    }
+}
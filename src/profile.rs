@@ -0,0 +1,127 @@
+use std::sync::{Arc, Mutex};
+
+use {CalcOptions, Error, RCalc, ROp, RRes, RSet, Stopwatch};
+
+/// How often a single bound was evaluated during a profiled search, and how often it rejected
+/// the combination.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub struct BoundProfile {
+    /// The source expression of the bound.
+    pub bound: String,
+    /// The number of combinations the bound was evaluated for. Bounds are evaluated in order
+    /// and stop at the first rejection, so later bounds see fewer combinations.
+    pub evaluations: u128,
+    /// The number of combinations the bound rejected.
+    pub rejects: u128,
+}
+
+/// Counts and timings from `RCalc::calc_profiled` and `RCalc::calc_profiled_with`, for finding
+/// which bounds do the work of a search and where its time goes.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub struct Profile {
+    /// The number of combinations evaluated.
+    pub evaluated: u128,
+    /// The number of combinations that satisfied the bounds.
+    pub accepted: u128,
+    /// The total number of bound evaluations across all combinations.
+    pub bound_evaluations: u128,
+    /// The evaluations and rejections of each bound, in the order they were added.
+    pub bounds: Vec<BoundProfile>,
+    /// The time spent evaluating combinations, in seconds.
+    pub search_secs: f64,
+    /// The time spent sorting the results, in seconds.
+    pub sort_secs: f64,
+    /// A lower bound on the heap allocations made by the search: one for the values of each
+    /// combination evaluated, and one for each result kept.
+    pub allocations: u128,
+}
+
+impl<'a> RCalc<'a> {
+    /// As with `calc` for the bounds of `op`, also returning a profile of the search with the
+    /// number of evaluations and rejections of each bound and the time spent in each phase.
+    /// # Example
+    /// ```
+    ///     # use resistor_calc::*;
+    ///     let op = ROpBuilder::new()
+    ///         .bound("R1 + R2 <= 10e3")
+    ///         .bound("R2 / (R1 + R2) ~ 0.25")
+    ///         .build();
+    ///     let (res, profile) = RCalc::e24(2).calc_profiled(&op);
+    ///     assert!(res.is_some());
+    ///     assert_eq!(profile.bounds[0].evaluations, profile.evaluated);
+    ///     assert_eq!(profile.bounds[1].evaluations, profile.accepted);
+    /// ```
+    pub fn calc_profiled(&self, op: &ROp) -> (Option<RRes>, Profile) {
+        let (res, profile) = self.calc_profiled_with(op, |_, err| err, CalcOptions::new());
+        (res.ok(), profile)
+    }
+
+    /// As with `calc_profiled`, searching with `options` as with `calc_with`. Each combination is
+    /// scored by `objective` from its values and the error the bounds of `op` gave it, or `None`
+    /// if they rejected it, so that the bounds can be profiled under the same limits and with
+    /// the same objective, such as ranking by cost, as a search that is not profiled.
+    /// # Example
+    /// ```
+    ///     # use resistor_calc::*;
+    ///     let op = ROpBuilder::new().bound("R2 / (R1 + R2) ~ 0.25").build();
+    ///     // Only the combinations within 1% of the target, ranked by their total resistance.
+    ///     let (res, profile) = RCalc::e24(2).calc_profiled_with(
+    ///         &op,
+    ///         |rs, err| err.filter(|&err| err <= 0.01).map(|_| rs.sum()),
+    ///         CalcOptions::new().top(10),
+    ///     );
+    ///     assert_eq!(res.unwrap().len(), 10);
+    ///     assert!(profile.accepted < profile.bounds[0].evaluations);
+    /// ```
+    pub fn calc_profiled_with(
+        &self,
+        op: &ROp,
+        objective: impl Fn(&RSet, Option<f64>) -> Option<f64> + Sync,
+        mut options: CalcOptions,
+    ) -> (Result<RRes, Error>, Profile) {
+        let stats = Mutex::new(vec![(0, 0); op.bounds().len()]);
+        let counts = Arc::new(Mutex::new((0, 0)));
+        let mut forward = options.take_progress();
+        let options = {
+            let counts = Arc::clone(&counts);
+            options.progress(move |p| {
+                *counts.lock().unwrap() = (p.evaluated, p.accepted);
+                if let Some(ref mut forward) = forward {
+                    forward(p);
+                }
+            })
+        };
+
+        let search = Stopwatch::start();
+        let (res, sort_secs) = self.calc_with_timed(
+            |rs| objective(rs, op.eval_counted(rs.values(), &mut stats.lock().unwrap())),
+            options,
+        );
+        let search_secs = search.secs() - sort_secs;
+        let stats = stats.into_inner().unwrap();
+        let (evaluated, accepted) = *counts.lock().unwrap();
+
+        let profile = Profile {
+            evaluated,
+            accepted,
+            bound_evaluations: stats.iter().map(|s| s.0).sum(),
+            bounds: op
+                .bounds()
+                .iter()
+                .zip(stats)
+                .map(|(bound, (evaluations, rejects))| BoundProfile {
+                    bound: bound.clone(),
+                    evaluations,
+                    rejects,
+                })
+                .collect(),
+            search_secs,
+            sort_secs,
+            allocations: evaluated + accepted,
+        };
+        debug!("Profiled search: {:?}", profile);
+        (res, profile)
+    }
+}
@@ -0,0 +1,147 @@
+use std::collections::HashMap;
+
+use {Price, PriceTable, RCalc, RSet};
+
+/// A source of prices and stock for parts, such as a distributor API or an internal ERP. The
+/// solver consults it through a `Sourcing`, which looks up every value of a calculation once
+/// before the search, so providers may be slow.
+/// # Example
+/// A provider with every value in stock at a flat price:
+/// ```
+///     # use resistor_calc::*;
+///     struct Flat;
+///
+///     impl PartsProvider for Flat {
+///         fn offer(&self, value: f64, package: Option<&str>, _: f64, _: u64) -> Option<Price> {
+///             Some(Price {
+///                 value,
+///                 package: package.unwrap_or("0603").to_string(),
+///                 unit_cost: 0.01,
+///                 moq: 1,
+///                 stock: Some(1000),
+///                 tolerance: Some(0.01),
+///             })
+///         }
+///     }
+///
+///     let rcalc = RCalc::e24(2);
+///     let sourcing = Sourcing::new(&Flat, &rcalc, None, 10);
+///     assert_eq!(sourcing.cost(&RSet::new(&[1e3, 2e3])), Some(0.2));
+/// ```
+pub trait PartsProvider {
+    /// Looks up the best offer for `qty` parts of `value`, in `package` or any package if it is
+    /// `None`, with a tolerance of at most `tolerance`. A `tolerance` of zero places no limit on
+    /// the tolerance. Returns `None` if the part cannot be sourced.
+    fn offer(&self, value: f64, package: Option<&str>, tolerance: f64, qty: u64) -> Option<Price>;
+}
+
+impl PartsProvider for PriceTable {
+    /// The cheapest price in the table for the part with enough stock, where parts of unknown
+    /// stock or tolerance are assumed to be suitable.
+    fn offer(&self, value: f64, package: Option<&str>, tolerance: f64, qty: u64) -> Option<Price> {
+        self.prices()
+            .iter()
+            .filter(|p| ((p.value - value) / value).abs() < 1e-6)
+            .filter(|p| package.map_or(true, |pkg| p.package.eq_ignore_ascii_case(pkg)))
+            .filter(|p| tolerance <= 0.0 || p.tolerance.map_or(true, |t| t <= tolerance + 1e-12))
+            .filter(|p| p.stock.map_or(true, |stock| stock >= qty))
+            .min_by(|a, b| a.order_cost(qty).total_cmp(&b.order_cost(qty)))
+            .cloned()
+    }
+}
+
+/// The offers from a `PartsProvider` for every value that each resistor of a calculation can
+/// take, used to cost results and to rank them by availability.
+#[derive(Debug, Clone)]
+pub struct Sourcing {
+    offers: Vec<HashMap<u64, Price>>,
+    quantity: u64,
+}
+
+impl Sourcing {
+    /// Looks up the offers for `quantity` builds of every value of every resistor of `rcalc`,
+    /// in `package` if given and within the tolerance of each resistor's series.
+    pub fn new(
+        provider: &dyn PartsProvider,
+        rcalc: &RCalc,
+        package: Option<&str>,
+        quantity: u64,
+    ) -> Self {
+        let offers = rcalc
+            .rs
+            .iter()
+            .map(|series| {
+                series
+                    .iter()
+                    .filter_map(|&v| {
+                        let offer = provider.offer(v, package, series.tolerance(), quantity)?;
+                        Some((v.to_bits(), offer))
+                    })
+                    .collect()
+            })
+            .collect();
+        Sourcing { offers, quantity }
+    }
+
+    /// The offer for each resistor of `rs`, or `None` for those that cannot be sourced.
+    pub fn offers(&self, rs: &RSet) -> Vec<Option<&Price>> {
+        rs.values()
+            .iter()
+            .zip(&self.offers)
+            .map(|(v, offers)| offers.get(&v.to_bits()))
+            .collect()
+    }
+
+    /// The cost of the parts for the builds using the values of `rs`, where parts of the same
+    /// value are ordered together. Returns `None` if any of them cannot be sourced in the
+    /// quantity needed.
+    pub fn cost(&self, rs: &RSet) -> Option<f64> {
+        let mut orders: Vec<(f64, &Price, u64)> = Vec::new();
+        for (&v, offer) in rs.values().iter().zip(self.offers(rs)) {
+            let offer = offer?;
+            match orders.iter_mut().find(|(value, _, _)| *value == v) {
+                Some((_, _, qty)) => *qty += self.quantity,
+                None => orders.push((v, offer, self.quantity)),
+            }
+        }
+        orders
+            .into_iter()
+            .map(|(_, offer, qty)| match offer.stock {
+                Some(stock) if stock < qty => None,
+                _ => Some(offer.order_cost(qty)),
+            })
+            .sum()
+    }
+
+    /// Returns true if every part of `rs` can be sourced in the quantity needed.
+    pub fn available(&self, rs: &RSet) -> bool {
+        self.cost(rs).is_some()
+    }
+
+    /// Turns the bounds `f` into an objective that rejects combinations that cannot be sourced
+    /// and adds `cost_weight` times their cost to the error of the rest, so that cheaper
+    /// combinations rank ahead of dearer ones of similar accuracy.
+    /// # Example
+    /// ```
+    ///     # use resistor_calc::*;
+    ///     let prices = PriceTable::from_csv(
+    ///         "value,package,unit_cost,stock\n1k,0603,0.01,0\n3k,0603,0.01,100\n10k,0603,0.01,100\n",
+    ///     ).unwrap();
+    ///     let rcalc = RCalc::e24(2);
+    ///     let sourcing = Sourcing::new(&prices, &rcalc, None, 1);
+    ///     let op = ROpBuilder::new().bound("R2 / (R1 + R2) ~ 0.75").build();
+    ///     let res = rcalc.calc(sourcing.rank(|rs| op.eval(rs), 0.0)).unwrap();
+    ///     // 1k is out of stock, so 3k over 10k is the best that can be built.
+    ///     assert_eq!(res.iter().next().unwrap().1.values(), &[3e3, 10e3]);
+    /// ```
+    pub fn rank<'s>(
+        &'s self,
+        f: impl Fn(&RSet) -> Option<f64> + 's,
+        cost_weight: f64,
+    ) -> impl Fn(&RSet) -> Option<f64> + 's {
+        move |rs: &RSet| {
+            let err = f(rs)?;
+            self.cost(rs).map(|cost| err + cost_weight * cost)
+        }
+    }
+}
@@ -0,0 +1,124 @@
+use std::cmp::Ordering;
+
+use {Error, Excitation, RRes};
+
+/// Power handling of a resistor package, used to check results against pulse loads.
+#[derive(Debug, Clone)]
+pub struct Package {
+    name: String,
+    rating: f64,
+    pulse: Vec<(f64, f64)>,
+}
+
+impl Package {
+    /// Defines a package with the given continuous power rating in watts.
+    pub fn new(name: &str, rating: f64) -> Self {
+        Package {
+            name: name.to_string(),
+            rating,
+            pulse: Vec::new(),
+        }
+    }
+
+    /// Adds a point from the manufacturer's pulse derating curve: the peak power in watts the
+    /// package can withstand for a single pulse of `duration` seconds. Between points the curve
+    /// is interpolated on log-log axes.
+    /// # Example
+    /// ```
+    ///     # use resistor_calc::*;
+    ///     let r0603 = Package::new("0603", 0.1)
+    ///         .pulse_limit(1e-6, 50.0)
+    ///         .pulse_limit(1e-3, 2.0)
+    ///         .pulse_limit(1.0, 0.2);
+    ///     assert_eq!(r0603.max_pulse_power(1e-3), 2.0);
+    /// ```
+    pub fn pulse_limit(mut self, duration: f64, power: f64) -> Self {
+        self.pulse.push((duration, power));
+        self.pulse
+            .sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(Ordering::Equal));
+        self
+    }
+
+    /// The name of the package.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The peak power in watts the package can withstand for a pulse of `duration` seconds. This
+    /// is never less than the continuous rating.
+    pub fn max_pulse_power(&self, duration: f64) -> f64 {
+        let curve = match (self.pulse.first(), self.pulse.last()) {
+            (Some(&(d0, p0)), _) if duration <= d0 => p0,
+            (_, Some(&(dn, pn))) if duration >= dn => pn,
+            (Some(_), Some(_)) => {
+                let i = self.pulse.iter().position(|&(d, _)| d > duration).unwrap();
+                let ((d0, p0), (d1, p1)) = (self.pulse[i - 1], self.pulse[i]);
+                let frac = (duration.ln() - d0.ln()) / (d1.ln() - d0.ln());
+                (p0.ln() + frac * (p1.ln() - p0.ln())).exp()
+            }
+            _ => self.rating,
+        };
+        curve.max(self.rating)
+    }
+}
+
+/// The pulse loading of a single resistor, produced by `RRes::pulse_check`.
+#[derive(Debug, Clone)]
+pub struct PulseCheck {
+    /// The peak power dissipated during the pulse in watts.
+    pub peak_power: f64,
+    /// The peak power the package can withstand for the pulse duration in watts.
+    pub limit: f64,
+    /// Whether the peak power is within the limit.
+    pub ok: bool,
+}
+
+impl RRes {
+    /// Checks each resistor of the `i`th result against a pulse of `duration` seconds, where
+    /// `peak` describes the voltages across the resistors at the peak of the pulse and
+    /// `packages` gives the package used for R1, R2, ..., Rn. Returns an error if there is not
+    /// one package for each resistor.
+    /// # Example
+    /// ```
+    ///     # use resistor_calc::*;
+    ///     let r0603 = Package::new("0603", 0.1).pulse_limit(1e-3, 2.0);
+    ///     let res = RCalc::e24(2)
+    ///         .calc(ROpBuilder::new().bound("R2 / (R1 + R2) ~ 0.1").finish())
+    ///         .unwrap();
+    ///     let surge = Excitation::divider(400.0, 2);
+    ///     let checks = res.pulse_check(0, &surge, 1e-3, &[&r0603, &r0603]).unwrap();
+    ///     assert!(res.pulse_check(0, &surge, 1e-3, &[&r0603]).is_err());
+    ///     if checks.iter().any(|c| !c.ok) {
+    ///         println!("Result would not survive the surge");
+    ///     }
+    /// ```
+    pub fn pulse_check(
+        &self,
+        i: usize,
+        peak: &Excitation,
+        duration: f64,
+        packages: &[&Package],
+    ) -> Result<Vec<PulseCheck>, Error> {
+        let values = &(self.res[i].1).0;
+        if packages.len() != values.len() {
+            return Err(Error::Parse(format!(
+                "{} packages given for {} resistors",
+                packages.len(),
+                values.len()
+            )));
+        }
+        Ok(peak
+            .power(values)
+            .into_iter()
+            .zip(packages)
+            .map(|(peak_power, pkg)| {
+                let limit = pkg.max_pulse_power(duration);
+                PulseCheck {
+                    peak_power,
+                    limit,
+                    ok: peak_power <= limit,
+                }
+            })
+            .collect())
+    }
+}
@@ -0,0 +1,143 @@
+//! Python bindings for the `python` feature, built as an extension module with maturin using
+//! the settings in `pyproject.toml`:
+//!
+//! ```text
+//! maturin develop --release
+//! ```
+//!
+//! The module mirrors the Rust API:
+//!
+//! ```python
+//! from resistor_calc import RCalc, ROpBuilder
+//!
+//! ops = ROpBuilder().bound("R2 / (R1 + R2) ~ 0.25").bound("R1 + R2 <= 100e3")
+//! res = RCalc(["E24", "E24"]).calc(ops)
+//! for error, values in res.top(5):
+//!     print(error, values)
+//! ```
+
+use pyo3::exceptions::{PyIndexError, PyValueError};
+use pyo3::prelude::*;
+
+use {RCalc, ROpBuilder, RRes, RSeries};
+
+/// Builds the bounds of a problem, as with `ROpBuilder`.
+#[pyclass(name = "ROpBuilder")]
+#[derive(Clone, Default)]
+pub struct PyROpBuilder {
+    ops: ROpBuilder,
+}
+
+#[pymethods]
+impl PyROpBuilder {
+    #[new]
+    fn new() -> Self {
+        PyROpBuilder::default()
+    }
+
+    /// Adds a bound such as `"R2 / (R1 + R2) ~ 0.25"`, raising `ValueError` if it is invalid.
+    fn bound<'py>(mut slf: PyRefMut<'py, Self>, expr: &str) -> PyResult<PyRefMut<'py, Self>> {
+        slf.ops = slf
+            .ops
+            .clone()
+            .try_bound(expr)
+            .map_err(|e| PyValueError::new_err(e.to_string()))?;
+        Ok(slf)
+    }
+
+    /// Declares a named constant that can be used in the bounds.
+    fn constant<'py>(mut slf: PyRefMut<'py, Self>, name: &str, value: f64) -> PyRefMut<'py, Self> {
+        slf.ops = slf.ops.clone().constant(name, value);
+        slf
+    }
+
+    /// The source expressions of the bounds added so far.
+    fn bounds(&self) -> Vec<String> {
+        self.ops.clone().build().bounds().to_vec()
+    }
+}
+
+/// A calculator over positions drawn from named series, as with `RCalc`.
+#[pyclass(name = "RCalc")]
+pub struct PyRCalc {
+    series: Vec<&'static RSeries>,
+}
+
+#[pymethods]
+impl PyRCalc {
+    /// Creates a calculator from series names such as `["E24", "E24", "E6"]`, raising
+    /// `ValueError` if any are unknown.
+    #[new]
+    fn new(series: Vec<String>) -> PyResult<Self> {
+        let series = series
+            .iter()
+            .map(|name| {
+                RSeries::by_name(name)
+                    .ok_or_else(|| PyValueError::new_err(format!("Unknown series '{}'", name)))
+            })
+            .collect::<PyResult<_>>()?;
+        Ok(PyRCalc { series })
+    }
+
+    /// The number of combinations a search evaluates.
+    fn combinations(&self) -> u128 {
+        RCalc::new(self.series.clone()).combinations()
+    }
+
+    /// Searches every combination against the bounds, returning the results or `None` if no
+    /// combination satisfies them, and raising `ValueError` if a bound cannot be evaluated. The
+    /// GIL is released during the search.
+    fn calc(&self, py: Python, ops: &PyROpBuilder) -> PyResult<Option<PyRRes>> {
+        let op = ops.ops.clone().build();
+        let rcalc = RCalc::new(self.series.clone());
+        rcalc
+            .validate(&op)
+            .map_err(|e| PyValueError::new_err(e.to_string()))?;
+        Ok(py
+            .allow_threads(move || rcalc.calc(|rs| op.eval(rs)))
+            .map(|res| PyRRes { res }))
+    }
+}
+
+/// The results of a search, as with `RRes`. Each result is a tuple of its error and the values
+/// of R1, R2, ..., Rn.
+#[pyclass(name = "RRes")]
+pub struct PyRRes {
+    res: RRes,
+}
+
+#[pymethods]
+impl PyRRes {
+    fn __len__(&self) -> usize {
+        self.res.len()
+    }
+
+    fn __getitem__(&self, i: usize) -> PyResult<(f64, Vec<f64>)> {
+        self.res
+            .get(i)
+            .map(|(err, rs)| (*err as f64 / 1e9, rs.values().to_vec()))
+            .ok_or_else(|| PyIndexError::new_err("result index out of range"))
+    }
+
+    /// The best `n` results, from lowest to highest error.
+    fn top(&self, n: usize) -> Vec<(f64, Vec<f64>)> {
+        self.res
+            .iter()
+            .take(n)
+            .map(|(err, rs)| (*err as f64 / 1e9, rs.values().to_vec()))
+            .collect()
+    }
+
+    /// Whether the search was stopped early, so better combinations may exist.
+    fn is_partial(&self) -> bool {
+        self.res.is_partial()
+    }
+}
+
+#[pymodule]
+fn resistor_calc(m: &Bound<PyModule>) -> PyResult<()> {
+    m.add_class::<PyROpBuilder>()?;
+    m.add_class::<PyRCalc>()?;
+    m.add_class::<PyRRes>()?;
+    Ok(())
+}
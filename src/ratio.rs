@@ -0,0 +1,133 @@
+use core::cmp::Ordering;
+
+use RSeries;
+
+/// The relative difference below which two ratios are treated as the same, so that pairs that
+/// differ only by a power of ten, such as 1K/3K3 and 10K/33K, share a ratio.
+const SAME: f64 = 1e-9;
+
+/// Whether `a` and `b` are the same ratio, allowing for rounding.
+fn same(a: f64, b: f64) -> bool {
+    (a - b).abs() <= SAME * a.max(b)
+}
+
+/// The value that most of `pairs` divide to exactly, so that a ratio reached by rounding, such as
+/// 3.3/1.1, is shown as the 3 of 3/1 rather than just below it.
+fn representative(pairs: &[(f64, f64)]) -> f64 {
+    let mut values: Vec<f64> = pairs.iter().map(|&(n, d)| n / d).collect();
+    values.sort_by(|a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+    let mut best = (0, values[0]);
+    let mut run = 0;
+    for (i, &v) in values.iter().enumerate() {
+        run = if i > 0 && values[i - 1] == v {
+            run + 1
+        } else {
+            1
+        };
+        if run > best.0 {
+            best = (run, v);
+        }
+    }
+    best.1
+}
+
+/// The ratios achievable with a numerator drawn from one series and a denominator from another,
+/// for problems that constrain only the ratio of two resistors, such as the gain of an amplifier
+/// or the division of a divider.
+///
+/// The distinct ratios form a series of their own, so that they can be searched as a single
+/// position in place of the pair. As many pairs give the same ratio, this shrinks the search,
+/// and the bounds are written in terms of the ratio itself. The pairs giving each ratio are
+/// recovered afterwards with `pairs`.
+/// # Example
+/// ```
+///     # use resistor_calc::*;
+///     // An LM317 setting 5V, with the ratio of its resistors searched as R1.
+///     let ratio = Ratio::new(&E24, &E24);
+///     assert!(ratio.len() < (24 * 7) * (24 * 7) / 3);
+///     let res = RCalc::new(vec![ratio.series()])
+///         .calc(ROpBuilder::new().bound("1.25 * (1 + R1) ~ 5").finish())
+///         .unwrap();
+///     let (_, best) = res.iter().next().unwrap();
+///     assert_eq!(best.values(), &[3.0]);
+///     // 3K / 1K, 3K3 / 1K1, 30K / 10K and so on.
+///     let pairs = ratio.pairs(best.r(1));
+///     assert!(pairs.contains(&(3e3, 1e3)));
+///     assert!(pairs.iter().all(|&(num, den)| (num / den - 3.0).abs() < 1e-9));
+/// ```
+#[derive(Debug, Clone)]
+pub struct Ratio {
+    series: RSeries,
+    pairs: Vec<Vec<(f64, f64)>>,
+}
+
+impl Ratio {
+    /// Finds the distinct ratios with a numerator from `num` and a denominator from `den`. The
+    /// tolerances of the ratio series are the sums of those of the two series, the worst case
+    /// when the parts drift in opposite directions.
+    pub fn new(num: &RSeries, den: &RSeries) -> Self {
+        let mut all: Vec<(f64, f64, f64)> = num
+            .iter()
+            .flat_map(|&n| den.iter().map(move |&d| (n / d, n, d)))
+            .collect();
+        all.sort_by(|a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+
+        let mut ratios: Vec<f64> = Vec::new();
+        let mut pairs: Vec<Vec<(f64, f64)>> = Vec::new();
+        for (ratio, n, d) in all {
+            match ratios.last() {
+                Some(&last) if same(last, ratio) => pairs.last_mut().unwrap().push((n, d)),
+                _ => {
+                    ratios.push(ratio);
+                    pairs.push(vec![(n, d)]);
+                }
+            }
+        }
+        for (ratio, group) in ratios.iter_mut().zip(&mut pairs) {
+            group.sort_by(|a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+            *ratio = representative(group);
+        }
+        debug!(
+            "{} pairs of {} and {} give {} distinct ratios",
+            num.len() * den.len(),
+            num.name(),
+            den.name(),
+            ratios.len()
+        );
+        let series =
+            RSeries::with_powers(&format!("{}/{}", num.name(), den.name()), &ratios, &[1.0])
+                .with_tolerance(num.tolerance() + den.tolerance())
+                .with_tempco(num.tempco() + den.tempco())
+                .with_drift(num.drift() + den.drift());
+        Ratio { series, pairs }
+    }
+
+    /// The distinct ratios as a series, in increasing order, to be searched as a position of an
+    /// `RCalc`.
+    pub fn series(&self) -> &RSeries {
+        &self.series
+    }
+
+    /// The number of distinct ratios.
+    pub fn len(&self) -> usize {
+        self.pairs.len()
+    }
+
+    /// Returns true if there are no ratios, as when either series is empty.
+    pub fn is_empty(&self) -> bool {
+        self.pairs.is_empty()
+    }
+
+    /// The pairs of numerator and denominator that give `ratio`, such as a value of a ratio
+    /// position in a result, in increasing order of numerator. This is empty if `ratio` is not
+    /// one of the ratios.
+    pub fn pairs(&self, ratio: f64) -> &[(f64, f64)] {
+        let values = self.series.values();
+        let idx = values.partition_point(|&v| v < ratio);
+        [idx.checked_sub(1), Some(idx)]
+            .iter()
+            .flatten()
+            .find(|&&i| i < values.len() && same(values[i], ratio))
+            .map_or(&[], |&i| &self.pairs[i])
+    }
+}
@@ -0,0 +1,353 @@
+extern crate serde_json;
+
+use std::collections::BTreeMap;
+use {
+    CalcOptions, Error, Estimate, Excitation, PriceTable, Progress, RCalc, ROp, ROpBuilder, RRes,
+    RSeries, RSet, Sourcing, Stopwatch,
+};
+
+/// A single combination of values within a `Report`.
+#[derive(Debug, Serialize)]
+pub struct ReportMatch {
+    /// The error of the combination, as returned by the bounds.
+    pub error: f64,
+    /// The values of R1, R2, ..., Rn in ohms.
+    pub values: Vec<f64>,
+    /// The power dissipated in R1, R2, ..., Rn in watts, if added with `Report::with_power`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub power: Option<Vec<f64>>,
+    /// The cost of the parts, if added with `Report::with_cost` and every value is priced.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cost: Option<f64>,
+    /// Whether every part can be sourced, if added with `Report::with_sourcing`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub available: Option<bool>,
+}
+
+/// A machine-readable summary of a calculation, including the problem that was solved and the
+/// results, intended to be serialised to JSON.
+/// # Example
+/// ```
+///     # use resistor_calc::*;
+///     let rcalc = RCalc::e3(2);
+///     let ops = ROpBuilder::new().constant("VIN", 5.0).bound("VIN * (R1 + R2) ~ 2500");
+///     let report = Report::run(&rcalc, ops);
+///     assert_eq!(report.series, vec!["E3", "E3"]);
+///     assert_eq!(report.constants["VIN"], 5.0);
+///     println!("{}", report.to_json());
+/// ```
+#[derive(Debug, Serialize)]
+pub struct Report {
+    /// The bound expressions that describe the problem.
+    pub bounds: Vec<String>,
+    /// The named constants used in the bounds, with their values, as with
+    /// `ROpBuilder::constant`.
+    pub constants: BTreeMap<String, f64>,
+    /// The names of the series used for R1, R2, ..., Rn.
+    pub series: Vec<String>,
+    /// The number of combinations searched.
+    pub combinations: u128,
+    /// Time taken to perform the calculation, in seconds.
+    pub elapsed: f64,
+    /// All matching combinations, from lowest to highest error.
+    pub results: Vec<ReportMatch>,
+}
+
+impl Report {
+    /// Runs the calculation described by `ops` over the resistors configured in `rcalc` and
+    /// captures the problem, timing and results.
+    ///
+    /// Panics if a bound cannot be evaluated, as with `RCalc::calc`; `run_with` returns an error
+    /// instead.
+    pub fn run(rcalc: &RCalc, ops: ROpBuilder) -> Self {
+        Report::run_with_progress(rcalc, ops, |_| ())
+    }
+
+    /// As with `run`, calling `progress` periodically as with `RCalc::calc_with_progress`.
+    pub fn run_with_progress(
+        rcalc: &RCalc,
+        ops: ROpBuilder,
+        progress: impl FnMut(&Progress),
+    ) -> Self {
+        Report::run_with(rcalc, ops, CalcOptions::new().progress(progress))
+            .unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// As with `run`, searching with the given `options` as with `RCalc::calc_with`. The bounds
+    /// are checked with `RCalc::validate` first, so that a bound that cannot be evaluated is
+    /// returned as an error rather than panicking, as is a search refused by a guard. A search
+    /// that finds nothing gives a report with no results.
+    /// # Example
+    /// ```
+    ///     # use resistor_calc::*;
+    ///     let rcalc = RCalc::e24(2);
+    ///     let ops = ROpBuilder::new().bound("R2 / (R1 + R2) ~ 0.25");
+    ///     let report = Report::run_with(&rcalc, ops, CalcOptions::new().top(5)).unwrap();
+    ///     assert_eq!(report.results.len(), 5);
+    ///     let ops = ROpBuilder::new().bound("R3 ~ 1");
+    ///     let err = Report::run_with(&rcalc, ops, CalcOptions::new()).unwrap_err();
+    ///     assert!(matches!(err, Error::Eval(_)));
+    /// ```
+    pub fn run_with(rcalc: &RCalc, ops: ROpBuilder, options: CalcOptions) -> Result<Self, Error> {
+        let op = ops.build();
+        rcalc.validate(&op)?;
+        let start = Stopwatch::start();
+        let res = match rcalc.calc_with(|rs| op.eval(rs), options) {
+            Ok(res) => Some(res),
+            Err(Error::Solver(_)) => None,
+            Err(e) => return Err(e),
+        };
+        Ok(Report::new(rcalc, &op, res.as_ref(), start.secs()))
+    }
+
+    /// A report of results already found for `op` over `rcalc`, such as by a search with options
+    /// that `run_with` does not offer, which took `elapsed` seconds.
+    pub fn from_res(rcalc: &RCalc, op: &ROp, res: &RRes, elapsed: f64) -> Self {
+        Report::new(rcalc, op, Some(res), elapsed)
+    }
+
+    fn new(rcalc: &RCalc, op: &ROp, res: Option<&RRes>, elapsed: f64) -> Self {
+        Report {
+            bounds: op.bounds().to_vec(),
+            constants: op.constants().iter().cloned().collect(),
+            series: rcalc.rs.iter().map(|r| r.name().to_string()).collect(),
+            combinations: rcalc.combinations(),
+            elapsed,
+            results: res.map_or_else(Vec::new, |res| {
+                res.iter()
+                    .map(|&(err, ref rs)| ReportMatch {
+                        error: (err as f64) / 1e9,
+                        values: rs.0.to_vec(),
+                        power: None,
+                        cost: None,
+                        available: None,
+                    })
+                    .collect()
+            }),
+        }
+    }
+
+    /// Adds the power dissipated in each resistor of every result, for the given excitation.
+    pub fn with_power(mut self, exc: &Excitation) -> Self {
+        for m in &mut self.results {
+            m.power = Some(exc.power(&m.values));
+        }
+        self
+    }
+
+    /// Adds the cost of the parts of every result for `quantity` builds, as with
+    /// `PriceTable::cost`.
+    pub fn with_cost(mut self, prices: &PriceTable, quantity: u64) -> Self {
+        for m in &mut self.results {
+            m.cost = prices.cost(&RSet::new(&m.values), quantity);
+        }
+        self
+    }
+
+    /// Adds the cost of the parts of every result and whether they can all be sourced, from the
+    /// offers of `sourcing`.
+    pub fn with_sourcing(mut self, sourcing: &Sourcing) -> Self {
+        for m in &mut self.results {
+            m.cost = sourcing.cost(&RSet::new(&m.values));
+            m.available = Some(m.cost.is_some());
+        }
+        self
+    }
+
+    /// Serialises the report as pretty-printed JSON.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap()
+    }
+}
+
+impl RRes {
+    /// Exports the best `top` results as a JSON array of matches, in the same form as the
+    /// results of a `Report`.
+    pub fn to_json(&self, top: usize) -> String {
+        let matches: Vec<ReportMatch> = self
+            .res
+            .iter()
+            .take(top)
+            .map(|&(err, ref rs)| ReportMatch {
+                error: (err as f64) / 1e9,
+                values: rs.0.to_vec(),
+                power: None,
+                cost: None,
+                available: None,
+            })
+            .collect();
+        serde_json::to_string_pretty(&matches).unwrap()
+    }
+}
+
+/// A problem to solve, as read from JSON by `Problem::from_json`. This allows the optimiser to
+/// be driven from other languages and tools.
+/// # Example
+/// ```
+///     # use resistor_calc::*;
+///     let problem = Problem::from_json(r#"{
+///         "series": ["E24", "E24"],
+///         "bounds": ["R2 / (R1 + R2) ~ 0.25"],
+///         "top": 3
+///     }"#).unwrap();
+///     let report = problem.solve().unwrap();
+///     assert_eq!(report.results.len(), 3);
+/// ```
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct Problem {
+    /// The names of the series to draw R1, R2, ..., Rn from, as accepted by `RSeries::by_name`.
+    #[serde(default)]
+    pub series: Vec<String>,
+    /// The bound expressions that describe the problem.
+    #[serde(default)]
+    pub bounds: Vec<String>,
+    /// Named constants that can be used in the bounds, as with `ROpBuilder::constant`.
+    #[serde(default)]
+    pub constants: BTreeMap<String, f64>,
+    /// The maximum number of results to report, or all if absent.
+    #[serde(default)]
+    pub top: Option<usize>,
+    /// The most combinations the search may have, a larger search being refused with
+    /// `Error::TooLarge` as with `CalcOptions::guard`, or no limit if absent.
+    #[serde(default)]
+    pub max_combinations: Option<u64>,
+}
+
+impl Problem {
+    /// Parses a problem from JSON.
+    pub fn from_json(json: &str) -> Result<Self, Error> {
+        serde_json::from_str(json).map_err(|e| Error::Parse(e.to_string()))
+    }
+
+    /// Parses a problem from TOML, with the same fields as `from_json`.
+    /// # Example
+    /// ```
+    ///     # use resistor_calc::*;
+    ///     let problem = Problem::from_toml(r#"
+    ///         series = ["E24", "E24"]
+    ///         bounds = ["R2 / (R1 + R2) ~ 0.25"]
+    ///     "#).unwrap();
+    ///     assert_eq!(problem.bounds.len(), 1);
+    /// ```
+    pub fn from_toml(text: &str) -> Result<Self, Error> {
+        toml::from_str(text).map_err(|e| Error::Parse(e.to_string()))
+    }
+
+    /// Looks up the series and builds the bounds of the problem.
+    pub(crate) fn parts(&self) -> Result<(Vec<&'static RSeries>, ROpBuilder), Error> {
+        let series = self
+            .series
+            .iter()
+            .map(|name| {
+                RSeries::by_name(name)
+                    .ok_or_else(|| Error::Parse(format!("Unknown series '{}'", name)))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        if series.is_empty() {
+            return Err(Error::Parse("No series given".to_string()));
+        }
+        let ops = self
+            .constants
+            .iter()
+            .fold(ROpBuilder::new(), |ops, (name, &value)| {
+                ops.constant(name, value)
+            });
+        let ops = self
+            .bounds
+            .iter()
+            .try_fold(ops, |ops, bound| ops.try_bound(bound))?;
+        Ok((series, ops))
+    }
+
+    /// Looks up the series and builds the bounds, for solving the problem by other means than
+    /// `solve`, such as with `RCalc::calc_limited`. The bounds are checked with `RCalc::validate`,
+    /// so that evaluating them cannot panic, and those that cannot be evaluated for the series of
+    /// the problem are returned as `Error::Eval`.
+    /// # Example
+    /// ```
+    ///     # use resistor_calc::*;
+    ///     let problem = Problem::from_toml(r#"
+    ///         series = ["E24", "E24"]
+    ///         bounds = ["R2 / (R1 + R2) ~ 0.25"]
+    ///     "#).unwrap();
+    ///     let (rcalc, op) = problem.prepare().unwrap();
+    ///     let res = rcalc.calc_limited(|rs| op.eval(rs), &Limits::default(), |_| ());
+    ///     let problem = Problem::from_toml(r#"
+    ///         series = ["E24", "E24"]
+    ///         bounds = ["R3 ~ 1"]
+    ///     "#).unwrap();
+    ///     assert!(matches!(problem.prepare(), Err(Error::Eval(_))));
+    /// ```
+    pub fn prepare(&self) -> Result<(RCalc<'static>, ROp), Error> {
+        let (series, ops) = self.parts()?;
+        let (rcalc, op) = (RCalc::new(series), ops.build());
+        rcalc.validate(&op)?;
+        Ok((rcalc, op))
+    }
+
+    /// Solves the problem, producing a report of the results. Bounds that cannot be evaluated
+    /// for the series of the problem are returned as `Error::Eval`.
+    /// # Example
+    /// ```
+    ///     # use resistor_calc::*;
+    ///     let problem = Problem::from_json(r#"{
+    ///         "series": ["E24", "E24"],
+    ///         "bounds": ["R3 ~ 1"]
+    ///     }"#).unwrap();
+    ///     assert!(matches!(problem.solve(), Err(Error::Eval(_))));
+    /// ```
+    pub fn solve(&self) -> Result<Report, Error> {
+        self.solve_with(CalcOptions::new())
+    }
+
+    /// As with `solve`, calling `progress` periodically as with `RCalc::calc_with_progress`.
+    pub fn solve_with_progress(&self, progress: impl FnMut(&Progress)) -> Result<Report, Error> {
+        self.solve_with(CalcOptions::new().progress(progress))
+    }
+
+    /// As with `solve`, searching with the given `options` as with `Report::run_with`. The `top`
+    /// and `max_combinations` of the problem, if given, replace any set in `options`.
+    /// # Example
+    /// ```
+    ///     # use resistor_calc::*;
+    ///     let problem = Problem::from_json(r#"{
+    ///         "series": ["E96", "E96", "E96"],
+    ///         "bounds": ["R2 / (R1 + R2 + R3) ~ 0.25"],
+    ///         "max_combinations": 1000000
+    ///     }"#).unwrap();
+    ///     assert!(matches!(problem.solve_with(CalcOptions::new()), Err(Error::TooLarge(_))));
+    /// ```
+    pub fn solve_with(&self, options: CalcOptions) -> Result<Report, Error> {
+        let (series, ops) = self.parts()?;
+        let options = match self.top {
+            Some(top) => options.top(top),
+            None => options,
+        };
+        let options = match self.max_combinations {
+            Some(max) => options.guard(u128::from(max), |_| false),
+            None => options,
+        };
+        let mut report = Report::run_with(&RCalc::new(series), ops, options)?;
+        if let Some(top) = self.top {
+            report.results.truncate(top);
+        }
+        Ok(report)
+    }
+
+    /// Estimates the cost of solving the problem with `RCalc::estimate`, using `samples`
+    /// randomly chosen combinations. Bounds that cannot be evaluated for the series of the
+    /// problem are returned as `Error::Eval`, as with `solve`.
+    /// # Example
+    /// ```
+    ///     # use resistor_calc::*;
+    ///     let problem = Problem::from_json(r#"{
+    ///         "series": ["E24", "E24"],
+    ///         "bounds": ["R3 ~ 1"]
+    ///     }"#).unwrap();
+    ///     assert!(matches!(problem.estimate(100), Err(Error::Eval(_))));
+    /// ```
+    pub fn estimate(&self, samples: usize) -> Result<Estimate, Error> {
+        let (rcalc, op) = self.prepare()?;
+        Ok(rcalc.estimate(|rs| op.eval(rs), samples))
+    }
+}
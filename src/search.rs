@@ -0,0 +1,319 @@
+use std::iter;
+use std::mem;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use itertools::Itertools;
+
+use cache::Plan;
+use {CalcOptions, Executor, Progress, RCalc, RRes, RSet, Stopwatch, Task};
+
+/// Limits on the resources used by `RCalc::calc_limited`, so that long searches behave
+/// predictably on shared machines.
+/// # Example
+/// ```
+///     # use resistor_calc::*;
+///     use std::time::Duration;
+///     let limits = Limits {
+///         jobs: 4,
+///         max_results: Some(100),
+///         time_limit: Some(Duration::from_secs(60)),
+///         ..Default::default()
+///     };
+/// ```
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub struct Limits {
+    /// The number of threads to search with.
+    pub jobs: usize,
+    /// The maximum number of results to keep, discarding those with the highest error.
+    pub max_results: Option<usize>,
+    /// The time after which the search stops, returning the results found so far.
+    pub time_limit: Option<Duration>,
+    /// The approximate maximum memory in bytes to use for storing results.
+    pub memory_limit: Option<usize>,
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Limits {
+            jobs: 1,
+            max_results: None,
+            time_limit: None,
+            memory_limit: None,
+        }
+    }
+}
+
+/// How often each thread checks the time limit, in combinations.
+const DEADLINE_INTERVAL: usize = 1024;
+
+/// Counters shared between the searching threads.
+struct Shared {
+    evaluated: AtomicUsize,
+    accepted: AtomicUsize,
+    best: Mutex<Option<f64>>,
+    stop: AtomicBool,
+}
+
+/// Sorts `res` and keeps only the best `cap` results.
+fn prune(res: &mut Vec<(u64, RSet)>, cap: Option<usize>) {
+    res.sort_by_key(|(err, _rs)| *err);
+    if let Some(cap) = cap {
+        if res.len() > cap {
+            trace!("Pruning {} results to the best {}", res.len(), cap);
+            res.truncate(cap);
+        }
+    }
+}
+
+impl<'a> RCalc<'a> {
+    /// As with `calc_with_progress`, but spreading the search across threads and bounded by
+    /// `limits`. The values of R1 are shared out between `limits.jobs` threads, so `f` must be
+    /// `Sync`. If the time limit is reached the results found so far are returned and
+    /// `RRes::is_partial` is set.
+    /// # Example
+    /// ```
+    ///     # use resistor_calc::*;
+    ///     let op = ROpBuilder::new().bound("R2 / (R1 + R2) ~ 0.25").build();
+    ///     let limits = Limits {
+    ///         jobs: 2,
+    ///         max_results: Some(10),
+    ///         ..Default::default()
+    ///     };
+    ///     let res = RCalc::e24(2).calc_limited(|rs| op.eval(rs), &limits, |_| ()).unwrap();
+    ///     assert_eq!(res.len(), 10);
+    /// ```
+    pub fn calc_limited(
+        &self,
+        f: impl Fn(&RSet) -> Option<f64> + Sync,
+        limits: &Limits,
+        progress: impl FnMut(&Progress),
+    ) -> Option<RRes> {
+        let options = CalcOptions::new().limits(limits.clone()).progress(progress);
+        self.calc_with(f, options).ok()
+    }
+
+    /// As with `calc_limited`, but running the parts of the search with `executor` rather than
+    /// on threads of its own. The search is split into `limits.jobs` tasks.
+    /// # Example
+    /// ```
+    ///     # use resistor_calc::*;
+    ///     let op = ROpBuilder::new().bound("R2 / (R1 + R2) ~ 0.25").build();
+    ///     let limits = Limits { jobs: 4, ..Default::default() };
+    ///     let res = RCalc::e24(2).calc_on(|rs| op.eval(rs), &limits, &Inline, |_| ());
+    /// ```
+    pub fn calc_on(
+        &self,
+        f: impl Fn(&RSet) -> Option<f64> + Sync,
+        limits: &Limits,
+        executor: &dyn Executor,
+        progress: impl FnMut(&Progress),
+    ) -> Option<RRes> {
+        let options = CalcOptions::new()
+            .limits(limits.clone())
+            .executor(executor)
+            .progress(progress);
+        self.calc_with(f, options).ok()
+    }
+
+    /// The search behind every `calc_*` method: splits the values of R1 into `parts` tasks run by
+    /// `executor`, stopping early, with partial results, once `cancelled` returns true.
+    pub(crate) fn search(
+        &self,
+        f: impl Fn(&RSet) -> Option<f64> + Sync,
+        limits: &Limits,
+        executor: &dyn Executor,
+        parts: usize,
+        cancelled: &(dyn Fn() -> bool + Sync),
+        progress: impl FnMut(&Progress),
+    ) -> Option<RRes> {
+        self.search_timed(f, limits, executor, parts, cancelled, progress)
+            .0
+    }
+
+    /// As with `search`, also returning the time spent sorting the results, in seconds.
+    pub(crate) fn search_timed(
+        &self,
+        f: impl Fn(&RSet) -> Option<f64> + Sync,
+        limits: &Limits,
+        executor: &dyn Executor,
+        parts: usize,
+        cancelled: &(dyn Fn() -> bool + Sync),
+        mut progress: impl FnMut(&Progress),
+    ) -> (Option<RRes>, f64) {
+        let per_result = mem::size_of::<(u64, RSet)>() + self.rs.len() * mem::size_of::<f64>();
+        let cap = match (limits.max_results, limits.memory_limit) {
+            (Some(max), Some(mem)) => Some(max.min(mem / per_result)),
+            (max, mem) => max.or_else(|| mem.map(|mem| mem / per_result)),
+        };
+        let deadline = limits.time_limit.map(|limit| Instant::now() + limit);
+        let shared = Shared {
+            evaluated: AtomicUsize::new(0),
+            accepted: AtomicUsize::new(0),
+            best: Mutex::new(None),
+            stop: AtomicBool::new(false),
+        };
+        if self.rs.is_empty() {
+            return (None, 0.0);
+        }
+        let jobs = parts.max(1);
+        let plan = self.plan(jobs);
+        let snapshot = |shared: &Shared| Progress {
+            evaluated: shared.evaluated.load(Ordering::Relaxed) as u128,
+            accepted: shared.accepted.load(Ordering::Relaxed) as u128,
+            total: self.combinations(),
+            best: *shared.best.lock().unwrap(),
+        };
+
+        debug!(
+            "Searching {} combinations in {} parts, keeping {:?} results",
+            self.combinations(),
+            jobs,
+            cap
+        );
+        let start = Stopwatch::start();
+        let results = Mutex::new(Vec::new());
+        let tasks: Vec<Task> = plan
+            .firsts
+            .iter()
+            .map(|firsts| {
+                let (f, shared, results) = (&f, &shared, &results);
+                Box::new(move || {
+                    let part = self.search_part(f, firsts, cap, deadline, cancelled, shared);
+                    let mut results = results.lock().unwrap();
+                    results.extend(part);
+                    // Tasks run one after another keep the results of all of them within the cap.
+                    if cap.is_some_and(|max| results.len() >= 2 * max.max(1)) {
+                        prune(&mut results, cap);
+                    }
+                }) as Task
+            })
+            .collect();
+        executor.run(tasks, &mut || progress(&snapshot(&shared)));
+        let mut res = results.into_inner().unwrap();
+        let done = snapshot(&shared);
+        progress(&done);
+        info!(
+            "Searched {} of {} combinations in {:.3}s, {} accepted, best error {:?}",
+            done.evaluated,
+            done.total,
+            start.secs(),
+            done.accepted,
+            done.best
+        );
+        let sort = Stopwatch::start();
+        prune(&mut res, cap);
+        let sort_secs = sort.secs();
+        if res.is_empty() {
+            return (None, sort_secs);
+        }
+        let res = RRes {
+            res,
+            pos: self.positions(),
+            partial: shared.stop.load(Ordering::Relaxed),
+        };
+        (Some(res), sort_secs)
+    }
+
+    /// The values searched for each position, kept between calculations.
+    fn candidates(&self) -> &[Vec<f64>] {
+        self.cache.candidates.get_or_init(|| {
+            self.rs
+                .iter()
+                .map(|r| r.iter().cloned().collect())
+                .collect()
+        })
+    }
+
+    /// The plan of a search in `parts` parts, sharing out the values of R1 between them, kept
+    /// between calculations.
+    fn plan(&self, parts: usize) -> Arc<Plan> {
+        let mut plans = self.cache.plans.lock().unwrap();
+        plans
+            .entry(parts)
+            .or_insert_with(|| {
+                let firsts = self.candidates().first().map_or(&[][..], |r| &r[..]);
+                Arc::new(Plan {
+                    firsts: (0..parts)
+                        .map(|part| firsts.iter().cloned().skip(part).step_by(parts).collect())
+                        .collect(),
+                })
+            })
+            .clone()
+    }
+
+    /// Searches every combination whose R1 is one of `firsts`.
+    fn search_part(
+        &self,
+        f: &(dyn Fn(&RSet) -> Option<f64> + Sync),
+        firsts: &[f64],
+        cap: Option<usize>,
+        deadline: Option<Instant>,
+        cancelled: &(dyn Fn() -> bool + Sync),
+        shared: &Shared,
+    ) -> Vec<(u64, RSet)> {
+        let mut res = Vec::new();
+        let mut best = None;
+        let mut countdown = DEADLINE_INTERVAL;
+        for &first in firsts {
+            trace!("Searching R1 = {}", first);
+            let rest: Box<dyn Iterator<Item = Vec<f64>>> = match self.candidates() {
+                [_] => Box::new(iter::once(Vec::new())),
+                candidates => Box::new(
+                    candidates[1..]
+                        .iter()
+                        .map(|r| r.iter().cloned())
+                        .multi_cartesian_product(),
+                ),
+            };
+            for others in rest {
+                let mut values = vec![first];
+                values.extend(others);
+                let rs = RSet(values.into_boxed_slice());
+                shared.evaluated.fetch_add(1, Ordering::Relaxed);
+                if let Some(err) = f(&rs) {
+                    shared.accepted.fetch_add(1, Ordering::Relaxed);
+                    let improved = match best {
+                        Some(b) => err < b,
+                        None => true,
+                    };
+                    if improved {
+                        best = Some(err);
+                        let mut shared_best = shared.best.lock().unwrap();
+                        *shared_best = Some(shared_best.map_or(err, |b: f64| b.min(err)));
+                    }
+                    res.push(((err * 1e9).round() as u64, rs));
+                    if let Some(max) = cap {
+                        if res.len() >= 2 * max.max(1) {
+                            prune(&mut res, cap);
+                        }
+                    }
+                }
+                countdown -= 1;
+                if countdown == 0 {
+                    countdown = DEADLINE_INTERVAL;
+                    if let Some(deadline) = deadline {
+                        if Instant::now() >= deadline && !shared.stop.swap(true, Ordering::Relaxed)
+                        {
+                            info!("Time limit reached, stopping the search");
+                        }
+                    }
+                    if cancelled() && !shared.stop.swap(true, Ordering::Relaxed) {
+                        info!("Search cancelled");
+                    }
+                    if shared.stop.load(Ordering::Relaxed) {
+                        return res;
+                    }
+                }
+            }
+        }
+        debug!(
+            "Finished a chunk of {} values of R1 with {} results",
+            firsts.len(),
+            res.len()
+        );
+        res
+    }
+}
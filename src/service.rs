@@ -0,0 +1,227 @@
+//! A small HTTP service for the `service` feature, so that a shared machine can solve problems
+//! for a team. Requests and responses are JSON, with problems in the form of
+//! `Problem::from_json` and results in the form of `Report::to_json`.
+//!
+//! | Endpoint        | Request                                   | Response                     |
+//! |-----------------|-------------------------------------------|------------------------------|
+//! | `POST /solve`   | a problem                                 | a report                     |
+//! | `POST /analyze` | a problem with `values` and `samples`     | worst-case and Monte Carlo   |
+//! | `GET /series`   |                                           | the names of the series      |
+//! | `GET /health`   |                                           | `{"status":"ok"}`            |
+//!
+//! Failures are reported with a 4xx status, or 500 for an internal error, and a body of
+//! `{"error": "..."}`.
+//!
+//! As the service is shared, each request is bounded: a problem with more than
+//! `MAX_COMBINATIONS` combinations is refused, a search stops after `TIME_LIMIT` with the results
+//! found so far, at most `MAX_RESULTS` results are kept and at most `MAX_SAMPLES` Monte Carlo
+//! samples are drawn.
+//!
+//! ```no_run
+//! resistor_calc::service::serve("0.0.0.0:8080", 4).unwrap();
+//! ```
+
+extern crate serde_json;
+extern crate tiny_http;
+
+use std::io::Read;
+use std::panic;
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use self::serde_json::json;
+use self::tiny_http::{Header, Response, Server};
+
+use {BoundRange, CalcOptions, Distribution, Error, Limits, MonteCarlo, Problem, RCalc, RSeries};
+
+/// The largest request body accepted, in bytes.
+const MAX_BODY: u64 = 1024 * 1024;
+
+/// The most combinations a problem may have, or a lower limit given by the problem itself.
+pub const MAX_COMBINATIONS: u64 = 100_000_000;
+
+/// The time after which a search stops, returning the results found so far.
+pub const TIME_LIMIT: Duration = Duration::from_secs(60);
+
+/// The most results kept by a search, or fewer if the problem gives a lower `top`.
+pub const MAX_RESULTS: usize = 1000;
+
+/// The most Monte Carlo samples an analysis may ask for.
+pub const MAX_SAMPLES: usize = 1_000_000;
+
+/// A request to `/analyze`: the series and bounds of a problem, with the values to analyse.
+#[derive(Debug, Deserialize)]
+struct AnalyzeRequest {
+    #[serde(flatten)]
+    problem: Problem,
+    /// The values of R1, R2, ..., Rn in ohms.
+    values: Vec<f64>,
+    /// The number of Monte Carlo samples.
+    #[serde(default = "default_samples")]
+    samples: usize,
+}
+
+fn default_samples() -> usize {
+    10_000
+}
+
+/// The response to `/analyze`.
+#[derive(Debug, Serialize)]
+struct Analysis {
+    values: Vec<f64>,
+    error: f64,
+    worst_case: Vec<BoundRange>,
+    monte_carlo: MonteCarlo,
+}
+
+/// Runs tolerance analysis on the values of `req`, each keeping the tolerances of its series. A
+/// single series applies to every value, and no series means E24.
+fn analyze(req: &AnalyzeRequest) -> Result<Analysis, Error> {
+    let mut problem = req.problem.clone();
+    match problem.series.len() {
+        0 => problem.series = vec!["E24".to_string(); req.values.len()],
+        1 => problem.series = vec![problem.series[0].clone(); req.values.len()],
+        n if n != req.values.len() => {
+            return Err(Error::Parse(
+                "The number of series must match the number of values".to_string(),
+            ))
+        }
+        _ => (),
+    }
+    if !(1..=MAX_SAMPLES).contains(&req.samples) {
+        return Err(Error::Parse(format!(
+            "The number of samples must be from 1 to {}",
+            MAX_SAMPLES
+        )));
+    }
+    let (series, ops) = problem.parts()?;
+    let fixed: Vec<RSeries> = req
+        .values
+        .iter()
+        .zip(series)
+        .map(|(&v, s)| {
+            RSeries::with_powers(s.name(), &[v], &[1.0])
+                .with_tolerance(s.tolerance())
+                .with_tempco(s.tempco())
+                .with_drift(s.drift())
+        })
+        .collect();
+    let op = ops.build();
+    let res = RCalc::new(fixed.iter().collect())
+        .try_calc(&op)
+        .map_err(|e| match e {
+            Error::Solver(_) => Error::Solver("The values do not satisfy the bounds".to_string()),
+            e => e,
+        })?;
+    Ok(Analysis {
+        values: req.values.clone(),
+        error: res.get(0).unwrap().0 as f64 / 1e9,
+        worst_case: res.worst_case(0, &op),
+        monte_carlo: res.monte_carlo(0, &op, req.samples, Distribution::Normal(3.0)),
+    })
+}
+
+/// Solves `problem` within the bounds of a shared service.
+fn solve(mut problem: Problem) -> Result<String, Error> {
+    problem.max_combinations = Some(
+        problem
+            .max_combinations
+            .map_or(MAX_COMBINATIONS, |max| max.min(MAX_COMBINATIONS)),
+    );
+    problem.top = Some(problem.top.map_or(MAX_RESULTS, |top| top.min(MAX_RESULTS)));
+    let limits = Limits {
+        time_limit: Some(TIME_LIMIT),
+        ..Limits::default()
+    };
+    problem
+        .solve_with(CalcOptions::new().limits(limits))
+        .map(|report| report.to_json())
+}
+
+fn error_json(msg: &str) -> String {
+    json!({ "error": msg }).to_string()
+}
+
+/// Handles a single request, returning the status code and JSON body of the response. This is
+/// what `serve` runs for each request, and can be used to mount the endpoints in another server.
+/// # Example
+/// ```
+///     # use resistor_calc::service;
+///     let (status, body) = service::handle(
+///         "POST",
+///         "/solve",
+///         r#"{"series": ["E24", "E24"], "bounds": ["R2 / (R1 + R2) ~ 0.25"], "top": 1}"#,
+///     );
+///     assert_eq!(status, 200);
+///     assert!(body.contains("\"results\""));
+///     assert_eq!(service::handle("GET", "/nowhere", "").0, 404);
+///     // A bound using a position that the problem lacks.
+///     let problem = r#"{"series": ["E24"], "bounds": ["R2 ~ 1"]}"#;
+///     let (status, _) = service::handle("POST", "/solve", problem);
+///     assert_eq!(status, 400);
+/// ```
+pub fn handle(method: &str, path: &str, body: &str) -> (u16, String) {
+    let path = path.split('?').next().unwrap_or("");
+    let result = match (method, path) {
+        ("GET", "/health") => Ok(json!({ "status": "ok" }).to_string()),
+        ("GET", "/series") => Ok(json!(RSeries::names()).to_string()),
+        ("POST", "/solve") => Problem::from_json(body).and_then(solve),
+        ("POST", "/analyze") => serde_json::from_str(body)
+            .map_err(|e| Error::Parse(e.to_string()))
+            .and_then(|req| analyze(&req))
+            .map(|analysis| serde_json::to_string(&analysis).unwrap()),
+        (_, "/health") | (_, "/series") | (_, "/solve") | (_, "/analyze") => {
+            return (405, error_json("Method not allowed"))
+        }
+        _ => return (404, error_json("Not found")),
+    };
+    match result {
+        Ok(body) => (200, body),
+        Err(e) => (400, error_json(e.message())),
+    }
+}
+
+/// Serves the endpoints on `addr`, such as `0.0.0.0:8080`, solving up to `workers` problems at
+/// once. Only returns if the server cannot be started.
+pub fn serve(addr: &str, workers: usize) -> Result<(), Error> {
+    let server = Arc::new(Server::http(addr).map_err(|e| Error::Io(e.to_string()))?);
+    info!("Serving on {} with {} workers", addr, workers);
+    let threads: Vec<_> = (0..workers.max(1))
+        .map(|_| {
+            let server = server.clone();
+            thread::spawn(move || {
+                for mut request in server.incoming_requests() {
+                    let mut body = String::new();
+                    let read = request
+                        .as_reader()
+                        .take(MAX_BODY + 1)
+                        .read_to_string(&mut body);
+                    let (status, out) = match read {
+                        Ok(n) if n as u64 > MAX_BODY => (413, error_json("Request too large")),
+                        Ok(_) => {
+                            let (method, url) = (request.method().as_str(), request.url());
+                            // A request that panics fails alone rather than taking its worker
+                            // down with it.
+                            panic::catch_unwind(|| handle(method, url, &body))
+                                .unwrap_or_else(|_| (500, error_json("Internal error")))
+                        }
+                        Err(e) => (400, error_json(&e.to_string())),
+                    };
+                    debug!("{} {} -> {}", request.method(), request.url(), status);
+                    let header = Header::from_bytes("Content-Type", "application/json").unwrap();
+                    let response = Response::from_string(out)
+                        .with_status_code(status)
+                        .with_header(header);
+                    if let Err(e) = request.respond(response) {
+                        warn!("Failed to respond: {}", e);
+                    }
+                }
+            })
+        })
+        .collect();
+    for thread in threads {
+        let _ = thread.join();
+    }
+    Ok(())
+}
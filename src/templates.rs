@@ -0,0 +1,1323 @@
+//! Ready made problems for common circuits.
+//!
+//! Each template sets up the resistors and bounds for a circuit from a few design parameters and
+//! solves it, returning a `Design` holding both the results and the bounds used, so that the
+//! achieved values of each quantity can be inspected with `ROp::explain` and the results passed
+//! on to the analyses on `RRes`.
+
+use std::f64::consts::PI;
+
+use itertools::Itertools;
+
+use {parallel, CalcOptions, Error, RCalc, ROp, ROpBuilder, RRes, RSeries, C_E6, E12, E24, E6, SHUNT};
+
+/// The solution to a template problem.
+pub struct Design {
+    /// The matching combinations of values, from lowest to highest error.
+    pub results: RRes,
+    /// The bounds describing the problem, for use with `ROp::explain` and the `RRes` analyses.
+    pub op: ROp,
+}
+
+fn solve(rcalc: &RCalc, ops: ROpBuilder) -> Option<Design> {
+    let op = ops.build();
+    rcalc
+        .calc(|rs| op.eval(rs))
+        .map(|results| Design { results, op })
+}
+
+/// As with `solve`, for templates built from expressions given by the user, returning an error
+/// rather than panicking if a bound cannot be evaluated, or `Error::Solver` if no combination
+/// satisfies the bounds.
+fn try_solve(rcalc: &RCalc, ops: ROpBuilder) -> Result<Design, Error> {
+    let op = ops.build();
+    rcalc.try_calc(&op).map(|results| Design { results, op })
+}
+
+/// Options for `divider`.
+pub struct DividerOptions<'a> {
+    /// The series both resistors are drawn from.
+    pub series: &'a RSeries,
+    /// The minimum current through the divider in amps.
+    pub min_current: Option<f64>,
+    /// The maximum current through the divider in amps.
+    pub max_current: Option<f64>,
+    /// The maximum source impedance seen at the output in ohms.
+    pub max_impedance: Option<f64>,
+}
+
+impl<'a> Default for DividerOptions<'a> {
+    fn default() -> Self {
+        DividerOptions {
+            series: &E24,
+            min_current: None,
+            max_current: None,
+            max_impedance: None,
+        }
+    }
+}
+
+/// Designs a voltage divider producing `vout` from `vin`, with R1 as the top resistor and R2 as
+/// the bottom.
+/// # Example
+/// ```
+///     # use resistor_calc::*;
+///     let design = templates::divider(
+///         5.0,
+///         3.3,
+///         templates::DividerOptions {
+///             max_current: Some(1e-3),
+///             max_impedance: Some(10e3),
+///             ..Default::default()
+///         },
+///     ).unwrap();
+///     design.results.print_best();
+/// ```
+pub fn divider(vin: f64, vout: f64, opts: DividerOptions) -> Option<Design> {
+    let mut ops = ROpBuilder::new()
+        .constant("VIN", vin)
+        .chain_voltage(vin)
+        .bound(&format!("VIN * R2 / (R1 + R2) ~ {}", vout));
+    if let Some(min) = opts.min_current {
+        ops = ops.bound(&format!("I_chain >= {}", min));
+    }
+    if let Some(max) = opts.max_current {
+        ops = ops.bound(&format!("I_chain <= {}", max));
+    }
+    if let Some(max) = opts.max_impedance {
+        ops = ops.bound(&format!("par(R1, R2) <= {}", max));
+    }
+    solve(&RCalc::new(vec![opts.series; 2]), ops)
+}
+
+/// Options for `regulator_feedback` and `adjustable_regulator`.
+pub struct FeedbackOptions<'a> {
+    /// The series the fixed resistors are drawn from.
+    pub series: &'a RSeries,
+    /// The series the potentiometer is drawn from, for `adjustable_regulator`.
+    pub pot_series: &'a RSeries,
+    /// The minimum current through the feedback network in amps, often specified as a minimum
+    /// load for the regulator.
+    pub min_current: Option<f64>,
+    /// The maximum current through the feedback network in amps.
+    pub max_current: Option<f64>,
+}
+
+impl<'a> Default for FeedbackOptions<'a> {
+    fn default() -> Self {
+        FeedbackOptions {
+            series: &E24,
+            pot_series: &E6,
+            min_current: None,
+            max_current: None,
+        }
+    }
+}
+
+/// Bounds the feedback current, which is set by the reference voltage across the `bottom`
+/// resistor whatever the output voltage.
+fn feedback_current(mut ops: ROpBuilder, opts: &FeedbackOptions, bottom: &str) -> ROpBuilder {
+    if let Some(min) = opts.min_current {
+        ops = ops.bound(&format!("VREF / {} >= {}", bottom, min));
+    }
+    if let Some(max) = opts.max_current {
+        ops = ops.bound(&format!("VREF / {} <= {}", bottom, max));
+    }
+    ops
+}
+
+/// Designs the feedback network of an adjustable regulator, such as an LM317 or a buck
+/// converter, with a reference voltage of `vref` producing `vout`. R1 is the resistor from the
+/// output to the feedback pin and R2 is from the feedback pin to ground.
+/// # Example
+/// ```
+///     # use resistor_calc::*;
+///     let design = templates::regulator_feedback(1.25, 5.0, Default::default()).unwrap();
+///     design.results.print_best();
+/// ```
+pub fn regulator_feedback(vref: f64, vout: f64, opts: FeedbackOptions) -> Option<Design> {
+    let ops = ROpBuilder::new()
+        .constant("VREF", vref)
+        .bound(&format!("VREF * (1 + R1/R2) ~ {}", vout));
+    let ops = feedback_current(ops, &opts, "R2");
+    solve(&RCalc::new(vec![opts.series; 2]), ops)
+}
+
+/// Designs the feedback network of an adjustable regulator whose output is set by a
+/// potentiometer between `vout_min` and `vout_max`. R1 is the resistor from the output to the
+/// potentiometer, R2 is the potentiometer and R3 is from the feedback pin to ground. This is
+/// the problem solved in the crate level example.
+/// # Example
+/// ```rust no_run
+///     # use resistor_calc::*;
+///     let design = templates::adjustable_regulator(
+///         0.8,
+///         6.0,
+///         12.0,
+///         templates::FeedbackOptions {
+///             max_current: Some(1e-3),
+///             ..Default::default()
+///         },
+///     ).unwrap();
+///     design.results.print_best();
+/// ```
+pub fn adjustable_regulator(
+    vref: f64,
+    vout_min: f64,
+    vout_max: f64,
+    opts: FeedbackOptions,
+) -> Option<Design> {
+    let ops = ROpBuilder::new()
+        .constant("VREF", vref)
+        .bound(&format!("VREF * (1 + R1/R3) ~ {}", vout_min))
+        .bound(&format!("VREF * (1 + (R1+R2)/R3) ~ {}", vout_max));
+    let ops = feedback_current(ops, &opts, "R3");
+    solve(
+        &RCalc::new(vec![opts.series, opts.pot_series, opts.series]),
+        ops,
+    )
+}
+
+/// Options for the amplifier gain templates.
+pub struct GainOptions<'a> {
+    /// The series the resistors are drawn from.
+    pub series: &'a RSeries,
+    /// The minimum total resistance of the feedback network in ohms, to limit the load on the
+    /// amplifier output.
+    pub min_impedance: Option<f64>,
+    /// The maximum total resistance of the feedback network in ohms, to limit noise and errors
+    /// from input bias current.
+    pub max_impedance: Option<f64>,
+    /// The maximum fractional error of the gain, e.g. `0.01` for 1%.
+    pub max_gain_error: Option<f64>,
+}
+
+impl<'a> Default for GainOptions<'a> {
+    fn default() -> Self {
+        GainOptions {
+            series: &E24,
+            min_impedance: None,
+            max_impedance: None,
+            max_gain_error: None,
+        }
+    }
+}
+
+fn gain_bounds(mut ops: ROpBuilder, opts: &GainOptions, gain: &str, target: f64) -> ROpBuilder {
+    ops = ops.bound(&format!("{} ~ {}", gain, target));
+    if let Some(min) = opts.min_impedance {
+        ops = ops.bound(&format!("R1 + R2 >= {}", min));
+    }
+    if let Some(max) = opts.max_impedance {
+        ops = ops.bound(&format!("R1 + R2 <= {}", max));
+    }
+    if let Some(err) = opts.max_gain_error {
+        ops = ops
+            .bound(&format!("{} >= {}", gain, target * (1.0 - err)))
+            .bound(&format!("{} <= {}", gain, target * (1.0 + err)));
+    }
+    ops
+}
+
+/// Designs the gain setting resistors of a non-inverting amplifier with a gain of
+/// `target_gain`, where R1 is the feedback resistor and R2 is the resistor to ground.
+/// # Example
+/// ```
+///     # use resistor_calc::*;
+///     let design = templates::noninverting_gain(
+///         11.0,
+///         templates::GainOptions {
+///             max_impedance: Some(100e3),
+///             ..Default::default()
+///         },
+///     ).unwrap();
+///     design.results.print_best();
+/// ```
+pub fn noninverting_gain(target_gain: f64, opts: GainOptions) -> Option<Design> {
+    let ops = gain_bounds(ROpBuilder::new(), &opts, "1 + R1/R2", target_gain);
+    solve(&RCalc::new(vec![opts.series; 2]), ops)
+}
+
+/// Options for `inverting_gain`.
+#[derive(Default)]
+pub struct InvertingOptions<'a> {
+    /// Options shared with the other gain templates.
+    pub gain: GainOptions<'a>,
+    /// The minimum input impedance of the amplifier in ohms, which is the input resistor R2.
+    pub min_input_impedance: Option<f64>,
+    /// Whether to also select a bias current compensation resistor R3 for the non-inverting
+    /// input, matching the parallel resistance of R1 and R2.
+    pub bias_compensation: bool,
+}
+
+/// Designs the gain setting resistors of an inverting amplifier with a gain of magnitude
+/// `target_gain`, where R1 is the feedback resistor, R2 is the input resistor and, if requested,
+/// R3 is the bias current compensation resistor.
+/// # Example
+/// ```
+///     # use resistor_calc::*;
+///     let design = templates::inverting_gain(
+///         4.7,
+///         templates::InvertingOptions {
+///             min_input_impedance: Some(10e3),
+///             ..Default::default()
+///         },
+///     ).unwrap();
+///     design.results.print_best();
+/// ```
+pub fn inverting_gain(target_gain: f64, opts: InvertingOptions) -> Option<Design> {
+    let mut ops = gain_bounds(ROpBuilder::new(), &opts.gain, "R1/R2", target_gain);
+    if let Some(min) = opts.min_input_impedance {
+        ops = ops.bound(&format!("R2 >= {}", min));
+    }
+    let count = if opts.bias_compensation {
+        ops = ops.bound("R3 / par(R1, R2) ~ 1");
+        3
+    } else {
+        2
+    };
+    solve(&RCalc::new(vec![opts.gain.series; count]), ops)
+}
+
+/// How a resistance is built from standard values.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Composite {
+    /// A single resistor, R1.
+    Single,
+    /// Two resistors in series, R1 + R2.
+    Series,
+    /// Two resistors in parallel, R1 || R2.
+    Parallel,
+}
+
+impl Composite {
+    /// The expression for the resistance, built from the positions from R{first} on.
+    fn expr(self, first: usize) -> String {
+        match self {
+            Composite::Single => format!("(R{})", first),
+            Composite::Series => format!("(R{} + R{})", first, first + 1),
+            Composite::Parallel => format!("par(R{}, R{})", first, first + 1),
+        }
+    }
+
+    fn value(self, values: &[f64]) -> f64 {
+        match self {
+            Composite::Single => values[0],
+            Composite::Series => values[0] + values[1],
+            Composite::Parallel => parallel(values[0], values[1]),
+        }
+    }
+
+    fn count(self) -> usize {
+        match self {
+            Composite::Single => 1,
+            Composite::Series | Composite::Parallel => 2,
+        }
+    }
+}
+
+/// Selects the gain resistor of an instrumentation amplifier. `gain` is the gain equation from
+/// the amplifier's datasheet written in terms of `RG`, which is built from standard values of
+/// `series` as described by `composite`. An equation that cannot be parsed or evaluated is
+/// returned as an error, as is a gain that no combination reaches.
+/// # Example
+/// ```
+///     # use resistor_calc::*;
+///     // An INA128 with a target gain of 100.
+///     let design = templates::ina_gain("1 + 50e3 / RG", 100.0, &E24, templates::Composite::Series)
+///         .unwrap();
+///     let (_, rs) = design.results.get(0).unwrap();
+///     println!("Gain: {}", design.op.explain(rs)[0].value);
+///     let bad = templates::ina_gain("1 + 50e3 / RX", 100.0, &E24, templates::Composite::Single);
+///     assert!(matches!(bad, Err(Error::Eval(_))));
+/// ```
+pub fn ina_gain(
+    gain: &str,
+    target_gain: f64,
+    series: &RSeries,
+    composite: Composite,
+) -> Result<Design, Error> {
+    let ops = ROpBuilder::new().try_bound(&format!(
+        "{} ~ {}",
+        gain.replace("RG", &composite.expr(1)),
+        target_gain
+    ))?;
+    try_solve(&RCalc::new(vec![series; composite.count()]), ops)
+}
+
+/// Designs a three resistor hysteresis network for a comparator whose output swings between
+/// ground and `supply`. R1 runs from the reference voltage `vref` to the threshold node, R2 from
+/// the node to ground and R3 from the comparator output back to the node. The node sits at
+/// `upper` while the output is high and `lower` while it is low. When the reference is the
+/// supply itself pass the supply voltage as `vref`. The achieved thresholds are the values of
+/// the first and second bounds.
+/// # Example
+/// ```rust no_run
+///     # use resistor_calc::*;
+///     let design = templates::hysteresis(5.0, 2.5, 2.6, 2.4, &E24).unwrap();
+///     let (_, rs) = design.results.get(0).unwrap();
+///     let thresholds = design.op.explain(rs);
+///     println!("Upper: {}, Lower: {}", thresholds[0].value, thresholds[1].value);
+/// ```
+pub fn hysteresis(
+    supply: f64,
+    vref: f64,
+    upper: f64,
+    lower: f64,
+    series: &RSeries,
+) -> Option<Design> {
+    let g = "(1/R1 + 1/R2 + 1/R3)";
+    let ops = ROpBuilder::new()
+        .constant("VCC", supply)
+        .constant("VREF", vref)
+        .bound(&format!("(VREF/R1 + VCC/R3) / {} ~ {}", g, upper))
+        .bound(&format!("(VREF/R1) / {} ~ {}", g, lower));
+    solve(&RCalc::new(vec![series; 3]), ops)
+}
+
+/// Options for `shunt`.
+pub struct ShuntOptions<'a> {
+    /// The series the shunt is drawn from.
+    pub series: &'a RSeries,
+    /// The gains of the current sense amplifiers available.
+    pub gains: Vec<f64>,
+    /// The maximum power dissipated in the shunt at full scale in watts.
+    pub max_power: Option<f64>,
+    /// The maximum fractional error of the full scale output, e.g. `0.02` for 2%.
+    pub accuracy: Option<f64>,
+}
+
+impl<'a> Default for ShuntOptions<'a> {
+    fn default() -> Self {
+        ShuntOptions {
+            series: &SHUNT,
+            gains: vec![20.0, 50.0, 100.0, 200.0],
+            max_power: None,
+            accuracy: None,
+        }
+    }
+}
+
+/// Selects a current sense shunt R1 so that `max_current` produces `full_scale` volts at the
+/// output of a current sense amplifier. Each of the amplifier gains in the options is tried in
+/// turn, and a design is returned for each gain that has a solution, paired with the gain and
+/// ordered from the lowest to highest best error.
+/// # Example
+/// ```
+///     # use resistor_calc::*;
+///     let designs = templates::shunt(
+///         5.0,
+///         3.3,
+///         templates::ShuntOptions {
+///             max_power: Some(0.5),
+///             ..Default::default()
+///         },
+///     );
+///     let (gain, design) = &designs[0];
+///     println!("Gain {}:", gain);
+///     design.results.print_best();
+/// ```
+pub fn shunt(max_current: f64, full_scale: f64, opts: ShuntOptions) -> Vec<(f64, Design)> {
+    let rcalc = RCalc::new(vec![opts.series]);
+    let mut designs: Vec<(f64, Design)> = opts
+        .gains
+        .iter()
+        .filter_map(|&gain| {
+            let out = format!("{} * R1 * {}", max_current, gain);
+            let mut ops = ROpBuilder::new().bound(&format!("{} ~ {}", out, full_scale));
+            if let Some(max) = opts.max_power {
+                ops = ops.bound(&format!("{} * R1 <= {}", max_current * max_current, max));
+            }
+            if let Some(acc) = opts.accuracy {
+                ops = ops
+                    .bound(&format!("{} >= {}", out, full_scale * (1.0 - acc)))
+                    .bound(&format!("{} <= {}", out, full_scale * (1.0 + acc)));
+            }
+            solve(&rcalc, ops).map(|d| (gain, d))
+        })
+        .collect();
+    designs.sort_by_key(|(_, d)| d.results.get(0).unwrap().0);
+    designs
+}
+
+/// Selects a series resistor R1 for an LED. The forward voltage of the LED lies within
+/// `vf_range`, the supply within `supply_range`, and the current must stay within
+/// `current_range` at every combination of these and the tolerance of the resistor. Results are
+/// ranked by how close the nominal current, with the midpoints of each range, is to the middle
+/// of the current window.
+/// # Example
+/// ```
+///     # use resistor_calc::*;
+///     let design = templates::led((1.8, 2.2), (4.5, 5.5), (8e-3, 15e-3), &E24).unwrap();
+///     design.results.print_best();
+/// ```
+pub fn led(
+    vf_range: (f64, f64),
+    supply_range: (f64, f64),
+    current_range: (f64, f64),
+    series: &RSeries,
+) -> Option<Design> {
+    let mid = |r: (f64, f64)| (r.0 + r.1) / 2.0;
+    let tol = series.tolerance();
+    let ops = ROpBuilder::new()
+        .bound(&format!(
+            "{} / R1 ~ {}",
+            mid(supply_range) - mid(vf_range),
+            mid(current_range)
+        ))
+        .bound(&format!(
+            "{} / (R1 * {}) >= {}",
+            supply_range.0 - vf_range.1,
+            1.0 + tol,
+            current_range.0
+        ))
+        .bound(&format!(
+            "{} / (R1 * {}) <= {}",
+            supply_range.1 - vf_range.0,
+            1.0 - tol,
+            current_range.1
+        ));
+    solve(&RCalc::new(vec![series]), ops)
+}
+
+/// I2C bus speed modes, which set the maximum rise time and the sink current of the bus.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum I2cMode {
+    /// Standard mode, 100 kHz.
+    Standard,
+    /// Fast mode, 400 kHz.
+    Fast,
+    /// Fast mode plus, 1 MHz.
+    FastPlus,
+}
+
+impl I2cMode {
+    /// The maximum rise time in seconds and the minimum sink current at 0.4 V in amps.
+    fn limits(self) -> (f64, f64) {
+        match self {
+            I2cMode::Standard => (1000e-9, 3e-3),
+            I2cMode::Fast => (300e-9, 3e-3),
+            I2cMode::FastPlus => (120e-9, 20e-3),
+        }
+    }
+}
+
+/// Selects the bus pull-up resistor R1 for an I2C bus on a `vdd` supply with a total bus
+/// capacitance of `capacitance` farads, following the limits of the I2C specification for
+/// `mode`. The smallest pull-up is set by the sink current of the devices and the largest by
+/// the rise time. Both are checked at the extremes of the tolerance of `series`, and results
+/// are ranked by how close they are to the geometric middle of the allowed range, giving the
+/// most margin. The margins are the values of the second and third bounds.
+/// # Example
+/// ```
+///     # use resistor_calc::*;
+///     let design = templates::i2c_pullup(3.3, 200e-12, templates::I2cMode::Fast, &E24).unwrap();
+///     design.results.print_best();
+/// ```
+pub fn i2c_pullup(vdd: f64, capacitance: f64, mode: I2cMode, series: &RSeries) -> Option<Design> {
+    let (rise, sink) = mode.limits();
+    let r_min = (vdd - 0.4) / sink;
+    let r_max = rise / (0.8473 * capacitance);
+    let tol = series.tolerance();
+    let ops = ROpBuilder::new()
+        .bound(&format!("R1 / {} ~ 1", (r_min * r_max).sqrt()))
+        .bound(&format!("R1 * {} >= {}", 1.0 - tol, r_min))
+        .bound(&format!("R1 * {} <= {}", 1.0 + tol, r_max));
+    solve(&RCalc::new(vec![series]), ops)
+}
+
+/// Options for `rc_filter`.
+pub struct RcOptions<'a> {
+    /// The series the resistor is drawn from.
+    pub r_series: &'a RSeries,
+    /// The series the capacitor is drawn from.
+    pub c_series: &'a RSeries,
+    /// The minimum resistance in ohms, so that the filter does not overly load its source.
+    pub min_r: Option<f64>,
+    /// The maximum resistance in ohms, so that the load does not disturb the filter.
+    pub max_r: Option<f64>,
+}
+
+impl<'a> Default for RcOptions<'a> {
+    fn default() -> Self {
+        RcOptions {
+            r_series: &E24,
+            c_series: &C_E6,
+            min_r: None,
+            max_r: None,
+        }
+    }
+}
+
+/// Designs a first order RC filter with a cutoff of `cutoff` Hz, suitable for either a low-pass
+/// or a high-pass filter. R1 is the resistor and R2 is the capacitor in farads. Results are
+/// ranked by the fractional error of the cutoff frequency.
+/// # Example
+/// ```
+///     # use resistor_calc::*;
+///     let design = templates::rc_filter(
+///         1e3,
+///         templates::RcOptions {
+///             min_r: Some(1e3),
+///             max_r: Some(100e3),
+///             ..Default::default()
+///         },
+///     ).unwrap();
+///     let (_, rs) = design.results.get(0).unwrap();
+///     println!("R = {}, C = {}", rs.r(1), rs.r(2));
+/// ```
+pub fn rc_filter(cutoff: f64, opts: RcOptions) -> Option<Design> {
+    let mut ops =
+        ROpBuilder::new().bound(&format!("1 / ({} * R1 * R2) / {} ~ 1", 2.0 * PI, cutoff));
+    if let Some(min) = opts.min_r {
+        ops = ops.bound(&format!("R1 >= {}", min));
+    }
+    if let Some(max) = opts.max_r {
+        ops = ops.bound(&format!("R1 <= {}", max));
+    }
+    solve(&RCalc::new(vec![opts.r_series, opts.c_series]), ops)
+}
+
+/// The configuration of a 555 timer for `timer555`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Timer555 {
+    /// Free running, with a target frequency in Hz and duty cycle between 0.5 and 1. R1 is Ra,
+    /// R2 is Rb and R3 is the timing capacitor in farads.
+    Astable { frequency: f64, duty: f64 },
+    /// One shot, with a target pulse width in seconds. R1 is the timing resistor and R2 is the
+    /// timing capacitor in farads.
+    Monostable { width: f64 },
+}
+
+/// Selects the timing components of a 555 timer, with resistors drawn from `r_series` and the
+/// capacitor from `c_series`. For astable designs the errors of both the frequency and the duty
+/// cycle are included.
+/// # Example
+/// ```no_run
+///     # use resistor_calc::*;
+///     let design = templates::timer555(
+///         templates::Timer555::Astable {
+///             frequency: 1e3,
+///             duty: 0.6,
+///         },
+///         &E24,
+///         &C_E6,
+///     ).unwrap();
+///     design.results.print_best();
+/// ```
+pub fn timer555(mode: Timer555, r_series: &RSeries, c_series: &RSeries) -> Option<Design> {
+    match mode {
+        Timer555::Astable { frequency, duty } => {
+            let ops = ROpBuilder::new()
+                .bound(&format!("1.44 / ((R1 + 2 * R2) * R3) / {} ~ 1", frequency))
+                .bound(&format!("(R1 + R2) / (R1 + 2 * R2) / {} ~ 1", duty));
+            solve(&RCalc::new(vec![r_series, r_series, c_series]), ops)
+        }
+        Timer555::Monostable { width } => {
+            let ops = ROpBuilder::new().bound(&format!("1.1 * R1 * R2 / {} ~ 1", width));
+            solve(&RCalc::new(vec![r_series, c_series]), ops)
+        }
+    }
+}
+
+/// The topology of an attenuator pad for `attenuator`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Pad {
+    /// R1 from input to ground, R2 from input to output and R1 from output to ground.
+    Pi,
+    /// R1 from input to the centre node, R2 from the centre node to ground and R1 from the
+    /// centre node to output.
+    T,
+}
+
+/// Designs a symmetric attenuator pad with an attenuation of `db` decibels between matched
+/// impedances of `z0` ohms. The attenuation and input impedance when terminated in `z0` are
+/// both ranked by their fractional error, and the return loss at the input in dB must be at
+/// least `min_return_loss`.
+/// # Example
+/// ```
+///     # use resistor_calc::*;
+///     let design = templates::attenuator(templates::Pad::Pi, 6.0, 50.0, 20.0, &E24).unwrap();
+///     for outcome in design.op.explain(&design.results.get(0).unwrap().1) {
+///         println!("{}: {}", outcome.bound, outcome.value);
+///     }
+/// ```
+pub fn attenuator(
+    pad: Pad,
+    db: f64,
+    z0: f64,
+    min_return_loss: f64,
+    series: &RSeries,
+) -> Option<Design> {
+    let (zin, gain) = match pad {
+        Pad::Pi => (
+            format!("par(R1, R2 + par(R1, {z}))", z = z0),
+            format!("par(R1, {z}) / (R2 + par(R1, {z}))", z = z0),
+        ),
+        Pad::T => (
+            format!("(R1 + par(R2, R1 + {z}))", z = z0),
+            format!(
+                "par(R2, R1 + {z}) / (R1 + par(R2, R1 + {z})) * {z} / (R1 + {z})",
+                z = z0
+            ),
+        ),
+    };
+    let db_per_ln = 20.0 / 10f64.ln();
+    let ops = ROpBuilder::new()
+        .bound(&format!("-{} * ln({}) / {} ~ 1", db_per_ln, gain, db))
+        .bound(&format!("{} / {} ~ 1", zin, z0))
+        .bound(&format!(
+            "-{} * ln(abs({zin} - {z}) / ({zin} + {z})) >= {}",
+            db_per_ln,
+            min_return_loss,
+            zin = zin,
+            z = z0
+        ));
+    solve(&RCalc::new(vec![series, series]), ops)
+}
+
+/// Completes a Wheatstone bridge around a sensor with a nominal resistance of `sensor` ohms,
+/// excited by `excitation` volts. R1 over R2 forms one half of the bridge and R3 over the sensor
+/// forms the other, with the output taken between the two midpoints. Results are ranked by the
+/// offset voltage at the sensor's nominal value, and the sensitivity of the output to the sensor
+/// in V/Ω must be at least `min_sensitivity`.
+/// # Example
+/// ```no_run
+///     # use resistor_calc::*;
+///     let design = templates::bridge(350.0, 5.0, 3e-3, &E24).unwrap();
+///     for outcome in design.op.explain(&design.results.get(0).unwrap().1) {
+///         println!("{}: {}", outcome.bound, outcome.value);
+///     }
+/// ```
+pub fn bridge(
+    sensor: f64,
+    excitation: f64,
+    min_sensitivity: f64,
+    series: &RSeries,
+) -> Option<Design> {
+    let ops = ROpBuilder::new()
+        .bound(&format!(
+            "{v} * ({s} / (R3 + {s}) - R2 / (R1 + R2)) ~ 0",
+            v = excitation,
+            s = sensor
+        ))
+        .bound(&format!(
+            "{v} * R3 / ((R3 + {s}) * (R3 + {s})) >= {}",
+            min_sensitivity,
+            v = excitation,
+            s = sensor
+        ));
+    solve(&RCalc::new(vec![series, series, series]), ops)
+}
+
+/// The resistance-temperature characteristic of an NTC thermistor, for `ntc_linearise`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Ntc {
+    /// Defined by its resistance at 25°C in ohms and its Beta constant in kelvin.
+    Beta { r25: f64, beta: f64 },
+    /// Defined by a table of (temperature in °C, resistance in ohms) points from the
+    /// manufacturer's datasheet, sorted by temperature. Between points the resistance is
+    /// interpolated exponentially.
+    Table(Vec<(f64, f64)>),
+}
+
+impl Ntc {
+    /// The resistance of the thermistor in ohms at `temp` °C, or `None` if its table is empty
+    /// or its temperatures do not increase.
+    /// # Example
+    /// ```
+    ///     # use resistor_calc::*;
+    ///     let ntc = templates::Ntc::Beta { r25: 10e3, beta: 3950.0 };
+    ///     assert!((ntc.resistance(25.0).unwrap() - 10e3).abs() < 1e-9);
+    ///     let ntc = templates::Ntc::Table(vec![(25.0, 10e3), (0.0, 32e3)]);
+    ///     assert_eq!(ntc.resistance(10.0), None);
+    /// ```
+    pub fn resistance(&self, temp: f64) -> Option<f64> {
+        match *self {
+            Ntc::Beta { r25, beta } => {
+                Some(r25 * (beta * (1.0 / (temp + 273.15) - 1.0 / 298.15)).exp())
+            }
+            Ntc::Table(ref table) => {
+                if !table.windows(2).all(|pair| pair[0].0 < pair[1].0) {
+                    return None;
+                }
+                let (&(first, r_first), &(last, r_last)) = (table.first()?, table.last()?);
+                if temp <= first {
+                    return Some(r_first);
+                }
+                if temp >= last {
+                    return Some(r_last);
+                }
+                let i = table.iter().position(|&(t, _)| t > temp)?;
+                let ((t0, r0), (t1, r1)) = (table[i - 1], table[i]);
+                let frac = (temp - t0) / (t1 - t0);
+                Some((r0.ln() + frac * (r1.ln() - r0.ln())).exp())
+            }
+        }
+    }
+}
+
+/// Linearises an NTC thermistor placed at the bottom of a divider from `vref`, with R1 as the
+/// top resistor and R2 in parallel with the thermistor. The output is fitted to a straight line
+/// running from `volts.0` at `temps.0` °C to `volts.1` at `temps.1` °C, sampled at `points`
+/// evenly spaced temperatures, and results are ranked by the sum of the errors at each point.
+/// Returns `None` if the resistance of `ntc` is unknown, as for an empty or unsorted table, or if
+/// no combination satisfies the bounds.
+/// # Example
+/// ```
+///     # use resistor_calc::*;
+///     let ntc = templates::Ntc::Beta { r25: 10e3, beta: 3950.0 };
+///     let design = templates::ntc_linearise(&ntc, (0.0, 50.0), (2.5, 1.0), 3.3, 6, &E24).unwrap();
+///     design.results.print_best();
+/// ```
+pub fn ntc_linearise(
+    ntc: &Ntc,
+    temps: (f64, f64),
+    volts: (f64, f64),
+    vref: f64,
+    points: usize,
+    series: &RSeries,
+) -> Option<Design> {
+    let ops = (0..points).try_fold(ROpBuilder::new(), |ops, i| {
+        let frac = i as f64 / (points - 1).max(1) as f64;
+        let rt = ntc.resistance(temps.0 + frac * (temps.1 - temps.0))?;
+        Some(ops.bound(&format!(
+            "{} * par(R2, {rt}) / (R1 + par(R2, {rt})) ~ {}",
+            vref,
+            volts.0 + frac * (volts.1 - volts.0),
+            rt = rt
+        )))
+    })?;
+    solve(&RCalc::new(vec![series, series]), ops)
+}
+
+/// The worst-case error of the bit weights of a voltage-mode R-2R ladder DAC of `bits` bits, in
+/// LSBs, when built with `r` for the series resistors and `two_r` for the bit arms and the
+/// termination.
+/// # Example
+/// ```
+///     # use resistor_calc::*;
+///     assert!(templates::r2r_error(10e3, 20e3, 8) < 1e-9);
+///     assert!(templates::r2r_error(10e3, 20.5e3, 8) > 0.1);
+///     // Ladders of more than 64 bits do not overflow.
+///     assert!(templates::r2r_error(10e3, 20e3, 70).is_finite());
+/// ```
+pub fn r2r_error(r: f64, two_r: f64, bits: usize) -> f64 {
+    // Walk the ladder from the LSB, keeping the weight of each bit in the Thevenin equivalent
+    // seen at the current node.
+    let mut weights = vec![two_r / (two_r + two_r)];
+    let mut rth = parallel(two_r, two_r);
+    for _ in 1..bits {
+        let left = rth + r;
+        let total = 1.0 / left + 1.0 / two_r;
+        for w in &mut weights {
+            *w = *w / left / total;
+        }
+        weights.push(1.0 / two_r / total);
+        rth = parallel(left, two_r);
+    }
+    let lsbs = 2f64.powi(bits as i32);
+    weights
+        .iter()
+        .enumerate()
+        .map(|(i, w)| (w * lsbs - 2f64.powi(i as i32)).abs())
+        .fold(0.0, f64::max)
+}
+
+/// The number of results kept by `r2r_ladder`.
+pub const R2R_TOP: usize = 100;
+
+/// Selects the resistors of a voltage-mode R-2R ladder DAC of `bits` bits, ranked by the
+/// worst-case bit weight error in LSBs given by `r2r_error`. R1 is the series resistor R, and
+/// the 2R arms are made from the following resistors as given by `composite`, so that for
+/// example `Composite::Series` allows 2R to be built from two resistors of value R. The `op` of
+/// the design bounds the ratio of 2R to R at 2, for use with `ROp::explain`, but the results are
+/// ranked by `r2r_error`. Every combination has an error, so only the best `R2R_TOP` are kept.
+/// # Example
+/// ```
+///     # use resistor_calc::*;
+///     let design = templates::r2r_ladder(10, &E24, templates::Composite::Series).unwrap();
+///     assert_eq!(design.results.len(), templates::R2R_TOP);
+///     design.results.print_best();
+/// ```
+pub fn r2r_ladder(bits: usize, series: &RSeries, composite: Composite) -> Option<Design> {
+    let op = ROpBuilder::new()
+        .bound(&format!("{} / R1 ~ 2", composite.expr(2)))
+        .build();
+    RCalc::new(vec![series; composite.count() + 1])
+        .calc_with(
+            |rs| Some(r2r_error(rs.0[0], composite.value(&rs.0[1..]), bits)),
+            CalcOptions::new().top(R2R_TOP),
+        )
+        .ok()
+        .map(|results| Design { results, op })
+}
+
+/// A single valued series for a feedback resistor fixed to `feedback` ohms, if it is fixed.
+fn fixed_feedback(feedback: Option<f64>, series: &RSeries) -> Option<RSeries> {
+    feedback.map(|rf| {
+        RSeries::with_powers("Feedback", &[rf], &[1.0]).with_tolerance(series.tolerance())
+    })
+}
+
+/// Selects the resistors of an inverting summing amplifier so that input `k` is amplified by
+/// `weights[k]`. R1 is the feedback resistor and R2, R3, ... are the input resistors of each
+/// channel. As the search grows quickly with the number of channels the feedback resistor can be
+/// fixed to `feedback` ohms. There is one bound per channel, so the gain error of each channel
+/// can be seen with `ROp::explain`.
+/// # Example
+/// ```
+///     # use resistor_calc::*;
+///     let design = templates::summing_amplifier(&[1.0, 0.5], Some(10e3), &E24).unwrap();
+///     for outcome in design.op.explain(&design.results.get(0).unwrap().1) {
+///         println!("{}: {}", outcome.bound, outcome.deviation);
+///     }
+/// ```
+pub fn summing_amplifier(
+    weights: &[f64],
+    feedback: Option<f64>,
+    series: &RSeries,
+) -> Option<Design> {
+    let fixed = fixed_feedback(feedback, series);
+    let mut rs = vec![fixed.as_ref().unwrap_or(series)];
+    rs.extend(weights.iter().map(|_| series));
+    let ops = weights
+        .iter()
+        .enumerate()
+        .fold(ROpBuilder::new(), |ops, (k, w)| {
+            ops.bound(&format!("R1 / R{} / {} ~ 1", k + 2, w))
+        });
+    solve(&RCalc::new(rs), ops)
+}
+
+/// Options for `battery_sense`.
+pub struct BatteryOptions<'a> {
+    /// The series both resistors are drawn from.
+    pub series: &'a RSeries,
+    /// The maximum current drawn from the battery in amps.
+    pub max_drain: Option<f64>,
+    /// The input leakage current of the ADC pin in amps.
+    pub leakage: f64,
+    /// The input impedance of the ADC in ohms, if it loads the divider.
+    pub input_impedance: Option<f64>,
+    /// The maximum error at the ADC input in volts due to leakage and input impedance.
+    pub max_error: Option<f64>,
+}
+
+impl<'a> Default for BatteryOptions<'a> {
+    fn default() -> Self {
+        BatteryOptions {
+            series: &E24,
+            max_drain: None,
+            leakage: 0.0,
+            input_impedance: None,
+            max_error: None,
+        }
+    }
+}
+
+/// Designs a divider that scales a battery voltage of at most `vbat_max` into an ADC with a full
+/// scale of `full_scale` volts, with R1 from the battery to the ADC input and R2 from the input
+/// to ground. Results are ranked by how closely `vbat_max` maps to full scale without exceeding
+/// it. The error at the input is the leakage current through the divider's source impedance plus
+/// the shift caused by the ADC's input impedance appearing in parallel with R2.
+/// # Example
+/// ```
+///     # use resistor_calc::*;
+///     let design = templates::battery_sense(
+///         4.2,
+///         3.3,
+///         templates::BatteryOptions {
+///             max_drain: Some(10e-6),
+///             leakage: 100e-9,
+///             max_error: Some(10e-3),
+///             ..Default::default()
+///         },
+///     ).unwrap();
+///     design.results.print_best();
+/// ```
+pub fn battery_sense(vbat_max: f64, full_scale: f64, opts: BatteryOptions) -> Option<Design> {
+    let mut ops = ROpBuilder::new()
+        .constant("VBAT", vbat_max)
+        .chain_voltage(vbat_max)
+        .bound(&format!("VBAT * R2 / (R1 + R2) ~ {}", full_scale))
+        .bound(&format!("VBAT * R2 / (R1 + R2) <= {}", full_scale));
+    if let Some(max) = opts.max_drain {
+        ops = ops.bound(&format!("I_chain <= {}", max));
+    }
+    if let Some(max) = opts.max_error {
+        let loading = match opts.input_impedance {
+            Some(rin) => format!(
+                " + VBAT * (R2 / (R1 + R2) - par(R2, {rin}) / (R1 + par(R2, {rin})))",
+                rin = rin
+            ),
+            None => String::new(),
+        };
+        ops = ops.bound(&format!(
+            "{} * par(R1, R2){} <= {}",
+            opts.leakage, loading, max
+        ));
+    }
+    solve(&RCalc::new(vec![opts.series; 2]), ops)
+}
+
+/// Scales a signal spanning `input` volts onto an ADC range of `range` volts, with R1 from the
+/// signal to the ADC input and R2 from the input to ground. If the range needs an offset,
+/// `offset_ref` gives a reference voltage that R3 pulls the input towards. The source impedance
+/// seen by the ADC must not exceed `max_impedance` ohms, and results are ranked by the error at
+/// both ends of the range.
+/// # Example
+/// ```
+///     # use resistor_calc::*;
+///     let design = templates::adc_scaling((0.0, 10.0), (0.0, 3.0), 5e3, None, &E24).unwrap();
+///     design.results.print_best();
+/// ```
+pub fn adc_scaling(
+    input: (f64, f64),
+    range: (f64, f64),
+    max_impedance: f64,
+    offset_ref: Option<f64>,
+    series: &RSeries,
+) -> Option<Design> {
+    let (node, impedance, count) = match offset_ref {
+        Some(vref) => (
+            format!("(VIN / R1 + {} / R3) / (1 / R1 + 1 / R2 + 1 / R3)", vref),
+            "par(par(R1, R2), R3)",
+            3,
+        ),
+        None => ("VIN * R2 / (R1 + R2)".to_string(), "par(R1, R2)", 2),
+    };
+    let ops = ROpBuilder::new()
+        .bound(&format!(
+            "{} ~ {}",
+            node.replace("VIN", &input.0.to_string()),
+            range.0
+        ))
+        .bound(&format!(
+            "{} ~ {}",
+            node.replace("VIN", &input.1.to_string()),
+            range.1
+        ))
+        .bound(&format!("{} <= {}", impedance, max_impedance));
+    solve(&RCalc::new(vec![series; count]), ops)
+}
+
+/// Designs a resistor string from `supply` to ground with a tap at each of the voltages in
+/// `taps`, given from highest to lowest. R1 runs from the supply to the first tap and the last
+/// resistor from the last tap to ground. All taps are optimised together, and results are ranked
+/// by the error of the worst tap in volts rather than the total error.
+/// # Example
+/// ```
+///     # use resistor_calc::*;
+///     let design = templates::multi_tap(5.0, &[3.75, 1.25], &E6).unwrap();
+///     for outcome in design.op.explain(&design.results.get(0).unwrap().1) {
+///         println!("{}: {}", outcome.bound, outcome.value);
+///     }
+/// ```
+pub fn multi_tap(supply: f64, taps: &[f64], series: &RSeries) -> Option<Design> {
+    let count = taps.len() + 1;
+    let sum = |from: usize| (from..=count).map(|i| format!("R{}", i)).join(" + ");
+    let op = taps
+        .iter()
+        .enumerate()
+        .fold(ROpBuilder::new(), |ops, (k, tap)| {
+            ops.bound(&format!(
+                "{} * ({}) / ({}) ~ {}",
+                supply,
+                sum(k + 2),
+                sum(1),
+                tap
+            ))
+        })
+        .build();
+    RCalc::new(vec![series; count])
+        .calc(|rs| {
+            Some(
+                op.explain(rs)
+                    .iter()
+                    .map(|outcome| outcome.deviation)
+                    .fold(0.0, f64::max),
+            )
+        })
+        .map(|results| Design { results, op })
+}
+
+/// Selects the resistors of a switched-gain non-inverting amplifier, with a single feedback
+/// resistor R1 and a gain resistor to ground for each setting in `gains`, R2, R3, ..., of which
+/// one is switched in at a time. All settings are optimised together, ranked by the sum of their
+/// fractional gain errors. As with `summing_amplifier` the feedback resistor can be fixed to
+/// `feedback` ohms to keep the search small.
+/// # Example
+/// ```
+///     # use resistor_calc::*;
+///     let design = templates::pga(&[2.0, 5.0], Some(10e3), &E24).unwrap();
+///     for outcome in design.op.explain(&design.results.get(0).unwrap().1) {
+///         println!("{}: {}", outcome.bound, outcome.deviation);
+///     }
+/// ```
+pub fn pga(gains: &[f64], feedback: Option<f64>, series: &RSeries) -> Option<Design> {
+    let fixed = fixed_feedback(feedback, series);
+    let mut rs = vec![fixed.as_ref().unwrap_or(series)];
+    rs.extend(gains.iter().map(|_| series));
+    let ops = gains
+        .iter()
+        .enumerate()
+        .fold(ROpBuilder::new(), |ops, (k, gain)| {
+            ops.bound(&format!("(1 + R1 / R{}) / {} ~ 1", k + 2, gain))
+        });
+    solve(&RCalc::new(rs), ops)
+}
+
+/// Options for `strap`.
+pub struct StrapOptions<'a> {
+    /// The series both resistors are drawn from.
+    pub series: &'a RSeries,
+    /// The range of the supply the strap is pulled up to in volts.
+    pub supply: (f64, f64),
+    /// The resistance of a pull-up inside the IC in ohms, if it has one.
+    pub internal_pull_up: Option<f64>,
+    /// The resistance of a pull-down inside the IC in ohms, if it has one.
+    pub internal_pull_down: Option<f64>,
+}
+
+impl<'a> Default for StrapOptions<'a> {
+    fn default() -> Self {
+        StrapOptions {
+            series: &E24,
+            supply: (3.3, 3.3),
+            internal_pull_up: None,
+            internal_pull_down: None,
+        }
+    }
+}
+
+/// Designs a strap divider for an IC configuration pin that must be read in the window of
+/// `window` volts at power-up, with R1 pulling up to the supply and R2 pulling down to ground,
+/// alongside any pulls inside the IC. The window must hold at both ends of the supply range with
+/// R1 and R2 at opposing ends of their tolerance, and results are ranked by how close the nominal
+/// voltage is to the centre of the window.
+/// # Example
+/// ```
+///     # use resistor_calc::*;
+///     let design = templates::strap(
+///         (1.2, 1.6),
+///         templates::StrapOptions {
+///             supply: (3.135, 3.465),
+///             internal_pull_down: Some(50e3),
+///             ..Default::default()
+///         },
+///     ).unwrap();
+///     design.results.print_best();
+/// ```
+pub fn strap(window: (f64, f64), opts: StrapOptions) -> Option<Design> {
+    let tol = opts.series.tolerance();
+    let arm = |r: &str, scale: f64, internal: Option<f64>| match internal {
+        Some(ri) => format!("par({} * {}, {})", r, scale, ri),
+        None => format!("({} * {})", r, scale),
+    };
+    let pin = |supply: f64, up: f64, down: f64| {
+        let top = arm("R1", up, opts.internal_pull_up);
+        let bottom = arm("R2", down, opts.internal_pull_down);
+        format!("{} * {b} / ({} + {b})", supply, top, b = bottom)
+    };
+    let ops = ROpBuilder::new()
+        .bound(&format!(
+            "{} ~ {}",
+            pin((opts.supply.0 + opts.supply.1) / 2.0, 1.0, 1.0),
+            (window.0 + window.1) / 2.0
+        ))
+        .bound(&format!(
+            "{} >= {}",
+            pin(opts.supply.0, 1.0 + tol, 1.0 - tol),
+            window.0
+        ))
+        .bound(&format!(
+            "{} <= {}",
+            pin(opts.supply.1, 1.0 - tol, 1.0 + tol),
+            window.1
+        ));
+    solve(&RCalc::new(vec![opts.series; 2]), ops)
+}
+
+/// Options for `level_shift`.
+pub struct LevelShiftOptions<'a> {
+    /// The series both resistors are drawn from.
+    pub series: &'a RSeries,
+    /// The maximum source impedance seen by the receiver in ohms, which with its input
+    /// capacitance sets the edge rate.
+    pub max_impedance: Option<f64>,
+    /// The maximum current drawn from the driver when high in amps.
+    pub max_current: Option<f64>,
+}
+
+impl<'a> Default for LevelShiftOptions<'a> {
+    fn default() -> Self {
+        LevelShiftOptions {
+            series: &E24,
+            max_impedance: None,
+            max_current: None,
+        }
+    }
+}
+
+/// Designs a resistive level shifter from a driver whose high output lies in `voh` volts and
+/// whose low output is at most `vol` volts, to a receiver with thresholds of `vih` and `vil`
+/// volts and an absolute maximum input of `vmax` volts. R1 runs from the driver to the receiver
+/// and R2 from the receiver to ground. Both logic levels must hold with the resistors at the
+/// ends of their tolerance, and results are ranked by how close the nominal high level sits to
+/// the middle of the window between `vih` and `vmax`.
+/// # Example
+/// ```
+///     # use resistor_calc::*;
+///     let design = templates::level_shift(
+///         (4.5, 5.5),
+///         0.4,
+///         2.0,
+///         0.8,
+///         3.6,
+///         templates::LevelShiftOptions {
+///             max_impedance: Some(2e3),
+///             ..Default::default()
+///         },
+///     ).unwrap();
+///     design.results.print_best();
+/// ```
+pub fn level_shift(
+    voh: (f64, f64),
+    vol: f64,
+    vih: f64,
+    vil: f64,
+    vmax: f64,
+    opts: LevelShiftOptions,
+) -> Option<Design> {
+    let tol = opts.series.tolerance();
+    let out = |v: f64, up: f64, down: f64| {
+        format!("{} * R2 * {d} / (R1 * {} + R2 * {d})", v, up, d = down)
+    };
+    let mut ops = ROpBuilder::new()
+        .bound(&format!(
+            "{} ~ {}",
+            out((voh.0 + voh.1) / 2.0, 1.0, 1.0),
+            (vih + vmax) / 2.0
+        ))
+        .bound(&format!("{} >= {}", out(voh.0, 1.0 + tol, 1.0 - tol), vih))
+        .bound(&format!("{} <= {}", out(voh.1, 1.0 - tol, 1.0 + tol), vmax))
+        .bound(&format!("{} <= {}", out(vol, 1.0 - tol, 1.0 + tol), vil));
+    if let Some(max) = opts.max_impedance {
+        ops = ops.bound(&format!("par(R1, R2) <= {}", max));
+    }
+    if let Some(max) = opts.max_current {
+        ops = ops.bound(&format!("{} / (R1 + R2) <= {}", voh.1, max));
+    }
+    solve(&RCalc::new(vec![opts.series; 2]), ops)
+}
+
+/// Returns the frequency equation of a Schmitt trigger inverter relaxation oscillator, with R1
+/// the timing resistor from output to input and R2 the timing capacitor in farads, for use with
+/// `relaxation`. `vdd` is the supply and `vt_plus` and `vt_minus` are the rising and falling
+/// input thresholds in volts.
+pub fn schmitt_oscillator(vdd: f64, vt_plus: f64, vt_minus: f64) -> String {
+    let ratio = (vt_plus * (vdd - vt_minus)) / (vt_minus * (vdd - vt_plus));
+    format!("1 / (R1 * R2 * {})", ratio.ln())
+}
+
+/// Selects the components of a relaxation oscillator from its frequency equation `frequency`,
+/// an expression of R1, R2, ..., Rn drawn from `series`, where capacitors are given in farads.
+/// Results are ranked by the fractional error from `target_frequency`, plus the fractional error
+/// of the duty cycle expression from its target if `duty` is given. An expression that cannot be
+/// parsed or evaluated is returned as an error, as is a frequency that no combination reaches.
+/// # Example
+/// ```
+///     # use resistor_calc::*;
+///     let design = templates::relaxation(
+///         &templates::schmitt_oscillator(5.0, 2.9, 1.8),
+///         10e3,
+///         None,
+///         vec![&E24, &C_E6],
+///     ).unwrap();
+///     design.results.print_best();
+///     let bad = templates::relaxation("1 / (R1 * R2", 10e3, None, vec![&E24, &C_E6]);
+///     assert!(matches!(bad, Err(Error::Parse(_))));
+/// ```
+pub fn relaxation(
+    frequency: &str,
+    target_frequency: f64,
+    duty: Option<(&str, f64)>,
+    series: Vec<&RSeries>,
+) -> Result<Design, Error> {
+    let mut ops =
+        ROpBuilder::new().try_bound(&format!("({}) / {} ~ 1", frequency, target_frequency))?;
+    if let Some((duty, target)) = duty {
+        ops = ops.try_bound(&format!("({}) / {} ~ 1", duty, target))?;
+    }
+    try_solve(&RCalc::new(series), ops)
+}
+
+/// Options for `bjt_bias`.
+pub struct BiasOptions<'a> {
+    /// The series all four resistors are drawn from.
+    pub series: &'a RSeries,
+    /// The current gain of the transistor.
+    pub beta: f64,
+    /// The base-emitter voltage of the transistor in volts.
+    pub vbe: f64,
+    /// The maximum stability factor, the sensitivity of the collector current to the collector
+    /// leakage current.
+    pub max_stability: f64,
+}
+
+impl<'a> Default for BiasOptions<'a> {
+    fn default() -> Self {
+        BiasOptions {
+            series: &E12,
+            beta: 100.0,
+            vbe: 0.65,
+            max_stability: 10.0,
+        }
+    }
+}
+
+/// Designs a four resistor bias network for an NPN transistor from a supply of `vcc` volts, for
+/// a collector current of `ic` amps and a collector-emitter voltage of `vce` volts. R1 and R2 are
+/// the top and bottom of the base divider, R3 is the collector resistor and R4 the emitter
+/// resistor. Results are ranked by the fractional errors of the collector current and voltage,
+/// and the stability factor must not exceed `max_stability`. With four resistors the search is
+/// large, so a coarse series is a good starting point.
+/// # Example
+/// ```no_run
+///     # use resistor_calc::*;
+///     let design = templates::bjt_bias(
+///         12.0,
+///         1e-3,
+///         6.0,
+///         templates::BiasOptions {
+///             series: &E6,
+///             ..Default::default()
+///         },
+///     ).unwrap();
+///     design.results.print_best();
+/// ```
+pub fn bjt_bias(vcc: f64, ic: f64, vce: f64, opts: BiasOptions) -> Option<Design> {
+    let beta = opts.beta;
+    let ie = format!(
+        "({} * R2 / (R1 + R2) - {}) / (par(R1, R2) / {} + R4)",
+        vcc,
+        opts.vbe,
+        beta + 1.0
+    );
+    let ops = ROpBuilder::new()
+        .bound(&format!("{} * {} / {} ~ 1", beta / (beta + 1.0), ie, ic))
+        .bound(&format!(
+            "({} - {} * {ie} * R3 - {ie} * R4) / {} ~ 1",
+            vcc,
+            beta / (beta + 1.0),
+            vce,
+            ie = ie
+        ))
+        .bound(&format!(
+            "{b} * (1 + par(R1, R2) / R4) / ({b} + par(R1, R2) / R4) <= {}",
+            opts.max_stability,
+            b = beta + 1.0
+        ));
+    solve(&RCalc::new(vec![opts.series; 4]), ops)
+}
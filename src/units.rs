@@ -0,0 +1,63 @@
+//! Typed quantities from the [uom](https://docs.rs/uom) crate for the `units` feature, so that
+//! constants, targets and results carry their units and mixing them up is a compile error.
+//!
+//! Quantities are converted to their SI value (ohms, volts, amps, ...) for use in bounds, which
+//! is the unit the bound expressions and results are in.
+//! # Example
+//! ```
+//!     # extern crate resistor_calc;
+//!     extern crate uom;
+//!     # use resistor_calc::*;
+//!     use uom::si::electric_potential::volt;
+//!     use uom::si::electrical_resistance::kiloohm;
+//!     use uom::si::f64::{ElectricPotential, ElectricalResistance};
+//!
+//!     # fn main() {
+//!     let op = ROpBuilder::new()
+//!         .quantity("VREF", ElectricPotential::new::<volt>(0.8))
+//!         .target("VREF * (1 + R1/R2)", ElectricPotential::new::<volt>(5.0))
+//!         .build();
+//!     let res = RCalc::e24(2).calc(|rs| op.eval(rs)).unwrap();
+//!     let r1: ElectricalResistance = res.iter().next().unwrap().1.resistance(1);
+//!     assert!(r1.get::<kiloohm>() > 0.0);
+//!     # }
+//! ```
+
+extern crate uom;
+
+use self::uom::si::electrical_resistance::ohm;
+use self::uom::si::f64::ElectricalResistance;
+use self::uom::si::{Dimension, Quantity, SI};
+
+use {ROpBuilder, RSet};
+
+impl ROpBuilder {
+    /// Declares a named constant as with `constant`, given as a typed quantity.
+    pub fn quantity<D>(self, name: &str, value: Quantity<D, SI<f64>, f64>) -> Self
+    where
+        D: Dimension + ?Sized,
+    {
+        self.constant(name, value.value)
+    }
+
+    /// Adds a `~` bound drawing `expr` towards a target given as a typed quantity, as with
+    /// `bound("expr ~ target")`.
+    pub fn target<D>(self, expr: &str, target: Quantity<D, SI<f64>, f64>) -> Self
+    where
+        D: Dimension + ?Sized,
+    {
+        self.bound(&format!("{} ~ {}", expr, target.value))
+    }
+}
+
+impl RSet {
+    /// Returns the resistance of the resistor Ridx, as with `r`.
+    pub fn resistance(&self, idx: usize) -> ElectricalResistance {
+        ElectricalResistance::new::<ohm>(self.r(idx))
+    }
+
+    /// Returns the resistances of R1, R2, ..., Rn.
+    pub fn resistances(&self) -> Vec<ElectricalResistance> {
+        (1..=self.values().len()).map(|i| self.resistance(i)).collect()
+    }
+}
@@ -0,0 +1,83 @@
+use std::borrow::Cow;
+
+use {RCalc, RSeries, RSet};
+
+/// The best error achievable with a set of positions upgraded to a finer series, produced by
+/// `RCalc::upgrade_advice`.
+#[derive(Debug, Clone)]
+pub struct Upgrade {
+    /// The positions upgraded, counting from R1. Empty for the original problem.
+    pub positions: Vec<usize>,
+    /// The lowest error achievable, or `None` if no combination satisfies the bounds.
+    pub error: Option<f64>,
+}
+
+/// The decade of `value`, allowing for rounding just below a power of ten.
+fn decade(value: f64) -> i32 {
+    (value * (1.0 + 1e-9)).log10().floor() as i32
+}
+
+/// The values of `finer` within the range and decades of `current`, so that a position narrowed
+/// with `range` or `decades` stays narrowed when upgraded.
+fn narrowed(current: &RSeries, finer: &RSeries) -> RSeries {
+    let (min, max) = current
+        .iter()
+        .fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), &v| {
+            (min.min(v), max.max(v))
+        });
+    let decades: Vec<i32> = current.iter().map(|&v| decade(v)).collect();
+    finer.filtered(|v| (min..=max).contains(&v) && decades.contains(&decade(v)))
+}
+
+impl<'a> RCalc<'a> {
+    fn best_error(&self, f: &(impl Fn(&RSet) -> Option<f64> + Sync)) -> Option<f64> {
+        self.calc(f).map(|res| (res.res[0].0 as f64) / 1e9)
+    }
+
+    /// Reports how the best achievable error changes when the listed `positions` (counting from
+    /// R1) are drawn from the `finer` series instead. The first entry is the original problem,
+    /// followed by each position upgraded on its own and finally all of them together. Each
+    /// entry requires a full calculation, so this takes correspondingly longer than `calc`.
+    ///
+    /// An upgraded position keeps only the values of `finer` within its range and decades, so a
+    /// position narrowed with `range` or `decades` stays narrowed, and a position fixed with `fix`
+    /// is left as it is.
+    /// # Example
+    /// ```
+    ///     # use resistor_calc::*;
+    ///     let f = ROpBuilder::new().bound("0.8 * (1 + R1/R2) ~ 3.3").finish();
+    ///     for upgrade in RCalc::e12(2).upgrade_advice(&f, &[1, 2], &E96) {
+    ///         println!("{:?}: {:?}", upgrade.positions, upgrade.error);
+    ///     }
+    ///     // With R2 fixed, upgrading it changes nothing.
+    ///     let advice = RCalc::e12(2).fix(2, 10e3).upgrade_advice(&f, &[2], &E96);
+    ///     assert_eq!(advice[0].error, advice[1].error);
+    /// ```
+    pub fn upgrade_advice(
+        &self,
+        f: &(impl Fn(&RSet) -> Option<f64> + Sync),
+        positions: &[usize],
+        finer: &'a RSeries,
+    ) -> Vec<Upgrade> {
+        let mut sets: Vec<Vec<usize>> = vec![vec![]];
+        sets.extend(positions.iter().map(|&p| vec![p]));
+        if positions.len() > 1 {
+            sets.push(positions.to_vec());
+        }
+        sets.into_iter()
+            .map(|upgraded| {
+                let mut rcalc = self.clone();
+                for &p in &upgraded {
+                    if rcalc.rs[p - 1].len() > 1 {
+                        rcalc.rs[p - 1] = Cow::Owned(narrowed(&rcalc.rs[p - 1], finer));
+                    }
+                }
+                rcalc.cache.invalidate();
+                Upgrade {
+                    error: rcalc.best_error(f),
+                    positions: upgraded,
+                }
+            })
+            .collect()
+    }
+}
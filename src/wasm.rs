@@ -0,0 +1,56 @@
+//! JavaScript bindings for the `wasm` feature, allowing the optimiser to run in a browser. Problems
+//! and reports are passed as JSON in the same form as `Problem::from_json` and `Report::to_json`.
+//!
+//! ```js
+//! import init, { solve, solveWithProgress } from "./resistor_calc.js";
+//!
+//! await init();
+//! const report = JSON.parse(solveWithProgress(
+//!     JSON.stringify({ series: ["E24", "E24"], bounds: ["R2 / (R1 + R2) ~ 0.25"], top: 5 }),
+//!     (evaluated, total, best) => console.log(`${evaluated} / ${total}`),
+//! ));
+//! ```
+
+extern crate js_sys;
+extern crate wasm_bindgen;
+
+use self::wasm_bindgen::prelude::*;
+
+use {Problem, RSeries};
+
+/// Solves a problem given as JSON, returning the report as JSON.
+#[wasm_bindgen]
+pub fn solve(problem: &str) -> Result<String, JsValue> {
+    solve_with_progress(problem, &JsValue::UNDEFINED.into())
+}
+
+/// As with `solve`, calling `progress(evaluated, total, best)` periodically during the search,
+/// where `best` is the lowest error found so far or `undefined`.
+#[wasm_bindgen(js_name = solveWithProgress)]
+pub fn solve_with_progress(problem: &str, progress: &js_sys::Function) -> Result<String, JsValue> {
+    let problem = Problem::from_json(problem).map_err(|e| JsValue::from_str(e.message()))?;
+    let report = problem
+        .solve_with_progress(|p| {
+            if progress.is_function() {
+                let best = p.best.map_or(JsValue::UNDEFINED, JsValue::from_f64);
+                // Errors thrown by the callback are not allowed to stop the search.
+                let _ = progress.call3(
+                    &JsValue::NULL,
+                    &JsValue::from_f64(p.evaluated as f64),
+                    &JsValue::from_f64(p.total as f64),
+                    &best,
+                );
+            }
+        })
+        .map_err(|e| JsValue::from_str(e.message()))?;
+    Ok(report.to_json())
+}
+
+/// The names of the standard series that problems can draw values from.
+#[wasm_bindgen(js_name = seriesNames)]
+pub fn series_names() -> Vec<JsValue> {
+    RSeries::names()
+        .iter()
+        .map(|&name| JsValue::from_str(name))
+        .collect()
+}
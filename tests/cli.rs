@@ -0,0 +1,118 @@
+//! Runs the command line tool, checking its exit codes and that bad input is reported rather
+//! than panicking.
+
+use std::env;
+use std::fs;
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::{Command, Output, Stdio};
+
+const DIVIDER: &str = "R2 / (R1 + R2) ~ 0.25";
+
+fn run(args: &[&str]) -> Output {
+    run_with_input(args, "")
+}
+
+fn run_with_input(args: &[&str], input: &str) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_resistor-calc"))
+        .args(args)
+        .env("RESISTOR_CALC_PRESETS", temp_dir("presets"))
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(input.as_bytes())
+        .unwrap();
+    child.wait_with_output().unwrap()
+}
+
+fn exit_code(args: &[&str]) -> i32 {
+    run(args).status.code().unwrap()
+}
+
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = env::temp_dir().join(format!("resistor-calc-cli-{}-{}", name, std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+/// Writes a problem file with the given bounds, returning its path.
+fn problem_file(name: &str, bounds: &str) -> String {
+    let path = temp_dir("problems").join(format!("{}.toml", name));
+    let toml = format!("series = [\"E12\", \"E12\"]\nbounds = [\"{}\"]\n", bounds);
+    fs::write(&path, toml).unwrap();
+    path.to_str().unwrap().to_string()
+}
+
+#[test]
+fn solves() {
+    let out = run(&["-b", DIVIDER, "--top", "1"]);
+    assert_eq!(out.status.code(), Some(0));
+    assert!(String::from_utf8_lossy(&out.stdout).contains("Match 1:"));
+}
+
+#[test]
+fn no_solution() {
+    assert_eq!(exit_code(&["-b", "R1 > 1e9"]), 1);
+}
+
+#[test]
+fn parse_errors() {
+    assert_eq!(exit_code(&["-b", "R2 / (R1 + R2) ~"]), 2);
+    assert_eq!(exit_code(&["-b", DIVIDER, "--max-results", "x"]), 2);
+    assert_eq!(exit_code(&["-b", DIVIDER, "--constant", "VIN"]), 2);
+    assert_eq!(exit_code(&["--preset", "missing"]), 2);
+}
+
+#[test]
+fn too_large() {
+    assert_eq!(exit_code(&["-b", DIVIDER, "--max-combinations", "10"]), 5);
+}
+
+#[test]
+fn missing_file() {
+    assert_eq!(exit_code(&["explain", "-v", "1k,3k", "missing.toml"]), 4);
+}
+
+#[test]
+fn json() {
+    let problem = format!(
+        "{{\"series\": [\"E12\", \"E12\"], \"bounds\": [\"{}\"]}}",
+        DIVIDER
+    );
+    let out = run_with_input(&["--json"], &problem);
+    assert_eq!(out.status.code(), Some(0));
+    assert!(String::from_utf8_lossy(&out.stdout).contains("\"results\""));
+    assert_eq!(
+        run_with_input(&["--json"], "{\"bounds\": [").status.code(),
+        Some(2)
+    );
+}
+
+/// Bounds naming a position past the last are only found when they are evaluated, which once
+/// panicked in each of these commands.
+#[test]
+fn invalid_bounds() {
+    let bad = "R3 ~ 1";
+    let problem = problem_file("invalid", bad);
+    let json = format!(
+        "{{\"series\": [\"E12\", \"E12\"], \"bounds\": [\"{}\"]}}",
+        bad
+    );
+    let outputs = vec![
+        run(&["-b", bad]),
+        run(&["analyze", "-b", bad, "--values", "1k,3k"]),
+        run(&["explain", "-v", "1k,3k", &problem]),
+        run(&["estimate", &problem]),
+        run_with_input(&["--json"], &json),
+    ];
+    for out in outputs {
+        assert_eq!(out.status.code(), Some(2));
+        assert!(!String::from_utf8_lossy(&out.stderr).contains("panicked"));
+    }
+}